@@ -1,17 +1,20 @@
 use rand::{Rng, thread_rng};
 use aes_gcm::{aead::{Aead, generic_array::GenericArray, KeyInit}, Aes256Gcm};
-use typenum::{U12, U32, B1, B0, UInt, UTerm};
+use typenum::{U12, U32};
+use zeroize::Zeroize;
+
+use crate::secret::SecretString;
 
 pub fn u12_from_slice(slice: &[u8]) -> GenericArray<u8, U12> {
     //Database will return a Vec<u8>, so use this function to convert the nonce
     let mut default_array: GenericArray<u8, U12> = GenericArray::default();
-    default_array.clone_from_slice(&slice);
+    default_array.clone_from_slice(slice);
     default_array
 }
 
 pub fn u32_from_slice(slice: &[u8]) -> GenericArray<u8, U32> {
     //Converts the key to a GenericArray
-    let mut array: GenericArray<u8, UInt<UInt<UInt<UInt<UInt<UInt<UTerm, B1>, B0>, B0>, B0>, B0>, B0>> = GenericArray::default();
+    let mut array: GenericArray<u8, U32> = GenericArray::default();
     array.copy_from_slice(slice);
     array
 }
@@ -33,14 +36,22 @@ pub fn encrypt_aesgcm(key: &GenericArray<u8, U32>, nonce: &GenericArray<u8, U12>
     ciphertext
 }
 
-pub fn decrypt_aesgcm(key: &GenericArray<u8, U32>, nonce: &GenericArray<u8, U12>, ciphermessage: &Vec<u8>) -> String {
+pub fn decrypt_aesgcm(key: &GenericArray<u8, U32>, nonce: &GenericArray<u8, U12>, ciphermessage: &[u8]) -> Option<SecretString> {
+    // returns None if the ciphertext or its tag is broken
     let cipher = Aes256Gcm::new(&key.clone());
-    let decrypted_text = cipher.decrypt(nonce, ciphermessage.as_slice());
-    match decrypted_text {
-        Ok(decrypted) => String::from_utf8(decrypted).expect("Conversion to String failed"),
+    match cipher.decrypt(nonce, ciphermessage) {
+        Ok(decrypted) => match String::from_utf8(decrypted) {
+            Ok(text) => Some(SecretString::new(text)),
+            Err(error) => {
+                let mut bytes = error.into_bytes();
+                bytes.zeroize();
+                log::warn!("Decrypted entry is not valid UTF-8");
+                None
+            }
+        },
         Err(_) => {
             log::warn!("AES-GCM Decryption failed");
-            String::from("Decryption failed.")
+            None
         },
     }
-}
\ No newline at end of file
+}
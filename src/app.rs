@@ -4,50 +4,120 @@
     widgets::{Block, BorderType, Borders},
 };
 use stateful_list::StatefulList;
-use std::sync::{Arc, Mutex};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
 use self::{
     extras::*,
-    states::{LoginState, LoginStates},
+    states::{EntryEdit, EntryView, LoginState, LoginStates, ReauthAction, ReauthPrompt},
 };
 use crate::{
     app_db_conn::AppDBConnector,
+    audit::{self, AuditAction, AuditRecord},
+    config::{Config, VaultSetting, VaultSettings},
+    doctor::DoctorReport,
+    entry_index::EntryIndex,
     event::handle_events,
-    file_manager::FileManager,
-    key_processor::{derive_key, SecureStorage},
-    password::generate_strong_password,
+    folders::{self, ListRow, Section},
+    generator::{GeneratorOptions, GeneratorPopup, GeneratorTarget},
+    search::{self, Candidate, Query},
+    file_manager::{resolve_vault_path, vault_entry_from_path, BackupFile, FileManager, VaultEntry},
+    history::FieldHistory,
+    key_processor::{derive_key, keys_match, SecureStorage},
+    metadata::{self, Timestamp},
+    redact,
+    secret::SecretString,
+    signals::{self, SignalEvent, SignalHandler},
+    terminal::{resume_terminal, suspend_terminal},
+    trash::TrashItem,
     types::{ClState, Terminal},
     ui::{
         draw_ui,
-        fields::{input_field, password_field},
+        fields::{input_field, password_field, refill_field, wipe_field},
     },
 };
 
 
+mod clipboard_backend;
 mod clipboard_thread;
 pub(crate) mod extras;
-mod stateful_list;
+pub(crate) mod stateful_list;
 pub(crate) mod states;
 
+// seconds after entering the master password in which sensitive entries stay accessible
+const REAUTH_GRACE: u64 = 60;
+// index of the page showing the audit trail
+const AUDIT_PAGE: usize = 4;
+// index of the page showing the trash
+const TRASH_PAGE: usize = 5;
+
 pub struct App<'a> {
     // App handling all states and storage of the application
     pub vault_state: LoginStates,
     pub text_fields: EditableTextFields<'a>,
 
-    pub entries_list: StatefulList<String>,
-    pub current_entry: Option<(String, StatefulList<(String, String, bool)>)>,
+    pub vault_list: StatefulList<VaultEntry>,
+    pub vault_error: Option<String>,
+
+    pub entries_list: StatefulList<ListRow>,
+    entry_names: Vec<String>,
+    collapsed_folders: HashSet<String>,
+    pub index: EntryIndex,
+    pub tag_filter: Option<String>,
+    pub search_matches: HashMap<String, Vec<usize>>,
+    pub current_entry: Option<EntryView>,
+    pub entry_sensitive: bool,
     pub delete_confirm: bool,
+    pub entry_edit: Option<EntryEdit>,
+    pub entry_edit_error: Option<String>,
+    pub field_history: Option<FieldHistory>,
+    pub generator: Option<GeneratorPopup>,
+
+    pub reauth_prompt: Option<ReauthPrompt>,
+    last_reauth: Option<Instant>,
+    last_backup: Option<Instant>,
+    backup_pending: bool,
+    retention_changed: bool,
 
     pub templates: StatefulList<Template>,
     pub current_template: Option<usize>,
     pub insert_success: Option<bool>,
 
+    pub vault_actions: StatefulList<VaultAction>,
+    pub vault_status: Option<Result<String, String>>,
+    pub doctor_report: Option<DoctorReport>,
+
+    pub backups: StatefulList<BackupFile>,
+    pub backup_entries: Option<StatefulList<String>>,
+    backup_db: Option<AppDBConnector>,
+    pub restore_confirm: bool,
+
+    pub vault_settings: VaultSettings,
+    pub settings_list: StatefulList<VaultSetting>,
+    last_input: Instant,
+
+    audit_trail: Vec<AuditRecord>,
+    pub audit_list: StatefulList<AuditRecord>,
+    pub audit_filter: Option<AuditAction>,
+    pub audit_status: Option<Result<String, String>>,
+
+    pub trash_list: StatefulList<TrashItem>,
+    pub trash_status: Option<Result<String, String>>,
+    pub list_status: Option<Result<String, String>>,
+    sequence_entry: Option<String>,
+    pub purge_confirm: bool,
+
     pub page_index: IndexManager,
     pub page_selected: bool,
 
     pub clipboard: clipboard_thread::ClipboardManager,
     clip_copied: ClState,
 
+    pub config: Config,
     pub file_manager: &'a mut FileManager,
     db_manager: AppDBConnector,
     master_key: Option<SecureStorage>,
@@ -55,53 +125,123 @@ pub struct App<'a> {
 }
 
 impl<'a> App<'a> {
-    pub fn new(file_manager: &'a mut FileManager) -> App<'a> {
-        // creates a new with testing values
-        let path = file_manager.create_path().unwrap();
+    pub fn new(file_manager: &'a mut FileManager, config: Config, cli_vault: Option<VaultEntry>) -> App<'a> {
+        // creates a new app with the known vaults, a vault from the command line is shown but not remembered
+        let path = file_manager.vault.path.clone();
         let copied = Arc::new(Mutex::new(SingleValue { value: None }));
 
+        let mut vaults = file_manager.registered_vaults().to_vec();
+        if let Some(entry) = cli_vault.filter(|entry| !vaults.iter().any(|v| v.path == entry.path)) {
+            vaults.push(entry);
+        }
+        let selected = vaults.iter().position(|v| v.path == path).unwrap_or(0);
+
         // return new instance of app
-        App {
-            vault_state: LoginStates::new(file_manager.check_db_exist()),
+        let mut app = App {
+            vault_state: LoginStates::new(file_manager.vault.exists()),
             text_fields: EditableTextFields::new(),
 
+            vault_list: StatefulList::with_items(vaults),
+            vault_error: None,
+
             entries_list: StatefulList::with_items(vec![]),
+            entry_names: vec![],
+            collapsed_folders: HashSet::new(),
+            index: EntryIndex::default(),
+            tag_filter: None,
+            search_matches: HashMap::new(),
             current_entry: None,
+            entry_sensitive: false,
             delete_confirm: false,
+            entry_edit: None,
+            entry_edit_error: None,
+            field_history: None,
+            generator: None,
+
+            reauth_prompt: None,
+            last_reauth: None,
+            last_backup: None,
+            backup_pending: false,
+            retention_changed: false,
 
             templates: StatefulList::with_items(Vec::new()),
             current_template: None,
             insert_success: None,
 
-            page_index: IndexManager::new(3),
+            vault_actions: StatefulList::with_items(VaultAction::all()),
+            vault_status: None,
+            doctor_report: None,
+
+            backups: StatefulList::with_items(vec![]),
+            backup_entries: None,
+            backup_db: None,
+            restore_confirm: false,
+
+            vault_settings: VaultSettings::default(),
+            settings_list: StatefulList::with_items(VaultSetting::all()),
+            last_input: Instant::now(),
+
+            audit_trail: vec![],
+            audit_list: StatefulList::with_items(vec![]),
+            audit_filter: None,
+            audit_status: None,
+
+            trash_list: StatefulList::with_items(vec![]),
+            trash_status: None,
+            list_status: None,
+            sequence_entry: None,
+            purge_confirm: false,
+
+            page_index: IndexManager::new(6),
             page_selected: false,
 
-            clipboard: clipboard_thread::ClipboardManager::new(Arc::clone(&copied)),
+            clipboard: clipboard_thread::ClipboardManager::new(
+                clipboard_backend::create(config.clipboard_backend),
+                Arc::clone(&copied),
+            ),
             clip_copied: copied,
+            config,
             file_manager,
 
             db_manager: AppDBConnector::new(path),
             master_key: None,
             login_count: 0,
-        }
+        };
+
+        app.vault_list.set_index(selected);
+        app.open_selected_vault();
+        app
     }
 
-    pub fn run(mut self, terminal: &mut Terminal) -> crate::Result<()> {
+    pub fn run(mut self, terminal: &mut Terminal, signals: &SignalHandler) -> crate::Result<()> {
         // runs application forever until exited. Draws to the screen and handles events
         log::info!("Starting application view");
 
         loop {
+            match signals.poll() {
+                Some(SignalEvent::Terminate) => {
+                    log::warn!("Received termination signal");
+                    self.shutdown();
+                    return Ok(());
+                }
+                Some(SignalEvent::Suspend) => self.suspend(terminal)?,
+                None => {}
+            }
+
             if handle_events(&mut self)?.is_break() {
+                self.shutdown();
                 return Ok(());
             }
+            self.check_auto_lock();
 
+            let _output = clipboard_backend::TERMINAL_OUTPUT.lock().unwrap_or_else(PoisonError::into_inner);
             terminal.draw(|f| draw_ui(f, &mut self))?;
         }
     }
 
     pub fn display_entry(&mut self) {
         // displays a selected entry in ui
-        if let Some(item) = self.entries_list.current_item() {
+        if let Some(item) = self.selected_entry().cloned() {
             log::info!("Loading new entry to display");
 
             // get data from database
@@ -109,29 +249,603 @@ pub fn display_entry(&mut self) {
                 item.clone(),
                 self.master_key.as_mut().unwrap().get_contents(),
             );
+            self.entry_sensitive = self.db_manager.is_sensitive(item.clone());
+            self.db_manager.record(AuditAction::Viewed, Some(&item), None);
 
             self.set_copied_state(None);
 
             // create list for ui renderer to interpret
-            let template = self.templates
+            let Some(template) = self.templates
                 .items
                 .iter()
                 .find(|t| t.db_name == template_name)
-                .unwrap();
+            else {
+                log::error!("Template of entry could not be loaded");
+                return;
+            };
+
+            self.current_entry = Some(EntryView {
+                name: item,
+                template: template.name.clone(),
+                fields: StatefulList::with_items(template
+                    .elements[1..]
+                    .iter()
+                    .zip(elements)
+                    .map(|(temp, elem)| {
+                        (temp.name.clone(), elem.1, temp.private)
+                    })
+                    .chain(std::iter::once((String::new(), SecretString::default(), false)))
+                    .collect()
+                ),
+            });
+        }
+    }
 
-            self.current_entry = Some((template.name.clone(), StatefulList::with_items(template
-                .elements[1..]
-                .iter()
-                .zip(elements)
-                .map(|(temp, elem)| {
-                    (temp.name.clone(), elem.1.clone(), temp.private)
-                })
-                .chain(std::iter::once((String::new(), String::new(), false)))
-                .collect()
-            )));
+    fn displayed_entry(&self) -> String {
+        // name of the entry shown on the right side, changes are written to it and not to the highlighted row
+        self.current_entry.as_ref().unwrap().name.clone()
+    }
+
+    pub fn close_stale_entry(&mut self) {
+        // closes the displayed entry once another row gets highlighted
+        if self.current_entry.as_ref().is_some_and(|entry| Some(&entry.name) != self.selected_entry()) {
+            self.current_entry = None;
+        }
+    }
+
+    pub fn current_entry_template(&self) -> Option<&Template> {
+        // returns the template of the displayed entry
+        let template_name = &self.current_entry.as_ref()?.template;
+        self.templates.items.iter().find(|t| &t.name == template_name)
+    }
+
+    pub fn is_entry_protected(&self) -> bool {
+        // returns weather the displayed entry needs the master password to reveal
+        self.entry_sensitive
+            || self
+                .current_entry_template()
+                .is_some_and(|t| t.reauthenticate)
+    }
+
+    fn is_private_field(&self, index: usize) -> bool {
+        // checks the template if a field of the displayed entry is private
+        self.current_entry_template()
+            .and_then(|t| t.elements.get(index + 1))
+            .is_some_and(|e| e.private)
+    }
+
+    pub fn toggle_field_visibility(&mut self) {
+        // hides or reveals the focused field of the displayed entry
+        let index = self.current_entry.as_ref().unwrap().fields.current_index().unwrap();
+        let hidden = self.current_entry.as_ref().unwrap().fields.items[index].2;
+
+        if hidden && self.is_private_field(index) {
+            self.request_action(ReauthAction::Reveal(index));
+        } else {
+            self.current_entry.as_mut().unwrap().fields.items[index].2 = !hidden;
+        }
+    }
+
+    pub fn copy_current_field(&mut self) {
+        // copies the focused field of the displayed entry
+        let entries = &self.current_entry.as_ref().unwrap().fields;
+        let index = entries.current_index().unwrap();
+
+        if index != entries.items.len() - 1 {
+            if self.is_private_field(index) {
+                self.request_action(ReauthAction::Copy(index));
+            } else {
+                self.run_action(ReauthAction::Copy(index));
+            }
+        }
+    }
+
+    pub fn edit_current_field(&mut self) {
+        // opens the input for a new value of the focused field
+        let entries = &self.current_entry.as_ref().unwrap().fields;
+        let index = entries.current_index().unwrap();
+
+        if index != entries.items.len() - 1 {
+            if self.is_private_field(index) {
+                self.request_action(ReauthAction::Edit(index));
+            } else {
+                self.run_action(ReauthAction::Edit(index));
+            }
+        }
+    }
+
+    pub fn show_field_history(&mut self) {
+        // opens the previous values of the focused field, only private fields keep them
+        let entries = &self.current_entry.as_ref().unwrap().fields;
+        let index = entries.current_index().unwrap();
+
+        if index != entries.items.len() - 1 && self.is_private_field(index) {
+            self.request_action(ReauthAction::History(index));
         }
     }
 
+    pub fn toggle_sensitive(&mut self) {
+        // marks the displayed entry as sensitive, removing the mark needs the password
+        if self.entry_sensitive {
+            self.request_action(ReauthAction::ToggleSensitive);
+        } else {
+            self.run_action(ReauthAction::ToggleSensitive);
+        }
+    }
+
+    pub fn toggle_template_reauthenticate(&mut self) {
+        // makes entries of the highlighted template require the master password, removing it needs the password
+        let Some(index) = self.templates.current_index() else {
+            return;
+        };
+        if self.templates.items[index].reauthenticate && !self.recently_authenticated() {
+            self.prompt_password(ReauthAction::ToggleTemplate(index));
+        } else {
+            self.run_action(ReauthAction::ToggleTemplate(index));
+        }
+    }
+
+    fn set_template_reauthenticate(&mut self, index: usize) {
+        // stores the changed protection of a template
+        let template = &self.templates.items[index];
+        let reauthenticate = !template.reauthenticate;
+        if let Err(error) = self.db_manager.set_template_reauthenticate(template, reauthenticate) {
+            log::error!("Failed to change the template: {error}");
+            return;
+        }
+
+        let detail = match reauthenticate {
+            true => format!("{} entries require the master password", template.name),
+            false => format!("{} entries no longer require the master password", template.name),
+        };
+        self.db_manager.record(AuditAction::Edited, None, Some(&detail));
+        self.templates.items[index].reauthenticate = reauthenticate;
+    }
+
+    fn request_action(&mut self, action: ReauthAction) {
+        // runs an action directly or asks for the master password first
+        if self.is_entry_protected() && !self.recently_authenticated() {
+            self.prompt_password(action);
+        } else {
+            self.run_action(action);
+        }
+    }
+
+    fn recently_authenticated(&self) -> bool {
+        // the master password was entered again within the grace period
+        self.last_reauth.is_some_and(|t| t.elapsed() < Duration::from_secs(REAUTH_GRACE))
+    }
+
+    fn prompt_password(&mut self, action: ReauthAction) {
+        // opens the password prompt, the action runs after the password was entered
+        log::info!("Requesting master password for sensitive entry");
+        wipe_field(&mut self.text_fields.reauth_input, password_field());
+        self.reauth_prompt = Some(ReauthPrompt { action, failed: false });
+    }
+
+    pub fn quick_copy(&mut self, role: FieldRole) {
+        // copies the username or password of the highlighted entry without opening it
+        self.copy_from_list(ReauthAction::QuickCopy(role));
+    }
+
+    pub fn copy_sequence(&mut self) {
+        // copies the username of the highlighted entry, pressing the key again copies its password
+        let running = self.sequence_entry.is_some() && self.sequence_entry.as_ref() == self.selected_entry();
+        if running && self.clipboard.next_in_sequence().is_some() {
+            let name = self.sequence_entry.clone().unwrap();
+            if let Some(field) = self.clipboard.copy_next() {
+                self.copied_from_list(&name, &field);
+                self.reindex_entry(&name);
+            }
+        } else {
+            self.copy_from_list(ReauthAction::CopySequence);
+        }
+    }
+
+    fn copy_from_list(&mut self, action: ReauthAction) {
+        // copies fields of the highlighted entry, private fields of protected entries need the master password
+        let Some(name) = self.selected_entry().cloned() else {
+            return;
+        };
+        let Some(template) = self.entry_template(&name) else {
+            log::error!("Template of entry could not be loaded");
+            return;
+        };
+
+        let indices: Vec<usize> = action.roles().iter().filter_map(|role| template.field_with_role(*role)).collect();
+        if indices.is_empty() {
+            let roles: Vec<&str> = action.roles().iter().map(FieldRole::name).collect();
+            self.list_status = Some(Err(format!("{} entries have no {} field", template.name, roles.join(" or "))));
+            return;
+        }
+
+        let private = indices.iter().any(|index| template.elements[index + 1].private);
+        let protected = template.reauthenticate || self.db_manager.is_sensitive(name);
+        if private && protected && !self.recently_authenticated() {
+            self.prompt_password(action);
+        } else {
+            self.run_action(action);
+        }
+    }
+
+    fn read_role_fields(&mut self, name: &str, roles: &[FieldRole]) -> Vec<(String, SecretString, Duration)> {
+        // names, values and clipboard timeouts of the fields of an entry with the given roles, in the order of the roles
+        let Some(template) = self.entry_template(name) else {
+            return vec![];
+        };
+        let fields: Vec<(usize, String, Duration)> = roles
+            .iter()
+            .filter_map(|role| template.field_with_role(*role))
+            .map(|index| {
+                let field = template.elements[index + 1].name.clone();
+                let timeout = self.config.clipboard_timeout(&template.name, &field);
+                (index, field, timeout)
+            })
+            .collect();
+
+        let (_, values) = self.db_manager.get_entry(name.to_string(), self.master_key.as_mut().unwrap().get_contents());
+        fields
+            .into_iter()
+            .filter_map(|(index, field, timeout)| Some((field, values.get(index)?.1.clone(), timeout)))
+            .collect()
+    }
+
+    fn copied_from_list(&mut self, name: &str, field: &str) {
+        // confirms a copy from the entries list below it and records it, the opened entry doesn't show it as copied
+        self.set_copied_state(None);
+        self.list_status = Some(Ok(match self.clipboard.next_in_sequence() {
+            Some(next) => format!(
+                "Copied {field} of {name}, press Ctrl+{} after pasting for {next}",
+                self.config.keys.copy_sequence.to_ascii_uppercase(),
+            ),
+            None => format!("Copied {field} of {name}"),
+        }));
+        self.db_manager.record(AuditAction::Copied, Some(name), Some(field));
+        self.db_manager.touch_entry(name, Timestamp::Copied);
+    }
+
+    fn entry_template(&self, name: &str) -> Option<&Template> {
+        // template of an entry of the list by the name kept in the index
+        let template = self.index.template(name)?;
+        self.templates.items.iter().find(|t| t.name == template)
+    }
+
+    fn run_action(&mut self, action: ReauthAction) {
+        // executes an authorized action on the displayed entry, copies from the list use the highlighted one
+        let name = match action {
+            ReauthAction::ToggleTemplate(index) => return self.set_template_reauthenticate(index),
+            ReauthAction::QuickCopy(_) | ReauthAction::CopySequence => self.selected_entry().unwrap().clone(),
+            _ => self.displayed_entry(),
+        };
+
+        match action {
+            ReauthAction::Reveal(index) => {
+                let field = &mut self.current_entry.as_mut().unwrap().fields.items[index];
+                field.2 = false;
+                self.db_manager.record(AuditAction::Revealed, Some(&name), Some(&field.0));
+                self.db_manager.touch_entry(&name, Timestamp::Revealed);
+            }
+            ReauthAction::Copy(index) => {
+                let (field, text, _) = self.current_entry.as_ref().unwrap().fields.items[index].clone();
+                self.copy_to_clipboard(text);
+                self.db_manager.record(AuditAction::Copied, Some(&name), Some(&field));
+                self.db_manager.touch_entry(&name, Timestamp::Copied);
+            }
+            ReauthAction::Edit(index) => {
+                // private values are not shown again, public ones can be changed in place
+                let (_, text, _) = &self.current_entry.as_ref().unwrap().fields.items[index];
+                let mut field = if self.is_private_field(index) { password_field() } else { input_field() };
+                if !self.is_private_field(index) {
+                    field.insert_str(text.as_str());
+                }
+
+                wipe_field(&mut self.text_fields.field_input, field);
+                self.entry_edit = Some(EntryEdit::Field(index));
+                self.entry_edit_error = None;
+            }
+            ReauthAction::History(index) => {
+                let field = self.current_entry.as_ref().unwrap().fields.items[index].0.clone();
+                let key = self.master_key.as_mut().unwrap().get_contents();
+
+                match self.db_manager.get_field_history(&name, index, &key) {
+                    Ok(values) => self.field_history = Some(FieldHistory::new(index, field, values)),
+                    Err(error) => log::error!("Failed to read the field history: {error}"),
+                }
+            }
+            ReauthAction::ToggleSensitive => {
+                self.entry_sensitive = !self.entry_sensitive;
+                self.db_manager.set_sensitive(name.clone(), self.entry_sensitive);
+
+                let detail = if self.entry_sensitive { "marked as sensitive" } else { "unmarked as sensitive" };
+                self.db_manager.record(AuditAction::Edited, Some(&name), Some(detail));
+                self.db_manager.touch_entry(&name, Timestamp::Modified);
+                self.backup_vault();
+            }
+            ReauthAction::ToggleTemplate(_) => {}
+            ReauthAction::QuickCopy(_) | ReauthAction::CopySequence => {
+                let fields = self.read_role_fields(&name, action.roles());
+                let copied = if action == ReauthAction::CopySequence {
+                    self.sequence_entry = Some(name.clone());
+                    self.clipboard.copy_sequence(fields)
+                } else {
+                    fields.into_iter().next().map(|(field, text, timeout)| {
+                        self.clipboard.copy_to_clipboard(&text, timeout);
+                        field
+                    })
+                };
+
+                match copied {
+                    Some(field) => self.copied_from_list(&name, &field),
+                    None => log::error!("Field of entry could not be loaded"),
+                }
+            }
+        }
+
+        // the list keeps its order until it gets updated the next time
+        self.reindex_entry(&name);
+    }
+
+    pub fn confirm_reauth(&mut self) {
+        // verifies the entered password against the stored key
+        let entered = derive_key(
+            self.text_fields.reauth_input.lines()[0].as_str(),
+            &self.file_manager.vault.get_salt().unwrap(),
+        );
+        wipe_field(&mut self.text_fields.reauth_input, password_field());
+
+        let stored = self.master_key.as_mut().unwrap().get_contents();
+
+        if keys_match(&entered, &stored) {
+            log::info!("Re-authentication successful");
+            self.last_reauth = Some(Instant::now());
+
+            if let Some(prompt) = self.reauth_prompt.take() {
+                self.run_action(prompt.action);
+            }
+        } else {
+            log::warn!("Re-authentication failed");
+            if let Some(prompt) = self.reauth_prompt.as_mut() {
+                prompt.failed = true;
+            }
+        }
+    }
+
+    pub fn cancel_reauth(&mut self) {
+        // closes the password prompt without running its action
+        wipe_field(&mut self.text_fields.reauth_input, password_field());
+        self.reauth_prompt = None;
+    }
+
+    pub fn toggle_field_input_mask(&mut self) {
+        // hides or shows the new value of a private field while typing
+        if !matches!(self.entry_edit, Some(EntryEdit::Field(index)) if self.is_private_field(index)) {
+            return;
+        }
+
+        let field = &mut self.text_fields.field_input;
+        if field.mask_char().is_none() {
+            field.set_mask_char('\u{2022}');
+        } else {
+            field.clear_mask_char();
+        }
+    }
+
+    pub fn edit_entry_folder(&mut self) {
+        // opens the input for the folder of the displayed entry
+        let name = &self.displayed_entry();
+        let mut field = input_field();
+        field.insert_str(folders::folder(name));
+
+        self.text_fields.field_input = field;
+        self.entry_edit = Some(EntryEdit::Folder);
+        self.entry_edit_error = None;
+    }
+
+    pub fn edit_entry_tags(&mut self) {
+        // opens the input for the tags of the displayed entry
+        let name = &self.displayed_entry();
+        let mut field = input_field();
+        field.insert_str(self.index.tags(name).join(", "));
+
+        self.text_fields.field_input = field;
+        self.entry_edit = Some(EntryEdit::Tags);
+        self.entry_edit_error = None;
+    }
+
+    pub fn save_entry_edit(&mut self) {
+        // stores the value of the open input
+        match self.entry_edit {
+            Some(EntryEdit::Field(index)) => self.save_field(index),
+            Some(EntryEdit::Folder) => self.save_folder(),
+            Some(EntryEdit::Tags) => self.save_tags(),
+            None => {}
+        }
+    }
+
+    fn save_folder(&mut self) {
+        // moves the displayed entry into the entered folder, created on the fly
+        let name = self.displayed_entry();
+        let new_name = folders::join(
+            &folders::normalize_folder(&self.text_fields.field_input.lines()[0]),
+            folders::leaf(&name),
+        );
+        if new_name == name {
+            self.cancel_entry_edit();
+            return;
+        }
+
+        if let Err(error) = self.db_manager.rename_entry(&name, &new_name) {
+            log::error!("Failed to move entry: {error}");
+            self.entry_edit_error = Some(error);
+            return;
+        }
+
+        let detail = match folders::folder(&name) {
+            "" => "moved from the top level".to_string(),
+            folder => format!("moved from {folder}"),
+        };
+        self.db_manager.record(AuditAction::Edited, Some(&new_name), Some(&detail));
+        self.db_manager.touch_entry(&new_name, Timestamp::Modified);
+        self.index.remove(&name);
+        self.reindex_entry(&new_name);
+        self.current_entry.as_mut().unwrap().name = new_name.clone();
+        self.cancel_entry_edit();
+
+        // keep the moved entry selected and its folder open
+        let mut folder = folders::folder(&new_name);
+        while !folder.is_empty() {
+            self.collapsed_folders.remove(folder);
+            folder = folders::folder(folder);
+        }
+        self.update_entries();
+        self.select_row(|row| matches!(row, ListRow::Entry { name, .. } if *name == new_name));
+    }
+
+    fn save_tags(&mut self) {
+        // replaces the tags of the displayed entry
+        let name = self.displayed_entry();
+        let tags = folders::parse_tags(&self.text_fields.field_input.lines()[0]);
+
+        if let Err(error) = self.db_manager.set_tags(&name, &tags) {
+            log::error!("Failed to change tags: {error}");
+            self.entry_edit_error = Some(error);
+            return;
+        }
+
+        let detail = match tags.is_empty() {
+            true => "removed tags".to_string(),
+            false => format!("tagged {}", tags.join(", ")),
+        };
+        self.db_manager.record(AuditAction::Edited, Some(&name), Some(&detail));
+        self.db_manager.touch_entry(&name, Timestamp::Modified);
+        self.reindex_entry(&name);
+        self.cancel_entry_edit();
+    }
+
+    fn save_field(&mut self, index: usize) {
+        // stores the entered value, an empty private field gets a random password first
+        let private = self.is_private_field(index);
+
+        if private && self.text_fields.field_input.is_empty() {
+            if let Some(password) = self.generate_password(GeneratorTarget::EntryEdit) {
+                self.text_fields.field_input.insert_str(password.as_str());
+            }
+            return;
+        }
+
+        let value = SecretString::new(self.text_fields.field_input.lines()[0].clone());
+        let name = self.displayed_entry();
+        let (field, current, _) = &self.current_entry.as_ref().unwrap().fields.items[index];
+        if value.as_str() == current.as_str() {
+            self.cancel_entry_edit();
+            return;
+        }
+        let field = field.clone();
+
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        if let Err(error) = self.db_manager.update_field(&name, index, &value, &key, private) {
+            log::error!("Failed to change field: {error}");
+            self.entry_edit_error = Some(error);
+            return;
+        }
+
+        self.current_entry.as_mut().unwrap().fields.items[index].1 = value;
+        self.db_manager.record(AuditAction::Edited, Some(&name), Some(&field));
+        self.db_manager.touch_entry(&name, Timestamp::Modified);
+        self.db_manager.prune_history(&self.vault_settings);
+        self.reindex_entry(&name);
+        self.cancel_entry_edit();
+        self.backup_vault();
+    }
+
+    pub fn cancel_entry_edit(&mut self) {
+        // closes the input and overwrites what was typed
+        wipe_field(&mut self.text_fields.field_input, input_field());
+        self.entry_edit = None;
+        self.entry_edit_error = None;
+    }
+
+    pub fn toggle_history_visibility(&mut self) {
+        // hides or reveals the selected previous value
+        let name = self.displayed_entry();
+        let Some(history) = self.field_history.as_mut() else {
+            return;
+        };
+        let Some((_, hidden)) = history.values.current_item_mut() else {
+            return;
+        };
+
+        *hidden = !*hidden;
+        if !*hidden {
+            let detail = format!("{} (previous value)", history.field);
+            self.db_manager.record(AuditAction::Revealed, Some(&name), Some(&detail));
+        }
+    }
+
+    pub fn copy_history_value(&mut self) {
+        // copies the selected previous value
+        let name = self.displayed_entry();
+        let Some(history) = self.field_history.as_mut() else {
+            return;
+        };
+        let Some((value, _)) = history.values.current_item() else {
+            return;
+        };
+
+        let text = SecretString::from(value.value.as_str());
+        let detail = format!("{} (previous value)", history.field);
+        history.status = Some(Ok("Copied to clipboard!".to_string()));
+
+        self.copy_to_clipboard(text);
+        self.db_manager.record(AuditAction::Copied, Some(&name), Some(&detail));
+        self.db_manager.touch_entry(&name, Timestamp::Copied);
+        self.reindex_entry(&name);
+    }
+
+    pub fn restore_history_value(&mut self) {
+        // puts the selected previous value back, the current value becomes the newest previous one
+        let name = self.displayed_entry();
+        let Some(history) = self.field_history.as_mut() else {
+            return;
+        };
+        let Some((value, _)) = history.values.current_item() else {
+            return;
+        };
+        let (index, field, restored) = (history.index, history.field.clone(), SecretString::from(value.value.as_str()));
+
+        if let Err(error) = self.db_manager.restore_field_value(value.id) {
+            log::error!("Failed to restore previous value: {error}");
+            history.status = Some(Err(error));
+            return;
+        }
+
+        self.current_entry.as_mut().unwrap().fields.items[index].1 = restored;
+        self.db_manager.record(AuditAction::Edited, Some(&name), Some(&format!("{field} restored from history")));
+        self.db_manager.touch_entry(&name, Timestamp::Modified);
+        self.db_manager.prune_history(&self.vault_settings);
+        self.reindex_entry(&name);
+        self.backup_vault();
+
+        // show the history again with the replaced value on top
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        self.field_history = match self.db_manager.get_field_history(&name, index, &key) {
+            Ok(values) => {
+                let mut history = FieldHistory::new(index, field, values);
+                history.status = Some(Ok("Restored, the replaced value is listed first".to_string()));
+                Some(history)
+            }
+            Err(error) => {
+                log::error!("Failed to read the field history: {error}");
+                None
+            }
+        };
+    }
+
+    pub fn close_field_history(&mut self) {
+        // closes the list of previous values
+        self.field_history = None;
+    }
+
     pub fn select_entry(&mut self) {
         // push right side of entries page to focus
         if self.current_entry.is_some() {
@@ -149,6 +863,7 @@ pub fn reset_input_fields(&mut self) {
             .items
             .get(self.current_template.unwrap())
             .unwrap();
+        self.text_fields.wipe_edit_fields();
         self.text_fields.edit_fields = Some(StatefulList::with_items(
             vec![input_field(); template.elements.len() + 1])
         );
@@ -165,7 +880,10 @@ pub fn reset_input_fields(&mut self) {
         {
             let mut placeholder = "Enter or paste credential".to_string();
             if temp.private {
-                placeholder.push_str("\nPress Enter to generate secure password");
+                placeholder.push_str(&format!(
+                    "\nPress Enter to generate secure password, Ctrl+{} for options",
+                    self.config.keys.generator.to_ascii_uppercase(),
+                ));
                 field.set_mask_char('\u{2022}');
             }
 
@@ -220,9 +938,76 @@ pub fn select_template(&mut self) {
     }
 
     pub fn fill_random_password(&mut self, i: usize) {
-        // fills selected field with a random password
-        let field = &mut self.text_fields.edit_fields.as_mut().unwrap().items[i];
-        field.insert_str(generate_strong_password(24));
+        // fills selected field with a random password, generated with the options last used on the field
+        if let Some(password) = self.generate_password(GeneratorTarget::NewEntry(i)) {
+            let field = &mut self.text_fields.edit_fields.as_mut().unwrap().items[i];
+            field.insert_str(password.as_str());
+        }
+    }
+
+    fn generator_field(&self, target: GeneratorTarget) -> Option<(String, usize, String)> {
+        // template, element and name of the private field a password gets generated for
+        let (template, element) = match target {
+            GeneratorTarget::NewEntry(index) => (self.templates.items.get(self.current_template?)?, index),
+            GeneratorTarget::EntryEdit => match self.entry_edit {
+                Some(EntryEdit::Field(index)) => (self.current_entry_template()?, index + 1),
+                _ => return None,
+            },
+        };
+        let field = template.elements.get(element).filter(|element| element.private)?;
+        Some((template.db_name.clone(), element, field.name.clone()))
+    }
+
+    fn generator_options(&self, template: &str, element: usize) -> GeneratorOptions {
+        // options last used on the field, else every kind of characters in the configured length
+        self.db_manager
+            .get_generator_options(template, element)
+            .unwrap_or_else(|| GeneratorOptions::new(self.config.password_length, self.vault_settings.password_symbols))
+    }
+
+    fn generate_password(&self, target: GeneratorTarget) -> Option<SecretString> {
+        // password for a private field without opening the generator, never shorter than the vault allows
+        let (template, element, _) = self.generator_field(target)?;
+        let mut options = self.generator_options(&template, element);
+        options.length = self.vault_settings.password_length(options.length);
+
+        match options.generate() {
+            Ok(password) => Some(password),
+            Err(error) => {
+                log::error!("Failed to generate password: {error}");
+                None
+            }
+        }
+    }
+
+    pub fn open_generator(&mut self, target: GeneratorTarget) {
+        // opens the generator on a private field with the options last used on it
+        let Some(field) = self.generator_field(target) else {
+            return;
+        };
+        let options = self.generator_options(&field.0, field.1);
+        self.generator = Some(GeneratorPopup::new(target, field, options, self.vault_settings.min_password_length));
+    }
+
+    pub fn accept_generator(&mut self) {
+        // writes the previewed password into the field and remembers the options for the field
+        let Some(generator) = self.generator.take_if(|generator| generator.preview.is_ok()) else {
+            return;
+        };
+        let Ok(password) = &generator.preview else {
+            return;
+        };
+
+        let field = match generator.target {
+            GeneratorTarget::NewEntry(index) => &mut self.text_fields.edit_fields.as_mut().unwrap().items[index],
+            GeneratorTarget::EntryEdit => &mut self.text_fields.field_input,
+        };
+        refill_field(field, password);
+
+        if let Err(error) = self.db_manager.set_generator_options(&generator.template, generator.element, &generator.options) {
+            log::error!("Failed to store generator options: {error}");
+        }
+        log::info!("Generated password with the generator");
     }
 
     pub fn unselect_right(&mut self) {
@@ -231,18 +1016,198 @@ pub fn unselect_right(&mut self) {
     }
 
     pub fn update_entries(&mut self) {
-        // updates the currently cached names according to the set filter if set
-        let filter = self.text_fields.search_bar.lines()[0].as_str();
+        // filters the indexed entries by the search and tag if set, best matches first; the vault isn't read
+        let query = Query::parse(&self.text_fields.search_bar.lines()[0]);
+        if self.tag_filter.as_ref().is_some_and(|tag| !self.index.all_tags().contains(&tag)) {
+            self.tag_filter = None;
+        }
 
-        self.entries_list.set_items({
-            let mut entries = self.db_manager.get_entry_names(filter)
-                .iter()
-                .map(|s| s.clone())
-                .collect::<Vec<String>>();
+        self.index.sort(self.vault_settings.entry_sort);
+        let mut matches = vec![];
+        for name in self.index.names() {
+            let entry = self.index.get(name).unwrap();
+            if self.tag_filter.as_ref().is_some_and(|tag| !entry.tags.contains(tag)) {
+                continue;
+            }
+
+            let candidate = Candidate {
+                name,
+                template: &entry.template,
+                tags: &entry.tags,
+                fields: &entry.fields,
+            };
+            if let Some(found) = search::match_entry(&query, &candidate) {
+                matches.push((name.clone(), found));
+            }
+        }
+
+        // the chosen order decides between equally good matches
+        matches.sort_by_key(|(_, found)| Reverse(found.score));
+
+        self.entry_names = matches.iter().map(|(name, _)| name.clone()).collect();
+        self.search_matches = matches
+            .into_iter()
+            .filter(|(_, found)| !found.positions.is_empty())
+            .map(|(name, found)| (name, found.positions))
+            .collect();
+        self.entries_list.set_items(self.build_rows());
+    }
+
+    fn reload_index(&mut self) {
+        // reads all entries into the index, listing and searching don't read the vault afterwards
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        self.index = self.db_manager.load_index(&self.templates.items, &key);
+    }
+
+    fn reindex_entry(&mut self, name: &str) {
+        // reads a changed entry into the index again, it gets removed if it doesn't exist anymore
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        match self.db_manager.load_index_entry(name, &self.templates.items, &key) {
+            Some(entry) => self.index.insert(name.to_string(), entry),
+            None => self.index.remove(name),
+        }
+    }
 
-            entries.sort_by(|a, b| a.cmp(&b));
-            entries
-        })
+    fn is_filtering(&self) -> bool {
+        // a search or tag limits the shown entries
+        !self.text_fields.search_bar.is_empty() || self.tag_filter.is_some()
+    }
+
+    fn build_rows(&self) -> Vec<ListRow> {
+        // tree of the cached names below favorites and recent entries, search results are ranked without folders
+        if !Query::parse(&self.text_fields.search_bar.lines()[0]).is_empty() {
+            return folders::build_results(&self.entry_names);
+        }
+
+        let filtering = self.is_filtering();
+        let tree = if self.vault_settings.group_by_template {
+            folders::build_groups(&self.template_groups(), &self.collapsed_folders, filtering)
+        } else {
+            folders::build_rows(&self.entry_names, &self.collapsed_folders, filtering)
+        };
+        if filtering {
+            return tree;
+        }
+
+        let favorites: Vec<String> = self.entry_names.iter().filter(|name| self.index.is_favorite(name)).cloned().collect();
+        let recent = metadata::recent(&self.entry_names, |name| self.index.metadata(name), folders::RECENT_COUNT);
+        folders::build_sections(&favorites, &recent, self.entry_names.len(), tree)
+    }
+
+    fn template_groups(&self) -> Vec<(String, Vec<String>)> {
+        // the cached names by the name of their template, templates sorted by name and entries in list order
+        let mut groups: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for name in self.entry_names.iter() {
+            let template = self.index.template(name).unwrap_or_default();
+            groups.entry(template).or_default().push(name.clone());
+        }
+        groups.into_iter().map(|(template, names)| (template.to_string(), names)).collect()
+    }
+
+    fn select_row(&mut self, predicate: impl Fn(&ListRow) -> bool) {
+        // moves the selection to the first matching row if there is one
+        if let Some(index) = self.entries_list.items.iter().position(predicate) {
+            self.entries_list.set_index(index);
+        }
+    }
+
+    pub fn selected_entry(&self) -> Option<&String> {
+        // name of the selected entry, None if a folder is selected
+        self.entries_list.current_item().and_then(ListRow::entry)
+    }
+
+    pub fn change_entry_sort(&mut self) {
+        // switches to the next order of the entries list and keeps the selected row
+        self.vault_settings.entry_sort = self.vault_settings.entry_sort.next();
+        self.store_list_view();
+        self.refresh_entries();
+    }
+
+    pub fn toggle_template_groups(&mut self) {
+        // groups the entries list by template or shows it as one tree again, the selected row is kept
+        self.vault_settings.group_by_template = !self.vault_settings.group_by_template;
+        self.store_list_view();
+        self.refresh_entries();
+    }
+
+    fn store_list_view(&mut self) {
+        // remembers order and grouping of the list in the vault, the list still changes if that fails
+        if let Err(error) = self.db_manager.set_vault_settings(&self.vault_settings) {
+            log::error!("Failed to store the list view: {error}");
+        }
+    }
+
+    pub fn change_tag_filter(&mut self) {
+        // shows only entries with the next tag, after the last one all entries are shown again
+        let tags = self.index.all_tags();
+
+        let position = self.tag_filter.as_ref().and_then(|f| tags.iter().position(|t| *t == f));
+        self.tag_filter = match position {
+            None => tags.first().map(|t| t.to_string()),
+            Some(i) => tags.get(i + 1).map(|t| t.to_string()),
+        };
+        self.update_entries();
+    }
+
+    pub fn toggle_favorite(&mut self) {
+        // pins the displayed entry at the top of the list or removes it from there
+        let name = self.displayed_entry();
+        let favorite = !self.index.is_favorite(&name);
+        if let Err(error) = self.db_manager.set_favorite(&name, favorite) {
+            log::error!("Failed to change favorites: {error}");
+            return;
+        }
+        self.reindex_entry(&name);
+        self.refresh_entries();
+    }
+
+    pub fn refresh_entries(&mut self) {
+        // reloads the list with recent uses and keeps the selected row
+        let selected = self.entries_list.current_item().cloned();
+        self.update_entries();
+        if let Some(selected) = selected {
+            self.select_row(|row| *row == selected);
+        }
+    }
+
+    pub fn jump_to_section(&mut self, section: Section) {
+        // selects the next entry of the favorites or recent section, starts over after the last one
+        if self.is_filtering() {
+            self.text_fields.search_bar = input_field();
+            self.tag_filter = None;
+            self.update_entries();
+        }
+
+        let rows: Vec<usize> = self.entries_list.items
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.entry().is_some() && row.section() == section)
+            .map(|(index, _)| index)
+            .collect();
+
+        let current = self.entries_list.current_index();
+        let next = rows.iter().find(|index| current.is_some_and(|c| **index > c)).or(rows.first());
+        if let Some(index) = next {
+            self.entries_list.set_index(*index);
+        }
+    }
+
+    pub fn toggle_folder(&mut self, open: Option<bool>) {
+        // opens or closes the selected folder, toggles it without a given state
+        let Some(ListRow::Folder { path, collapsed, .. }) = self.entries_list.current_item().cloned() else {
+            return;
+        };
+        if open.is_some_and(|open| open != collapsed) {
+            return;
+        }
+
+        if collapsed {
+            self.collapsed_folders.remove(&path);
+        } else {
+            self.collapsed_folders.insert(path.clone());
+        }
+        self.entries_list.set_items(self.build_rows());
+        self.select_row(|row| matches!(row, ListRow::Folder { path: p, .. } if *p == path));
     }
 
     pub fn unlock_vault(&mut self) {
@@ -250,25 +1215,39 @@ pub fn unlock_vault(&mut self) {
         // sets app state according to if password is correct
 
         let master_key = derive_key(
-            self.text_fields.password_input.lines()[0].clone(),
-            &self.file_manager.get_salt().unwrap(),
+            self.text_fields.password_input.lines()[0].as_str(),
+            &self.file_manager.vault.get_salt().unwrap(),
         );
 
         // login if password correct
-        if self.db_manager.check_key_correct(master_key.clone()) {
+        if self.db_manager.check_key_correct(&master_key) {
             log::info!("Login successful after {} failed attempts.", self.login_count);
             self.login_count = 0;
 
             // unlock vault and clear password
-            self.db_manager.connect_to_db(master_key.clone());
-            self.master_key = Some(SecureStorage::new(master_key));
+            if let Err(error) = self.db_manager.connect_to_db(&master_key) {
+                wipe_field(&mut self.text_fields.password_input, password_field());
+                self.vault_error = Some(format!("Vault can't be opened: {error}"));
+                self.vault_state.state = LoginState::VaultUnavailable;
+                return;
+            }
+            self.master_key = Some(SecureStorage::new(&master_key));
 
             self.vault_state.state = LoginState::Unlocked;
-            self.text_fields.password_input = password_field();
+            wipe_field(&mut self.text_fields.password_input, password_field());
+            self.db_manager.record(AuditAction::Unlocked, None, None);
 
-            // load entries and templates
+            // load entries, templates and settings
             self.templates.set_items(self.db_manager.get_all_templates());
+            self.vault_settings = self.db_manager.get_vault_settings();
+
+            // the state before expired values get removed
+            self.force_backup();
+            self.db_manager.prune_history(&self.vault_settings);
+            self.purge_expired_trash();
+            self.reload_index();
             self.update_entries();
+            self.load_audit_trail();
             log::info!("Loaded templates from database");
         } else {
             self.vault_state.state = LoginState::IncorrectLogin;
@@ -279,58 +1258,264 @@ pub fn unlock_vault(&mut self) {
     pub fn setup_vault(&mut self) {
         // creates a new vault with entered credential
         // get key and clear fields
-        let master_key = self.text_fields.password_input.lines()[0].clone();
+        let password = SecretString::from(self.text_fields.password_input.lines()[0].as_str());
 
         self.vault_state.clear_password();
-        self.text_fields.password_input = password_field();
+        wipe_field(&mut self.text_fields.password_input, password_field());
 
         // setup database
         self.db_manager.create_new_db();
 
         // derive key and store securely in memory
-        let master_key = derive_key(master_key, &self.file_manager.get_salt().unwrap());
-        self.master_key = Some(SecureStorage::new(master_key.clone()));
+        let master_key = derive_key(&password, &self.file_manager.vault.get_salt().unwrap());
+        self.master_key = Some(SecureStorage::new(&master_key));
 
         // set password to new key which needed the sqlite3 salt
-        self.db_manager.set_db_key(master_key);
+        self.db_manager.set_db_key(&master_key);
 
-        // unlock vault and load templates
+        // unlock vault and load templates and settings
         self.templates.set_items(self.db_manager.get_all_templates());
+        self.vault_settings = self.db_manager.get_vault_settings();
         self.vault_state.state = LoginState::Unlocked;
+        self.db_manager.record(AuditAction::Unlocked, None, Some("vault created"));
 
         log::info!("Created new vault");
+        if let Err(error) = self.file_manager.register_vault(self.file_manager.vault.entry()) {
+            log::error!("Failed to register vault: {error}");
+        }
+        self.force_backup();
+    }
+
+    pub fn open_selected_vault(&mut self) {
+        // switches to the vault selected in the picker and sets the login state for it
+        let Some(entry) = self.vault_list.current_item().cloned() else {
+            return;
+        };
+
+        wipe_field(&mut self.text_fields.password_input, password_field());
+        self.vault_state.clear_password();
+        self.vault_error = None;
+        self.login_count = 0;
+
+        self.vault_state.state = match self.file_manager.select_vault(&entry) {
+            Ok(true) => LoginStates::new(self.file_manager.vault.exists()).state,
+            Ok(false) => {
+                self.vault_error = Some("Vault is open in another instance".to_string());
+                LoginState::VaultUnavailable
+            }
+            Err(error) => {
+                log::error!("Failed to lock vault: {error}");
+                self.vault_error = Some(format!("Vault can't be used: {error}"));
+                LoginState::VaultUnavailable
+            }
+        };
+        self.db_manager = AppDBConnector::new(entry.path);
+
+        log::info!("Selected vault {}", entry.name);
+    }
+
+    pub fn select_next_vault(&mut self) {
+        // picks the next known vault on the login screen
+        self.vault_list.next();
+        self.open_selected_vault();
+    }
+
+    pub fn select_previous_vault(&mut self) {
+        // picks the previous known vault on the login screen
+        self.vault_list.previous();
+        self.open_selected_vault();
+    }
+
+    pub fn choose_vault_path(&mut self) {
+        // asks for the path of a new or existing vault file
+        self.text_fields.vault_path_input = input_field();
+        self.vault_error = None;
+        self.vault_state.state = LoginState::ChooseVaultPath;
+    }
+
+    pub fn add_vault(&mut self) {
+        // adds the entered vault to the picker, existing files get remembered right away
+        // new vaults get remembered after their creation
+        let input = self.text_fields.vault_path_input.lines()[0].trim().to_string();
+        if input.is_empty() {
+            self.vault_error = Some("Please enter a path".to_string());
+            return;
+        }
+
+        let path = resolve_vault_path(&input);
+        if path.is_dir() {
+            self.vault_error = Some("Path is a directory".to_string());
+            return;
+        }
+
+        let entry = vault_entry_from_path(path);
+        if entry.path.exists() {
+            if let Err(error) = self.file_manager.register_vault(entry.clone()) {
+                log::error!("Failed to register vault: {error}");
+                self.vault_error = Some(format!("Failed to remember vault: {error}"));
+                return;
+            }
+        }
+
+        let index = match self.vault_list.items.iter().position(|v| v.path == entry.path) {
+            Some(index) => index,
+            None => {
+                self.vault_list.items.push(entry);
+                self.vault_list.len() - 1
+            }
+        };
+        self.vault_list.set_index(index);
+        self.open_selected_vault();
+    }
+
+    fn lock_if_disconnected(&mut self) {
+        // locks the vault when its file could not be opened again after it was replaced
+        if self.master_key.is_none() || self.db_manager.is_connected() {
+            return;
+        }
+
+        let error = match &self.vault_status {
+            Some(Err(error)) => error.clone(),
+            _ => "Vault can't be opened again".to_string(),
+        };
+        self.lock_vault();
+        self.vault_error = Some(error);
+        self.vault_state.state = LoginState::VaultUnavailable;
     }
 
     pub fn lock_vault(&mut self) {
-        // disconnects from database and locks vault
+        // backs up changes made since the last backup, disconnects from database and locks vault
+        if self.master_key.is_some() && self.db_manager.is_connected() {
+            self.apply_retention();
+        }
+        self.retention_changed = false;
+        if self.backup_pending && self.master_key.is_some() && self.db_manager.is_connected() {
+            self.force_backup();
+        }
+        self.last_backup = None;
+        self.backup_pending = false;
         self.db_manager.disconnect_from_db();
+        self.master_key = None;
+        self.last_reauth = None;
+        self.cancel_reauth();
+        self.cancel_entry_edit();
+        self.close_field_history();
+        self.generator = None;
         self.vault_state.state = LoginState::Login;
 
-        // clear clipboard and clean search field on exiting
+        // clear clipboard and clean displayed values on exiting
         self.clipboard.force_clear_clipboard();
         self.text_fields.search_bar = input_field();
+        self.text_fields.wipe_edit_fields();
+        self.current_entry = None;
+        self.current_template = None;
+        self.entries_list.set_items(vec![]);
+        self.entry_names.clear();
+        self.collapsed_folders.clear();
+        self.index.clear();
+        self.tag_filter = None;
+        self.search_matches.clear();
+        self.page_selected = false;
+        self.delete_confirm = false;
+        self.vault_status = None;
+        self.doctor_report = None;
+        self.close_backup();
+        self.backups.set_items(vec![]);
+        self.vault_settings = VaultSettings::default();
+        self.text_fields.audit_search = input_field();
+        self.audit_trail.clear();
+        self.audit_list.set_items(vec![]);
+        self.audit_filter = None;
+        self.audit_status = None;
+        self.trash_list.set_items(vec![]);
+        self.trash_status = None;
+        self.list_status = None;
+        self.sequence_entry = None;
+        self.purge_confirm = false;
+        redact::clear();
 
         log::info!("Reset Login for vault");
     }
 
+    pub fn register_activity(&mut self) {
+        // remembers the last input for the auto-lock
+        self.last_input = Instant::now();
+    }
+
+    fn check_auto_lock(&mut self) {
+        // locks the vault after the inactivity set in its settings
+        if self.vault_state.state != LoginState::Unlocked {
+            return;
+        }
+
+        if self.vault_settings.auto_lock().is_some_and(|timeout| self.last_input.elapsed() >= timeout) {
+            log::info!("Locking vault after inactivity");
+            self.lock_vault();
+        }
+    }
+
+    pub fn shutdown(&mut self) {
+        // cleans up everything sensitive before the application exits
+        if self.vault_state.state == LoginState::Unlocked {
+            self.lock_vault();
+        }
+
+        self.clipboard.force_clear_clipboard();
+        wipe_field(&mut self.text_fields.password_input, password_field());
+        self.vault_state.clear_password();
+
+        log::info!("Shut down application");
+    }
+
+    fn suspend(&mut self, terminal: &mut Terminal) -> crate::Result<()> {
+        // locks the vault and stops the process until it gets continued
+        log::info!("Suspending application");
+
+        if self.vault_state.state == LoginState::Unlocked {
+            self.lock_vault();
+        }
+        self.clipboard.force_clear_clipboard();
+        wipe_field(&mut self.text_fields.password_input, password_field());
+
+        suspend_terminal(terminal)?;
+        signals::suspend_process();
+        resume_terminal(terminal)?;
+
+        log::info!("Resumed application");
+        Ok(())
+    }
+
     pub fn save_entry(&mut self) {
         // tries to save a new entry to database
         if self.all_fields_filled() {
-            let mut values: Vec<String> = self.text_fields.edit_fields
+            let mut values: Vec<SecretString> = self.text_fields.edit_fields
                 .as_ref()
                 .unwrap()
                 .items
                 .iter()
-                .map(|t| t.lines()[0].clone())
+                .map(|t| SecretString::from(t.lines()[0].as_str()))
                 .collect();
 
             // remove button
             values.pop();
 
+            // the name may contain folders, surrounding spaces of each level are dropped
+            match folders::normalize_name(values[0].as_str()) {
+                Ok(name) => values[0] = SecretString::new(name),
+                Err(error) => {
+                    let fields = self.text_fields.edit_fields.as_mut().unwrap();
+                    let last = fields.len() - 1;
+                    fields.state.select(Some(last));
+
+                    self.style_editable_confirm(&error);
+                    return;
+                }
+            }
+
             // select correct database from template in which to insert
-            let database_name = self.templates.get_ref(
-                self.current_template.unwrap()
-            ).unwrap().db_name.clone();
+            let template = self.templates.get_ref(self.current_template.unwrap()).unwrap();
+            let (database_name, template_name) = (template.db_name.clone(), template.name.clone());
+            let name = values[0].clone();
             let success = self.db_manager.insert_entry(
                 database_name,
                 values,
@@ -340,6 +1525,10 @@ pub fn save_entry(&mut self) {
 
             // display depending if insert worked or not
             if success {
+                self.db_manager.record(AuditAction::Created, Some(&name), Some(&template_name));
+                self.reindex_entry(&name);
+                self.backup_vault();
+
                 // load entries and clear fields
                 self.update_entries();
                 self.reset_input_fields();
@@ -361,9 +1550,15 @@ pub fn save_entry(&mut self) {
     }
 
     pub fn delete_entry(&mut self) {
-        // deletes entry from view and database
-        let current = self.entries_list.current_item().unwrap().clone();
-        self.db_manager.delete_entry(current);
+        // moves the entry into the trash and removes it from view
+        let current = self.displayed_entry();
+        if let Err(error) = self.db_manager.trash_entry(current.clone()) {
+            log::error!("Failed to move entry to trash: {error}");
+            return;
+        }
+        self.db_manager.record(AuditAction::Deleted, Some(&current), Some("moved to trash"));
+        self.index.remove(&current);
+        self.backup_vault();
 
         // remove from view and update entries
         self.current_entry = None;
@@ -372,6 +1567,438 @@ pub fn delete_entry(&mut self) {
         self.update_entries();
     }
 
+    pub fn run_vault_action(&mut self) {
+        // runs the selected maintenance action and stores its outcome for display
+        let action = match self.vault_actions.current_item() {
+            Some(action) => *action,
+            None => return,
+        };
+
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        self.doctor_report = None;
+
+        self.vault_status = Some(match action {
+            VaultAction::Compact => self.db_manager
+                .compact(&key, &mut self.file_manager.vault)
+                .map(|backup| format!("Vault compacted successfully, the previous file was saved to {}", backup.display())),
+            VaultAction::Doctor => self.db_manager
+                .diagnose(&key)
+                .and_then(|report| {
+                    let mut lines = report.lines();
+                    let fixes = report.fixes();
+
+                    if !fixes.is_empty() {
+                        lines.push(String::new());
+                        lines.push(format!(
+                            "Press {} to apply these fixes:",
+                            self.config.keys.repair.to_ascii_uppercase(),
+                        ));
+                        lines.extend(fixes.iter().map(|f| format!("  {}", f.description())));
+                    }
+
+                    let healthy = report.is_healthy();
+                    self.doctor_report = Some(report);
+
+                    if healthy { Ok(lines.join("\n")) } else { Err(lines.join("\n")) }
+                }),
+            VaultAction::Restore => match self.file_manager.vault.list_backups() {
+                Ok(backups) if backups.is_empty() => Err("No backups found".to_string()),
+                Ok(backups) => {
+                    self.backups.set_items(backups);
+                    self.backups.state.select(Some(0));
+                    self.page_selected = true;
+                    return;
+                }
+                Err(error) => Err(error.to_string()),
+            },
+            VaultAction::Settings => {
+                self.settings_list.default_selected();
+                self.page_selected = true;
+                return;
+            }
+        });
+
+        // the doctor report lists entry names, only its result gets logged
+        match &self.vault_status {
+            Some(Err(_)) if action == VaultAction::Doctor => log::warn!("Vault check found problems"),
+            Some(Err(error)) => log::error!("Vault action failed: {error}"),
+            _ => {}
+        }
+        self.lock_if_disconnected();
+    }
+
+    pub fn change_vault_setting(&mut self, increase: bool) {
+        // changes the selected vault setting by one step and stores it in the vault
+        let Some(setting) = self.settings_list.current_item().copied() else {
+            return;
+        };
+
+        let mut settings = self.vault_settings;
+        setting.change(&mut settings, increase);
+        if settings == self.vault_settings {
+            return;
+        }
+
+        self.vault_status = Some(match self.db_manager.set_vault_settings(&settings) {
+            Ok(()) => {
+                self.vault_settings = settings;
+                // stepping through shorter retentions must not remove on the way, applied when leaving the settings
+                if matches!(setting, VaultSetting::HistoryKeep | VaultSetting::HistoryDays | VaultSetting::TrashDays) {
+                    self.retention_changed = true;
+                }
+                Ok(format!("{}: {}", setting.name(), setting.value(&settings)))
+            }
+            Err(error) => {
+                log::error!("Failed to store vault settings: {error}");
+                Err(error)
+            }
+        });
+    }
+
+    pub fn leave_vault_settings(&mut self) {
+        // closes the vault settings and applies a changed retention
+        self.unselect_right();
+        self.vault_status = None;
+        self.apply_retention();
+    }
+
+    fn apply_retention(&mut self) {
+        // removes what the changed vault settings no longer keep, the state before gets backed up
+        if !self.retention_changed {
+            return;
+        }
+        self.retention_changed = false;
+
+        self.force_backup();
+        self.db_manager.prune_history(&self.vault_settings);
+        self.purge_expired_trash();
+    }
+
+    pub fn repair_vault(&mut self) {
+        // applies the fixes offered by the last health check
+        let Some(report) = self.doctor_report.take() else {
+            return;
+        };
+        if report.fixes().is_empty() {
+            return;
+        }
+
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        self.vault_status = Some(
+            self.db_manager
+                .repair(&key, &report, &mut self.file_manager.vault)
+                .map(|applied| applied.join("\n")),
+        );
+
+        if let Some(Err(error)) = &self.vault_status {
+            log::error!("Vault repair failed: {error}");
+        }
+        self.reload_index();
+        self.update_entries();
+    }
+
+    fn backup_vault(&mut self) {
+        // backs up a change unless the last backup is more recent than the configured interval,
+        // skipped changes are backed up by a later one or when the vault gets locked
+        match self.last_backup {
+            Some(last) if last.elapsed() < self.config.backups.interval => self.backup_pending = true,
+            _ => self.force_backup(),
+        }
+    }
+
+    fn force_backup(&mut self) {
+        // takes an automatic backup right away, failing ones must not interrupt working with the vault
+        let key = self.master_key.as_mut().unwrap().get_contents();
+
+        if let Err(error) = self.db_manager.backup(&mut self.file_manager.vault, &key, None) {
+            log::error!("Automatic backup failed: {error}");
+            return;
+        }
+        self.last_backup = Some(Instant::now());
+        self.backup_pending = false;
+
+        if let Err(error) = self.file_manager.vault.rotate_backups(&self.config.backups) {
+            log::error!("Failed to remove expired backups: {error}");
+        }
+    }
+
+    pub fn open_backup(&mut self) {
+        // opens the selected backup read-only and lists its entries
+        let Some(backup) = self.backups.current_item() else {
+            return;
+        };
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        let mut backup_db = AppDBConnector::new(backup.path.clone());
+
+        match backup_db.open_read_only(&key) {
+            Ok(()) => {
+                let mut entries: Vec<String> = backup_db.get_entries().into_iter().map(|(name, _)| name).collect();
+                entries.sort();
+
+                let mut entries = StatefulList::with_items(entries);
+                entries.state.select(Some(0));
+
+                self.backup_entries = Some(entries);
+                self.backup_db = Some(backup_db);
+                self.vault_status = None;
+            }
+            Err(error) => self.vault_status = Some(Err(error)),
+        }
+    }
+
+    pub fn close_backup(&mut self) {
+        // closes the opened backup and returns to the list of backups
+        if let Some(mut backup_db) = self.backup_db.take() {
+            backup_db.disconnect_from_db();
+        }
+        self.backup_entries = None;
+        self.restore_confirm = false;
+    }
+
+    pub fn restore_backup_entry(&mut self) {
+        // copies the selected entry of the opened backup into the vault
+        let Some(name) = self.backup_entries.as_ref().and_then(|e| e.current_item()).cloned() else {
+            return;
+        };
+        let key = self.master_key.as_mut().unwrap().get_contents();
+
+        self.vault_status = Some(
+            self.db_manager
+                .restore_entry(self.backup_db.as_ref().unwrap(), name.clone(), &key)
+                .map(|_| format!("Restored entry '{name}'")),
+        );
+
+        if self.vault_status.as_ref().is_some_and(|s| s.is_ok()) {
+            let backup = self.backups.current_item().map(|b| b.name());
+            self.db_manager.record(AuditAction::Restored, Some(&name), backup.as_deref());
+            self.reindex_entry(&name);
+            self.backup_vault();
+            self.update_entries();
+        }
+    }
+
+    pub fn restore_whole_vault(&mut self) {
+        // replaces the vault with the opened backup, needs to be confirmed by pressing twice
+        if !self.restore_confirm {
+            self.restore_confirm = true;
+            return;
+        }
+
+        let backup = self.backups.current_item().unwrap();
+        let (path, backup_name) = (backup.path.clone(), backup.name());
+        let key = self.master_key.as_mut().unwrap().get_contents();
+        self.close_backup();
+
+        self.vault_status = Some(
+            self.db_manager
+                .restore_vault(&path, &key, &mut self.file_manager.vault)
+                .map(|safety| format!("Vault restored, the previous state was saved to {}", safety.display())),
+        );
+
+        match &self.vault_status {
+            Some(Ok(_)) => {
+                self.db_manager.record(AuditAction::VaultRestored, None, Some(&backup_name));
+
+                // reload everything from the restored file
+                self.current_entry = None;
+                self.templates.set_items(self.db_manager.get_all_templates());
+                self.reload_index();
+                self.update_entries();
+                self.backups.set_items(self.file_manager.vault.list_backups().unwrap_or_default());
+                self.page_selected = false;
+            }
+            Some(Err(error)) => log::error!("Restoring vault failed: {error}"),
+            None => {}
+        }
+        self.lock_if_disconnected();
+    }
+
+    pub fn next_page(&mut self) {
+        // switches to the next page
+        self.page_index.page_up();
+        self.load_audit_trail();
+        self.load_trash();
+    }
+
+    pub fn previous_page(&mut self) {
+        // switches to the previous page
+        self.page_index.page_down();
+        self.load_audit_trail();
+        self.load_trash();
+    }
+
+    fn load_trash(&mut self) {
+        // reads the trashed entries from the vault when their page gets shown
+        if self.page_index.index != TRASH_PAGE {
+            return;
+        }
+
+        self.purge_confirm = false;
+        match self.db_manager.get_trash() {
+            Ok(items) => self.trash_list.set_items(items),
+            Err(error) => {
+                log::error!("Failed to read trash: {error}");
+                self.trash_list.set_items(vec![]);
+                self.trash_status = Some(Err(format!("Trash can't be read: {error}")));
+            }
+        }
+    }
+
+    pub fn trash_template_name(&self, item: &TrashItem) -> String {
+        // name of the template a trashed entry belongs to
+        self.templates
+            .items
+            .iter()
+            .find(|t| t.db_name == item.template)
+            .map_or_else(|| item.template.clone(), |t| t.name.clone())
+    }
+
+    pub fn restore_from_trash(&mut self) {
+        // moves the selected entry back into the vault
+        let Some(item) = self.trash_list.current_item() else {
+            return;
+        };
+
+        self.trash_status = Some(match self.db_manager.restore_from_trash(item) {
+            Ok(name) => {
+                let detail = if name == item.name {
+                    "from trash".to_string()
+                } else {
+                    format!("from trash, renamed from {}", item.name)
+                };
+                self.db_manager.record(AuditAction::Restored, Some(&name), Some(&detail));
+                self.reindex_entry(&name);
+                Ok(format!("Restored entry as '{name}'"))
+            }
+            Err(error) => {
+                log::error!("Failed to restore entry from trash: {error}");
+                Err(error)
+            }
+        });
+
+        if let Some(Ok(_)) = self.trash_status {
+            self.backup_vault();
+            self.update_entries();
+            self.load_trash();
+        }
+    }
+
+    pub fn purge_from_trash(&mut self) {
+        // permanently removes the selected entry after pressing the key twice
+        let Some(item) = self.trash_list.current_item() else {
+            return;
+        };
+        if !self.purge_confirm {
+            self.purge_confirm = true;
+            self.trash_status = None;
+            return;
+        }
+
+        // purging can't be undone, the vault gets backed up first
+        let (name, id) = (item.name.clone(), item.id);
+        self.force_backup();
+        self.trash_status = Some(match self.db_manager.purge_from_trash(&[id]) {
+            Ok(_) => {
+                self.db_manager.record(AuditAction::Purged, Some(&name), None);
+                Ok(format!("Purged '{name}' permanently"))
+            }
+            Err(error) => {
+                log::error!("Failed to purge entry: {error}");
+                Err(error)
+            }
+        });
+
+        self.load_trash();
+    }
+
+    fn purge_expired_trash(&mut self) {
+        // purges entries that stayed in the trash longer than the vault settings allow
+        let expired = self.db_manager.get_expired_trash(&self.vault_settings);
+        if expired.is_empty() {
+            return;
+        }
+
+        let ids: Vec<i64> = expired.iter().map(|item| item.id).collect();
+        match self.db_manager.purge_from_trash(&ids) {
+            Ok(_) => {
+                for item in expired.iter() {
+                    self.db_manager.record(AuditAction::Purged, Some(&item.name), Some("retention period ended"));
+                }
+            }
+            Err(error) => log::error!("Failed to purge expired entries from trash: {error}"),
+        }
+    }
+
+    fn load_audit_trail(&mut self) {
+        // reads the audit trail from the vault when its page gets shown
+        if self.page_index.index != AUDIT_PAGE {
+            return;
+        }
+
+        match self.db_manager.get_audit_trail() {
+            Ok(records) => {
+                self.audit_trail = records;
+                self.audit_status = None;
+            }
+            Err(error) => {
+                log::error!("Failed to read audit trail: {error}");
+                self.audit_trail.clear();
+                self.audit_status = Some(Err(format!("Audit trail can't be read: {error}")));
+            }
+        }
+        self.filter_audit_trail();
+    }
+
+    pub fn filter_audit_trail(&mut self) {
+        // shows the records matching the selected action and the search text
+        let search = self.text_fields.audit_search.lines()[0].as_str();
+        let records = self
+            .audit_trail
+            .iter()
+            .filter(|r| r.matches(self.audit_filter, search))
+            .cloned()
+            .collect();
+
+        self.audit_list.set_items(records);
+        self.audit_list.default_selected();
+    }
+
+    pub fn change_audit_filter(&mut self, forward: bool) {
+        // cycles through the actions, after the last one all actions are shown again
+        let actions = AuditAction::all();
+        let position = self.audit_filter.and_then(|f| actions.iter().position(|a| *a == f));
+
+        self.audit_filter = match (position, forward) {
+            (None, true) => actions.first().copied(),
+            (None, false) => actions.last().copied(),
+            (Some(i), true) => actions.get(i + 1).copied(),
+            (Some(i), false) => i.checked_sub(1).map(|i| actions[i]),
+        };
+        self.filter_audit_trail();
+    }
+
+    pub fn export_audit_trail(&mut self) {
+        // writes the shown records oldest first into a file for reviews
+        if self.audit_list.items.is_empty() {
+            self.audit_status = Some(Err("No records to export".to_string()));
+            return;
+        }
+
+        let csv = audit::to_csv(self.audit_list.items.iter().rev());
+        self.audit_status = Some(
+            self.file_manager
+                .write_export("audit", &csv)
+                .map(|path| format!("Exported {} record(s) to {}", self.audit_list.len(), path.display()))
+                .map_err(|e| format!("Export failed: {e}")),
+        );
+
+        match &self.audit_status {
+            Some(Ok(_)) => log::info!("Exported audit trail"),
+            Some(Err(error)) => log::error!("{error}"),
+            None => {}
+        }
+    }
+
     pub fn all_fields_filled(&self) -> bool {
         // checks if all template fields are filled
         for field in self.text_fields.edit_fields.as_ref().unwrap().items.iter() {
@@ -382,17 +2009,24 @@ pub fn all_fields_filled(&self) -> bool {
         true
     }
 
-    pub fn copy_to_clipboard(&mut self, text: String) {
-        // copies a string to clipboard
-        self.clipboard.copy_to_clipboard(text.as_str());
-        self.set_copied_state(Some(
-            self.current_entry
-                .as_ref()
-                .unwrap()
-                .1
-                .current_index()
-                .unwrap(),
-        ));
+    pub fn copy_to_clipboard(&mut self, text: SecretString) {
+        // copies a string of the selected field to clipboard for the timeout of that field
+        let EntryView { template, fields, .. } = self.current_entry.as_ref().unwrap();
+        let index = fields.current_index().unwrap();
+        let timeout = self.config.clipboard_timeout(template, &fields.items[index].0);
+
+        self.clipboard.copy_to_clipboard(&text, timeout);
+        self.set_copied_state(Some(index));
+    }
+
+    pub fn clear_clipboard(&mut self) {
+        // clears a copied value before its timeout
+        if self.clipboard.remaining().is_some() {
+            log::info!("Clearing clipboard on request");
+            self.clipboard.force_clear_clipboard();
+            self.set_copied_state(None);
+            self.list_status = None;
+        }
     }
 
     pub fn set_copied_state(&mut self, state: Option<usize>) {
@@ -0,0 +1,135 @@
+use arboard::Clipboard;
+use std::{
+    env,
+    io::{self, Write},
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{base64_enc_dec::encode_base64, config::ClipboardKind, secret::SecretString};
+
+// held while a frame gets drawn, escape sequences of OSC 52 must not end up inside one
+pub static TERMINAL_OUTPUT: Mutex<()> = Mutex::new(());
+
+pub trait ClipboardBackend: Send {
+    // a place copied values are put on, the "Clipboard Clearer" thread clears it after the timeout
+    fn name(&self) -> &'static str;
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+    // None if the clipboard can't be read back, it then gets cleared without comparing
+    fn get_text(&mut self) -> Option<SecretString>;
+    fn clear(&mut self) -> Result<(), String>;
+}
+
+pub struct SystemClipboard {
+    // clipboard of the desktop through arboard, needs X11, Wayland, macOS or Windows
+    clipboard: Clipboard,
+}
+
+impl ClipboardBackend for SystemClipboard {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.clipboard.set_text(text).map_err(|e| e.to_string())
+    }
+
+    fn get_text(&mut self) -> Option<SecretString> {
+        // an unreadable clipboard, e.g. holding an image, is cleared without comparing
+        self.clipboard.get_text().ok().map(SecretString::new)
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.clipboard.clear().map_err(|e| e.to_string())
+    }
+}
+
+pub struct Osc52Clipboard;
+
+impl Osc52Clipboard {
+    fn write(&self, text: &str) -> Result<(), String> {
+        // asks the terminal to set its clipboard, which also works through SSH; the terminal can't be asked back
+        // the encoded value is as readable as the text, it gets wiped like it and the sequence is built
+        // without growing, so no copy is left behind in a reallocated buffer
+        let encoded = SecretString::new(encode_base64(text));
+        let mut sequence = String::with_capacity(encoded.len() + 8);
+        sequence.push_str("\x1b]52;c;");
+        sequence.push_str(encoded.as_str());
+        sequence.push('\x07');
+        let sequence = SecretString::new(sequence);
+        let _output = TERMINAL_OUTPUT.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let mut stdout = io::stdout().lock();
+        stdout
+            .write_all(sequence.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl ClipboardBackend for Osc52Clipboard {
+    fn name(&self) -> &'static str {
+        "OSC 52"
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.write(text)
+    }
+
+    fn get_text(&mut self) -> Option<SecretString> {
+        None
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        // an empty value clears the clipboard of the terminal
+        self.write("")
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryClipboard {
+    // keeps the copied value inside the process only, for tests and machines without any clipboard
+    text: SecretString,
+}
+
+impl ClipboardBackend for MemoryClipboard {
+    fn name(&self) -> &'static str {
+        "in-memory"
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.text = SecretString::from(text);
+        Ok(())
+    }
+
+    fn get_text(&mut self) -> Option<SecretString> {
+        Some(self.text.clone())
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.text = SecretString::default();
+        Ok(())
+    }
+}
+
+pub fn create(kind: ClipboardKind) -> Box<dyn ClipboardBackend> {
+    // the configured backend; automatically OSC 52 in SSH sessions and where no desktop clipboard is available
+    let backend: Box<dyn ClipboardBackend> = match kind {
+        ClipboardKind::Auto if env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some() => {
+            Box::new(Osc52Clipboard)
+        }
+        ClipboardKind::Auto | ClipboardKind::System => match Clipboard::new() {
+            Ok(clipboard) => Box::new(SystemClipboard { clipboard }),
+            Err(error) => {
+                if kind == ClipboardKind::System {
+                    log::error!("System clipboard is not available: {error}");
+                }
+                Box::new(Osc52Clipboard)
+            }
+        },
+        ClipboardKind::Osc52 => Box::new(Osc52Clipboard),
+        ClipboardKind::Memory => Box::new(MemoryClipboard::default()),
+    };
+
+    log::info!("Using the {} clipboard", backend.name());
+    backend
+}
@@ -1,65 +1,110 @@
-use arboard::Clipboard;
 use std::{
+    collections::VecDeque,
     io,
     sync::{mpsc, Arc, Mutex},
     thread::{self, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use crate::{app::ClState, key_processor::SecureStorage};
-
-// const that holds timeout of clipboard
-const TIMEOUT: u64 = 30;
+use crate::{
+    app::{clipboard_backend::ClipboardBackend, ClState},
+    key_processor::SecureStorage,
+    secret::SecretString,
+};
 
 pub enum Message {
     // communication message sent to thread
     Stop,
-    Reset(String),
+    Reset(SecretString, Duration),
 }
 
+// named values waiting to be copied one after another, each with its timeout
+type Sequence = Arc<Mutex<VecDeque<(String, SecretString, Duration)>>>;
+
 pub struct ClipboardManager {
-    // holds pipe, thread handle and shared clipboard
+    // holds pipe, thread handle, shared clipboard, the values of a running copy sequence and when the clipboard gets cleared
     sender: Option<mpsc::Sender<Message>>,
     handle: Option<JoinHandle<io::Result<()>>>,
-    shared_clipboard: Arc<Mutex<Clipboard>>,
+    shared_clipboard: Arc<Mutex<Box<dyn ClipboardBackend>>>,
     shared_cl_state: ClState,
+    shared_sequence: Sequence,
+    deadline: Option<Instant>,
 }
 
 impl ClipboardManager {
-    pub fn new(cl_state: ClState) -> ClipboardManager {
-        // creates new empty manager
+    pub fn new(backend: Box<dyn ClipboardBackend>, cl_state: ClState) -> ClipboardManager {
+        // creates new empty manager copying to the given backend
         ClipboardManager {
             sender: None,
             handle: None,
-            shared_clipboard: Arc::new(Mutex::new(Clipboard::new().unwrap())),
+            shared_clipboard: Arc::new(Mutex::new(backend)),
             shared_cl_state: cl_state,
+            shared_sequence: Arc::new(Mutex::new(VecDeque::new())),
+            deadline: None,
         }
     }
 
-    pub fn copy_to_clipboard(&mut self, text: &str) {
-        // function to call for copying a text to clipboard
-        log::info!("Copied value to clipboard");
-        self.reset_timer(text);
+    pub fn copy_to_clipboard(&mut self, text: &str, timeout: Duration) {
+        // function to call for copying a text to clipboard until the timeout, a running sequence ends
+        self.shared_sequence.lock().unwrap().clear();
+        self.set_clipboard(text, timeout);
+    }
 
-        let mut clipboard = self.shared_clipboard.lock().unwrap();
-        clipboard.set_text(text).unwrap();
+    pub fn copy_sequence(&mut self, values: Vec<(String, SecretString, Duration)>) -> Option<String> {
+        // copies the first value and keeps the others for copy_next, returns the name of the copied one
+        *self.shared_sequence.lock().unwrap() = values.into();
+        self.copy_next()
+    }
+
+    pub fn copy_next(&mut self) -> Option<String> {
+        // copies the next value of the running sequence, None once all were copied or the timeout ended it;
+        // reading the clipboard can't be noticed, so this is called on a keypress
+        let (name, value, timeout) = self.shared_sequence.lock().unwrap().pop_front()?;
+        log::info!("Copied next value of sequence");
+        self.set_clipboard(&value, timeout);
+        Some(name)
+    }
+
+    pub fn next_in_sequence(&self) -> Option<String> {
+        // name of the value copy_next copies, None without a running sequence
+        self.shared_sequence.lock().unwrap().front().map(|(name, ..)| name.clone())
+    }
+
+    pub fn remaining(&self) -> Option<Duration> {
+        // time until the copied value gets cleared, None if nothing is waiting to be cleared
+        self.deadline?.checked_duration_since(Instant::now()).filter(|left| !left.is_zero())
     }
 
     pub fn force_clear_clipboard(&mut self) {
         // function to call for resetting the clipboard
         self.stop_timer();
+        self.shared_sequence.lock().unwrap().clear();
+        self.deadline = None;
     }
 
-    fn spawn_thread(&mut self, content: &str) {
+    fn set_clipboard(&mut self, text: &str, timeout: Duration) {
+        // puts a text on the clipboard and restarts the timeout
+        log::info!("Copied value to clipboard");
+        self.reset_timer(text, timeout);
+        self.deadline = Some(Instant::now() + timeout);
+
+        let mut clipboard = self.shared_clipboard.lock().unwrap();
+        if let Err(error) = clipboard.set_text(text) {
+            log::error!("Failed to copy to the {} clipboard: {error}", clipboard.name());
+        }
+    }
+
+    fn spawn_thread(&mut self, content: &str, mut timeout: Duration) {
         // spawns a new timer thread
         let (sender, receiver) = mpsc::channel();
 
         // init shared references
         let shared_clipboard = Arc::clone(&self.shared_clipboard);
         let shared_cl_state = Arc::clone(&self.shared_cl_state);
+        let shared_sequence = Arc::clone(&self.shared_sequence);
 
         // store last copied password in memory safely
-        let mut current_pw = SecureStorage::from_string(content.to_string());
+        let mut current_pw = SecureStorage::from_string(content);
 
         // spawn new thread "Clipboard Clearer"
         let handle: JoinHandle<io::Result<()>> = thread::Builder::new()
@@ -67,9 +112,10 @@ fn spawn_thread(&mut self, content: &str) {
             .spawn(move || {
                 // waits until timeout, restart if reset send through pipe, return if pipe dropped
                 loop {
-                    match receiver.recv_timeout(Duration::from_secs(TIMEOUT)) {
-                        Ok(Message::Reset(new_pw)) => {
-                            current_pw = SecureStorage::from_string(new_pw);
+                    match receiver.recv_timeout(timeout) {
+                        Ok(Message::Reset(new_pw, new_timeout)) => {
+                            current_pw = SecureStorage::from_string(&new_pw);
+                            timeout = new_timeout;
                         }
                         Ok(Message::Stop) => {
                             break;
@@ -84,21 +130,25 @@ fn spawn_thread(&mut self, content: &str) {
                     }
                 }
 
-                // clear clipboard if current password is still same (no new copies)
+                // clear clipboard if current password is still same (no new copies) or it can't be read back
                 let mut clipboard = shared_clipboard.lock().unwrap();
-                let copied = String::from_utf8(current_pw.get_contents()).unwrap_or_default();
+                let copied = current_pw.get_contents();
+                let unchanged = clipboard
+                    .get_text()
+                    .is_none_or(|current| copied.as_slice() == current.as_bytes());
 
-                if copied == clipboard.get_text().unwrap_or_default() {
+                if unchanged {
                     log::info!("Clearing clipboard");
 
-                    if clipboard.clear().is_err() {
-                        log::warn!("Failed to clear clipboard");
+                    if let Err(error) = clipboard.clear() {
+                        log::warn!("Failed to clear clipboard: {error}");
                     }
                 }
 
-                // clear visual copied
+                // clear visual copied, values of a sequence not copied in time are dropped as well
                 let mut clip_state = shared_cl_state.lock().unwrap();
                 clip_state.value = None;
+                shared_sequence.lock().unwrap().clear();
 
                 Ok(())
             })
@@ -109,7 +159,7 @@ fn spawn_thread(&mut self, content: &str) {
         self.handle = Some(handle);
     }
 
-    fn reset_timer(&mut self, copy: &str) {
+    fn reset_timer(&mut self, copy: &str, timeout: Duration) {
         // resets thread through pipe if it exists otherwise spawn a new with content
         if self.handle.is_some() && self.sender.is_some() {
             // possible error when thread is waiting for clipboard mutex. Stop and create new thread
@@ -117,14 +167,14 @@ fn reset_timer(&mut self, copy: &str) {
                 .sender
                 .as_ref()
                 .unwrap()
-                .send(Message::Reset(copy.to_string()))
+                .send(Message::Reset(SecretString::from(copy), timeout))
                 .is_err()
             {
                 self.handle.take().unwrap().join().unwrap().unwrap();
-                self.spawn_thread(copy);
+                self.spawn_thread(copy, timeout);
             }
         } else {
-            self.spawn_thread(copy);
+            self.spawn_thread(copy, timeout);
         }
     }
 
@@ -144,6 +194,13 @@ fn stop_timer(&mut self) {
     }
 }
 
+impl Drop for ClipboardManager {
+    fn drop(&mut self) {
+        // clears a still copied value when the application exits or unwinds
+        self.stop_timer();
+    }
+}
+
 // pub struct Timer {
 //     // holds current time of thread
 //     default: isize,
@@ -170,3 +227,120 @@ fn stop_timer(&mut self) {
 //         self.default
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{clipboard_backend::MemoryClipboard, extras::SingleValue};
+
+    const SHORT: Duration = Duration::from_millis(50);
+    const LONG: Duration = Duration::from_secs(60);
+
+    fn manager() -> (ClipboardManager, ClState) {
+        // manager copying to memory and the copied state it resets
+        let cl_state = Arc::new(Mutex::new(SingleValue { value: Some(0) }));
+        (ClipboardManager::new(Box::<MemoryClipboard>::default(), Arc::clone(&cl_state)), cl_state)
+    }
+
+    fn clipboard(manager: &ClipboardManager) -> String {
+        manager.shared_clipboard.lock().unwrap().get_text().unwrap().as_str().to_string()
+    }
+
+    fn wait_for_timeout() {
+        thread::sleep(SHORT * 6);
+    }
+
+    #[test]
+    fn clears_after_the_timeout() {
+        let (mut manager, cl_state) = manager();
+        manager.copy_to_clipboard("hunter2", SHORT);
+        assert_eq!(clipboard(&manager), "hunter2");
+        assert!(manager.remaining().is_some());
+
+        wait_for_timeout();
+        assert_eq!(clipboard(&manager), "");
+        assert!(manager.remaining().is_none());
+        assert!(cl_state.lock().unwrap().value.is_none());
+    }
+
+    #[test]
+    fn keeps_a_value_copied_by_something_else() {
+        let (mut manager, _) = manager();
+        manager.copy_to_clipboard("hunter2", SHORT);
+        manager.shared_clipboard.lock().unwrap().set_text("copied elsewhere").unwrap();
+
+        wait_for_timeout();
+        assert_eq!(clipboard(&manager), "copied elsewhere");
+    }
+
+    #[test]
+    fn a_new_copy_restarts_the_timeout() {
+        let (mut manager, _) = manager();
+        manager.copy_to_clipboard("hunter2", SHORT);
+        manager.copy_to_clipboard("alice", LONG);
+
+        wait_for_timeout();
+        assert_eq!(clipboard(&manager), "alice");
+        manager.force_clear_clipboard();
+    }
+
+    #[test]
+    fn force_clear_clears_right_away() {
+        let (mut manager, cl_state) = manager();
+        manager.copy_to_clipboard("hunter2", LONG);
+
+        manager.force_clear_clipboard();
+        assert_eq!(clipboard(&manager), "");
+        assert!(manager.remaining().is_none());
+        assert!(cl_state.lock().unwrap().value.is_none());
+    }
+
+    fn sequence() -> Vec<(String, SecretString, Duration)> {
+        vec![
+            ("Username".to_string(), SecretString::from("alice"), LONG),
+            ("Password".to_string(), SecretString::from("hunter2"), LONG),
+        ]
+    }
+
+    #[test]
+    fn copies_a_sequence_value_by_value() {
+        let (mut manager, _) = manager();
+        assert_eq!(manager.copy_sequence(sequence()).as_deref(), Some("Username"));
+        assert_eq!(clipboard(&manager), "alice");
+        assert_eq!(manager.next_in_sequence().as_deref(), Some("Password"));
+
+        assert_eq!(manager.copy_next().as_deref(), Some("Password"));
+        assert_eq!(clipboard(&manager), "hunter2");
+        assert!(manager.next_in_sequence().is_none());
+        assert!(manager.copy_next().is_none());
+        assert_eq!(clipboard(&manager), "hunter2");
+        manager.force_clear_clipboard();
+    }
+
+    #[test]
+    fn a_timeout_drops_the_rest_of_a_sequence() {
+        let (mut manager, _) = manager();
+        let mut values = sequence();
+        values[0].2 = SHORT;
+        manager.copy_sequence(values);
+
+        wait_for_timeout();
+        assert_eq!(clipboard(&manager), "");
+        assert!(manager.next_in_sequence().is_none());
+        assert!(manager.copy_next().is_none());
+    }
+
+    #[test]
+    fn copying_or_clearing_ends_a_sequence() {
+        let (mut manager, _) = manager();
+        manager.copy_sequence(sequence());
+        manager.copy_to_clipboard("other", LONG);
+        assert!(manager.next_in_sequence().is_none());
+        assert_eq!(clipboard(&manager), "other");
+
+        manager.copy_sequence(sequence());
+        manager.force_clear_clipboard();
+        assert!(manager.copy_next().is_none());
+        assert_eq!(clipboard(&manager), "");
+    }
+}
@@ -3,7 +3,8 @@
 
 use crate::{
     app::stateful_list::StatefulList,
-    ui::fields::{input_field, password_field}
+    config::KeyBindings,
+    ui::fields::{input_field, password_field, wipe_field}
 };
 
 
@@ -34,6 +35,61 @@ pub fn page_down(&mut self) {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum VaultAction {
+    // maintenance actions offered on the vault page
+    Compact,
+    Doctor,
+    Restore,
+    Settings,
+}
+
+impl VaultAction {
+    pub fn all() -> Vec<VaultAction> {
+        // returns all actions in display order
+        vec![VaultAction::Compact, VaultAction::Doctor, VaultAction::Restore, VaultAction::Settings]
+    }
+
+    pub fn name(&self) -> &'static str {
+        // title shown in the action list
+        match self {
+            VaultAction::Compact => "Compact Vault",
+            VaultAction::Doctor => "Check Vault Health",
+            VaultAction::Restore => "Restore Backup",
+            VaultAction::Settings => "Vault Settings",
+        }
+    }
+
+    pub fn description(&self, keys: &KeyBindings) -> String {
+        // explanation shown next to the action list
+        match self {
+            VaultAction::Compact => {
+                "Rewrites the vault into a new file without free pages and atomically replaces \
+                the old one. Leftovers of deleted or edited entries are removed from the file.\n\n\
+                Press Enter to start. A verified backup is created first.".to_string()
+            }
+            VaultAction::Doctor => format!(
+                "Verifies every page of the vault, decrypts every field of every entry and looks \
+                for leftovers of deleted entries and unreadable templates. Nothing gets changed.\n\n\
+                Press Enter to start, then {} to apply the offered fixes. A verified backup is \
+                created before each fix.",
+                keys.repair.to_ascii_uppercase(),
+            ),
+            VaultAction::Restore => {
+                "A backup is taken whenever the vault gets unlocked or changed. The 10 newest backups \
+                and the newest backup of each of the last 7 days and 4 weeks are kept.\n\n\
+                Press Enter to browse them. Backups are opened read-only, single entries or the \
+                whole vault can be restored from them.".to_string()
+            }
+            VaultAction::Settings => {
+                "Settings stored encrypted inside this vault. They apply to every device the vault \
+                is opened on.\n\n\
+                Press Enter to change them.".to_string()
+            }
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Template {
     // template struct which gets created from json
@@ -41,6 +97,15 @@ pub struct Template {
     pub name: String,
     pub db_name: String,
     pub elements: Vec<TemplateElement>,
+    #[serde(default)]
+    pub reauthenticate: bool,
+}
+
+impl Template {
+    pub fn field_with_role(&self, role: FieldRole) -> Option<usize> {
+        // index of the field marked as username or password, the name not counted
+        self.elements[1..].iter().position(|element| element.role == Some(role))
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -48,13 +113,37 @@ pub struct TemplateElement {
     // elements from json list
     pub name: String,
     pub private: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<FieldRole>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldRole {
+    // fields that can be copied from the entries list without opening the entry
+    Username,
+    Password,
+}
+
+impl FieldRole {
+    pub fn name(&self) -> &'static str {
+        // shown in the status below the entries list
+        match self {
+            FieldRole::Username => "username",
+            FieldRole::Password => "password",
+        }
+    }
 }
 
 pub struct EditableTextFields<'a> {
     // struct to hold all editable text fields, because they
     // need to be accessible in the event handling
     pub password_input: TextArea<'a>,
+    pub reauth_input: TextArea<'a>,
+    pub field_input: TextArea<'a>,
+    pub vault_path_input: TextArea<'a>,
     pub search_bar: TextArea<'a>,
+    pub audit_search: TextArea<'a>,
     pub edit_fields: Option<StatefulList<TextArea<'a>>>,
 }
 
@@ -63,8 +152,21 @@ pub fn new() -> EditableTextFields<'a> {
         // initialises default fields
         EditableTextFields {
             password_input: password_field(),
+            reauth_input: password_field(),
+            field_input: input_field(),
+            vault_path_input: input_field(),
             search_bar: input_field(),
+            audit_search: input_field(),
             edit_fields: None,
         }
     }
+
+    pub fn wipe_edit_fields(&mut self) {
+        // overwrites all entered credentials before removing the fields
+        if let Some(mut fields) = self.edit_fields.take() {
+            for field in fields.items.iter_mut() {
+                wipe_field(field, input_field());
+            }
+        }
+    }
 }
@@ -1,8 +1,12 @@
-#[derive(Clone)]
+use crate::{
+    app::{extras::FieldRole, stateful_list::StatefulList},
+    secret::SecretString,
+};
+
 pub struct LoginStates {
     // stores the current login state and confirm password for registration
     pub state: LoginState,
-    last_password: Option<String>,
+    last_password: Option<SecretString>,
 }
 
 impl LoginStates {
@@ -20,15 +24,15 @@ pub fn new(db_exists: bool) -> LoginStates {
         }
     }
 
-    pub fn check_pw(self, password: &String) -> bool {
+    pub fn check_pw(&self, password: &str) -> bool {
         // checks confirmation password on vault creation
-        if let Some(last_password) = self.last_password {
-            return last_password == *password;
+        if let Some(last_password) = &self.last_password {
+            return last_password.as_str() == password;
         }
         false
     }
 
-    pub fn set_password(&mut self, password: String) {
+    pub fn set_password(&mut self, password: SecretString) {
         // sets first entered password
         log::info!("Set password to confirm");
         self.last_password = Some(password);
@@ -46,6 +50,8 @@ pub enum LoginState {
     // rendering and input handling depend on these
     Login,
     IncorrectLogin,
+    VaultUnavailable,
+    ChooseVaultPath,
 
     Register,
     NewVaultConfirmMatch,
@@ -53,3 +59,50 @@ pub enum LoginState {
 
     Unlocked,
 }
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ReauthAction {
+    // actions on sensitive entries and protected templates waiting for the master password
+    Reveal(usize),
+    Copy(usize),
+    Edit(usize),
+    History(usize),
+    ToggleSensitive,
+    ToggleTemplate(usize),
+    QuickCopy(FieldRole),
+    CopySequence,
+}
+
+impl ReauthAction {
+    pub fn roles(&self) -> &'static [FieldRole] {
+        // fields copied from the entries list, in the order they get copied
+        match self {
+            ReauthAction::QuickCopy(FieldRole::Username) => &[FieldRole::Username],
+            ReauthAction::QuickCopy(FieldRole::Password) => &[FieldRole::Password],
+            ReauthAction::CopySequence => &[FieldRole::Username, FieldRole::Password],
+            _ => &[],
+        }
+    }
+}
+
+pub struct ReauthPrompt {
+    // open password prompt and the action to run after success
+    pub action: ReauthAction,
+    pub failed: bool,
+}
+
+pub struct EntryView {
+    // displayed entry, every change is written to the entry of this name and not to the highlighted one;
+    // fields are (field name, value, hidden) and end with the delete button
+    pub name: String,
+    pub template: String,
+    pub fields: StatefulList<(String, SecretString, bool)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EntryEdit {
+    // open input on the displayed entry
+    Field(usize),
+    Folder,
+    Tags,
+}
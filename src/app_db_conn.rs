@@ -1,9 +1,25 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
 use rusqlite::Connection;
 
 use crate::{
     app::extras::Template,
+    audit::{AuditAction, AuditRecord},
+    config::VaultSettings,
     db_interface,
+    doctor::{self, DoctorReport},
+    entry_index::{self, EntryIndex, IndexedEntry},
+    file_manager::{FileManager, Vault},
+    generator::GeneratorOptions,
+    history::HistoryValue,
+    metadata::{self, EntryMetadata, Timestamp},
+    redact,
+    secret::{SecretBytes, SecretString},
+    trash::{self, TrashItem},
 };
 
 
@@ -21,8 +37,13 @@ pub fn new(path: PathBuf) -> AppDBConnector {
         }
     }
 
-    pub fn vec_key_to_hex(key: Vec<u8>) -> String {
-        key.iter().map(|byte| format!("{:02X}", byte)).collect()
+    pub fn vec_key_to_hex(key: &[u8]) -> SecretString {
+        // formats the key into a pre-allocated buffer to avoid reallocated copies
+        let mut hex = String::with_capacity(key.len() * 2);
+        for byte in key {
+            write!(hex, "{:02X}", byte).unwrap();
+        }
+        SecretString::new(hex)
     }
 
     pub fn create_new_db(&mut self) {
@@ -31,25 +52,34 @@ pub fn create_new_db(&mut self) {
         log::info!("Created a database file.");
     }
 
-    pub fn set_db_key(&mut self, key: Vec<u8>) {
+    pub fn set_db_key(&mut self, key: &[u8]) {
         let db_key = AppDBConnector::vec_key_to_hex(key);
-        db_interface::change_password(self.connection.as_ref().unwrap(), db_key);
+        db_interface::change_password(self.connection.as_ref().unwrap(), &db_key);
         log::info!("Set a password for the database.");
     }
 
-    pub fn connect_to_db(&mut self, key: Vec<u8>) {
-        // tries to connect to db if correct key (returned as bool)
+    pub fn connect_to_db(&mut self, key: &[u8]) -> Result<(), String> {
+        // connects to the db with a key checked before, fails if the file can't be opened
         let db_key = AppDBConnector::vec_key_to_hex(key);
 
-        if let Ok(conn) = db_interface::establish_connection(&self.path, db_key) {
-            self.connection = Some(conn);
-            log::info!("Connection to database established successfully.");
-        } else {
-            log::error!("Connection to database could not be established!");
-            panic!("Failed to connect to database!")
+        match db_interface::establish_connection(&self.path, &db_key) {
+            Ok(conn) => {
+                self.connection = Some(conn);
+                log::info!("Connection to database established successfully.");
+                Ok(())
+            }
+            Err(error) => {
+                log::error!("Connection to database could not be established!");
+                Err(error.to_string())
+            }
         }
     }
 
+    pub fn is_connected(&self) -> bool {
+        // false after the vault could not be opened again, e.g. once its file was replaced
+        self.connection.is_some()
+    }
+
     pub fn disconnect_from_db(&mut self) {
         // disconnects from db
         if let Some(conn) = self.connection.take() {
@@ -59,41 +89,286 @@ pub fn disconnect_from_db(&mut self) {
         }
     }
 
-    pub fn check_key_correct(&mut self, key: Vec<u8>) -> bool {
+    pub fn compact(&mut self, key: &[u8], vault: &mut Vault) -> Result<PathBuf, String> {
+        // rewrites the vault into a fresh file and atomically replaces the old one, which is backed up first
+        let safety_backup = self.backup(vault, key, Some("before-compact"))?;
+        let compacted = self.path.with_extension("compact");
+        if compacted.exists() {
+            fs::remove_file(&compacted).map_err(|e| e.to_string())?;
+        }
+
+        if let Err(error) = self.write_verified_copy(&compacted, key) {
+            fs::remove_file(&compacted).unwrap_or(());
+            return Err(error);
+        }
+
+        // swap files while no connection is open
+        self.disconnect_from_db();
+        let replaced = FileManager::replace_file(&compacted, &self.path);
+        let connected = self.connect_to_db(key);
+
+        replaced.map_err(|e| e.to_string())?;
+        connected.map_err(|e| format!("Vault compacted, but it can't be opened again: {e}"))?;
+        log::info!("Compacted database.");
+        Ok(safety_backup)
+    }
+
+    fn write_verified_copy(&self, target: &Path, key: &[u8]) -> Result<(), String> {
+        // writes a compacted copy and verifies it before it gets used
+        db_interface::vacuum_into(self.connection.as_ref().unwrap(), target)
+            .map_err(|e| e.to_string())?;
+
+        let db_key = AppDBConnector::vec_key_to_hex(key);
+        let copy = db_interface::establish_connection(target, &db_key)
+            .map_err(|e| e.to_string())?;
+        let messages = db_interface::check_integrity(&copy)
+            .map_err(|e| e.to_string())?;
+
+        if messages != ["ok"] {
+            return Err(format!("Copy is damaged: {}", messages.join(", ")));
+        }
+        Ok(())
+    }
+
+    pub fn diagnose(&self, key: &[u8]) -> Result<DoctorReport, String> {
+        // runs all health checks on the vault
+        doctor::diagnose(self.connection.as_ref().unwrap(), key).map_err(|e| e.to_string())
+    }
+
+    pub fn repair(&self, key: &[u8], report: &DoctorReport, vault: &mut Vault) -> Result<Vec<String>, String> {
+        // applies all safe fixes of a report, each one after writing a verified backup
+        let mut applied = vec![];
+
+        for fix in report.fixes() {
+            let backup = self.backup(vault, key, Some(fix.backup_label()))
+                .map_err(|e| format!("Backup failed, nothing changed: {e}"))?;
+
+            let removed = doctor::apply_fix(self.connection.as_ref().unwrap(), report, fix)
+                .map_err(|e| e.to_string())?;
+            applied.push(format!(
+                "{}: {} row(s) removed, backup at {}",
+                fix.description(),
+                removed,
+                backup.display(),
+            ));
+        }
+
+        Ok(applied)
+    }
+
+    pub fn backup(&self, vault: &mut Vault, key: &[u8], label: Option<&str>) -> Result<PathBuf, String> {
+        // takes a verified backup of the open vault
+        let db_key = AppDBConnector::vec_key_to_hex(key);
+        vault.backup_vault(self.connection.as_ref().unwrap(), &db_key, label)
+    }
+
+    pub fn open_read_only(&mut self, key: &[u8]) -> Result<(), String> {
+        // opens the file without modifying it, used for browsing backups
+        let db_key = AppDBConnector::vec_key_to_hex(key);
+        let conn = db_interface::open_read_only(&self.path, &db_key)
+            .map_err(|_| "Backup can't be opened with the current master password".to_string())?;
+
+        self.connection = Some(conn);
+        log::info!("Opened backup read-only.");
+        Ok(())
+    }
+
+    pub fn restore_entry(&self, backup: &AppDBConnector, name: String, key: &[u8]) -> Result<(), String> {
+        // copies a single entry from an opened backup into the vault
+        let conn = self.connection.as_ref().unwrap();
+        let source = backup.connection.as_ref().unwrap();
+        redact::register(&name);
+
+        if !self.check_name_available(name.clone()) {
+            return Err(format!("An entry named '{name}' already exists, move it to the trash first"));
+        }
+
+        let (table_name, values) = db_interface::try_select_line(source, &name, key)
+            .map_err(|e| format!("Entry can't be read from backup: {e}"))?;
+        if !db_interface::get_all_tables(conn).contains(&table_name) {
+            return Err("The template of this entry doesn't exist anymore".to_string());
+        }
+
+        let mut elements = vec![SecretString::from(name.as_str())];
+        elements.extend(values);
+
+        let transaction = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        db_interface::insert_entry(conn, table_name, &elements, key).map_err(|e| e.to_string())?;
+        if db_interface::is_sensitive(source, name.clone()) {
+            db_interface::set_sensitive(conn, name.clone(), true).map_err(|e| e.to_string())?;
+        }
+
+        // backups of older versions have no timestamps, the entry counts as created now then
+        if let Ok(Some(entry_metadata)) = db_interface::get_metadata(source, &name) {
+            db_interface::set_metadata(conn, &name, &entry_metadata).map_err(|e| e.to_string())?;
+        }
+        transaction.commit().map_err(|e| e.to_string())?;
+
+        log::info!("Restored entry from backup.");
+        Ok(())
+    }
+
+    pub fn restore_vault(&mut self, backup: &Path, key: &[u8], vault: &mut Vault) -> Result<PathBuf, String> {
+        // replaces the whole vault with a verified backup, the current state is backed up first
+        let db_key = AppDBConnector::vec_key_to_hex(key);
+        let messages = db_interface::open_read_only(backup, &db_key)
+            .and_then(|copy| db_interface::check_integrity(&copy))
+            .map_err(|e| e.to_string())?;
+        if messages != ["ok"] {
+            return Err(format!("Backup is damaged: {}", messages.join(", ")));
+        }
+
+        let safety_backup = self.backup(vault, key, Some("before-restore"))?;
+        let restored = self.path.with_extension("restore");
+        fs::copy(backup, &restored).map_err(|e| e.to_string())?;
+
+        // the audit trail is append-only, records made after the backup are carried over
+        let audit_trail = db_interface::get_audit_records(self.connection.as_ref().unwrap())
+            .map_err(|e| e.to_string())?;
+
+        // swap files while no connection is open
+        self.disconnect_from_db();
+        let replaced = FileManager::replace_file(&restored, &self.path);
+        let connected = self.connect_to_db(key);
+
+        replaced.map_err(|e| e.to_string())?;
+        connected.map_err(|e| format!("Vault restored, but it can't be opened again: {e}"))?;
+        db_interface::append_audit_records(self.connection.as_ref().unwrap(), &audit_trail)
+            .map_err(|e| format!("Vault restored, but the audit trail could not be carried over: {e}"))?;
+        log::info!("Restored vault from backup.");
+        Ok(safety_backup)
+    }
+
+    pub fn check_key_correct(&mut self, key: &[u8]) -> bool {
         // returns a boolean weather the entered key is correct
         let db_key = AppDBConnector::vec_key_to_hex(key);
-        return db_interface::validate_key(&self.path, db_key);
+        db_interface::validate_key(&self.path, &db_key)
+    }
+
+    pub fn get_entries(&self) -> Vec<(String, String)> {
+        // gets the entry names for display (which is their id at the same time) with their template
+        match db_interface::get_entries(self.connection.as_ref().unwrap()) {
+            Ok(entries) => {
+                entries.iter().for_each(|(name, _)| redact::register(name));
+                entries
+            }
+            Err(error) => {
+                log::error!("Failed to read entries: {error}");
+                vec![]
+            }
+        }
     }
 
-    pub fn get_entry_names(&self, filter: &str) -> Vec<String> {
-        // gets the entry names for display (which is their id at the same time)
-        db_interface::filter_for_description(self.connection.as_ref().unwrap(), filter)
+    pub fn load_index(&self, templates: &[Template], key: &[u8]) -> EntryIndex {
+        // reads everything searched and shown of all entries at once, used when unlocking
+        let conn = self.connection.as_ref().unwrap();
+        let mut metadata = self.get_entry_metadata();
+        let mut tags = self.get_all_tags();
+        let favorites = self.get_favorites();
+        let usage = self.get_entry_usage();
+
+        let mut fields: HashMap<String, Vec<(String, SecretString)>> = HashMap::new();
+        for template in templates {
+            let indices = entry_index::searchable_indices(template);
+            if indices.is_empty() {
+                continue;
+            }
+            let values = db_interface::get_field_values(conn, &template.db_name, &indices, key).unwrap_or_else(|error| {
+                log::error!("Failed to read fields for searching: {error}");
+                vec![]
+            });
+            for (name, values) in values {
+                values.iter().for_each(|(_, value)| redact::register(value.as_str()));
+                fields.insert(name, values.into_iter().map(|(i, value)| (template.elements[i + 1].name.clone(), value)).collect());
+            }
+        }
+
+        let mut entries = HashMap::new();
+        for (name, template) in self.get_entries() {
+            let entry = IndexedEntry {
+                template: templates.iter().find(|t| t.db_name == template).map_or(template, |t| t.name.clone()),
+                metadata: metadata.remove(&name),
+                tags: tags.remove(&name).unwrap_or_default(),
+                favorite: favorites.contains(&name),
+                uses: usage.get(&name).copied().unwrap_or_default(),
+                fields: fields.remove(&name).unwrap_or_default(),
+            };
+            entries.insert(name, entry);
+        }
+        log::info!("Indexed {} entries.", entries.len());
+        EntryIndex::new(entries)
     }
 
-    pub fn get_entry(&self, name: String, key: Vec<u8>) -> (String, Vec<(String, String)>) {
+    pub fn load_index_entry(&self, name: &str, templates: &[Template], key: &[u8]) -> Option<IndexedEntry> {
+        // reads everything searched and shown of a single entry after it changed, None if it doesn't exist anymore
+        let conn = self.connection.as_ref().unwrap();
+        redact::register(name);
+
+        let table = match db_interface::get_entry_template(conn, name) {
+            Ok(table) => table?,
+            Err(error) => {
+                log::error!("Failed to read entry for the index: {error}");
+                return None;
+            }
+        };
+        let template = templates.iter().find(|t| t.db_name == table);
+
+        let fields = template.map_or(vec![], |template| {
+            let indices = entry_index::searchable_indices(template);
+            db_interface::get_entry_field_values(conn, &template.db_name, name, &indices, key)
+                .into_iter()
+                .map(|(i, value)| {
+                    redact::register(value.as_str());
+                    (template.elements[i + 1].name.clone(), value)
+                })
+                .collect()
+        });
+
+        Some(IndexedEntry {
+            template: template.map_or(table.clone(), |t| t.name.clone()),
+            metadata: db_interface::get_metadata(conn, name).ok().flatten(),
+            tags: db_interface::get_tags(conn, name).unwrap_or_default(),
+            favorite: db_interface::is_favorite(conn, name).unwrap_or_default(),
+            uses: db_interface::get_uses(conn, name).unwrap_or_default(),
+            fields,
+        })
+    }
+
+    pub fn get_entry(&self, name: String, key: SecretBytes) -> (String, Vec<(String, SecretString)>) {
         // returns a tuple with the template name the entry belongs to
         // and a list with the unencrypted entries names and the entries themselves
         log::info!("Selected an entry.");
-        db_interface::select_line(self.connection.as_ref().unwrap(), name, key)
+        redact::register(&name);
+        let (table_name, fields) = db_interface::select_line(self.connection.as_ref().unwrap(), name, &key);
+        fields.iter().for_each(|(_, value)| redact::register(value.as_str()));
+        (table_name, fields)
     }
 
     pub fn get_all_templates(&self) -> Vec<Template>  {
         // gets all templates
         let blobs: Vec<Vec<u8>> = db_interface::get_all_templates(self.connection.as_ref().unwrap());
 
+        // broken templates are skipped so the vault still opens and can be repaired
         blobs
             .iter()
-            .map(|t| serde_json::from_slice::<Template>(t).unwrap())
+            .filter_map(|t| match serde_json::from_slice::<Template>(t) {
+                Ok(template) => Some(template),
+                Err(error) => {
+                    log::error!("Skipped unreadable template: {error}");
+                    None
+                }
+            })
             .collect()
     }
 
-    pub fn insert_entry(&self, template_name: String, elementes: Vec<String>, key: Vec<u8>) -> bool {
+    pub fn insert_entry(&self, template_name: String, elementes: Vec<SecretString>, key: SecretBytes) -> bool {
         // inserts an entry in the correct table if unique
-        let description = elementes.first().unwrap().clone();
+        elementes.iter().for_each(|element| redact::register(element.as_str()));
+        let description = elementes.first().unwrap().to_string();
         let unique = self.check_name_available(description);
 
         if unique {
-            db_interface::insert_entry(self.connection.as_ref().unwrap(), template_name, elementes, key)
+            db_interface::insert_entry(self.connection.as_ref().unwrap(), template_name, &elementes, &key)
                 .expect("Failed to insert");
             log::info!("Created entry.");
         }
@@ -106,11 +381,267 @@ pub fn check_name_available(&self, name: String) -> bool {
         db_interface::check_name_available(self.connection.as_ref().unwrap(), name.to_string())
     }
 
-    pub fn delete_entry(&self, name: String) {
-        // tries to delete an entry if possible
+    pub fn rename_entry(&self, name: &str, new_name: &str) -> Result<(), String> {
+        // changes the name of an entry, used to move it into another folder
+        redact::register(new_name);
+        if !self.check_name_available(new_name.to_string()) {
+            return Err(format!("'{new_name}' already exists"));
+        }
+
+        db_interface::rename_entry(self.connection.as_ref().unwrap(), name, new_name).map_err(|e| e.to_string())?;
+        log::info!("Renamed entry.");
+        Ok(())
+    }
+
+    pub fn get_all_tags(&self) -> HashMap<String, Vec<String>> {
+        // tags of all entries by their name
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        match db_interface::get_all_tags(self.connection.as_ref().unwrap()) {
+            Ok(rows) => rows.into_iter().for_each(|(name, tag)| tags.entry(name).or_default().push(tag)),
+            Err(error) => log::error!("Failed to read tags: {error}"),
+        }
+        tags
+    }
+
+    pub fn set_tags(&self, name: &str, tags: &[String]) -> Result<(), String> {
+        // replaces the tags of an entry
+        db_interface::set_tags(self.connection.as_ref().unwrap(), name, tags).map_err(|e| e.to_string())?;
+        log::info!("Changed tags of entry.");
+        Ok(())
+    }
+
+    pub fn trash_entry(&self, name: String) -> Result<(), String> {
+        // moves an entry into the trash if it exists
         if !self.check_name_available(name.clone()) {
-            db_interface::delete_entry(self.connection.as_ref().unwrap(), name);
-            log::info!("Deleted entry.");
+            db_interface::trash_entry(self.connection.as_ref().unwrap(), name).map_err(|e| e.to_string())?;
+            log::info!("Moved entry to trash.");
+        }
+        Ok(())
+    }
+
+    pub fn get_trash(&self) -> Result<Vec<TrashItem>, String> {
+        // returns all trashed entries, most recently deleted first
+        let items = db_interface::get_trash(self.connection.as_ref().unwrap()).map_err(|e| e.to_string())?;
+        items.iter().for_each(|item| redact::register(&item.name));
+        Ok(items)
+    }
+
+    pub fn restore_from_trash(&self, item: &TrashItem) -> Result<String, String> {
+        // moves a trashed entry back, renamed if its name is taken by now; returns the name it got
+        let conn = self.connection.as_ref().unwrap();
+        if !db_interface::get_all_tables(conn).contains(&item.template) {
+            return Err("The template of this entry doesn't exist anymore".to_string());
+        }
+
+        let name = trash::available_name(&item.name, |name| self.check_name_available(name.to_string()));
+        redact::register(&name);
+        db_interface::restore_from_trash(conn, item.id, &name).map_err(|e| e.to_string())?;
+
+        log::info!("Restored entry from trash.");
+        Ok(name)
+    }
+
+    pub fn purge_from_trash(&self, ids: &[i64]) -> Result<usize, String> {
+        // permanently removes trashed entries
+        let purged = db_interface::purge_from_trash(self.connection.as_ref().unwrap(), ids).map_err(|e| e.to_string())?;
+        log::info!("Purged {purged} entry(s) from trash.");
+        Ok(purged)
+    }
+
+    pub fn get_expired_trash(&self, settings: &VaultSettings) -> Vec<TrashItem> {
+        // trashed entries older than the retention period of the vault
+        let Some(cutoff) = settings.trash_cutoff() else {
+            return vec![];
+        };
+
+        match db_interface::get_expired_trash(self.connection.as_ref().unwrap(), &metadata::format(cutoff)) {
+            Ok(items) => items,
+            Err(error) => {
+                log::error!("Failed to read the trash: {error}");
+                vec![]
+            }
         }
     }
-}
\ No newline at end of file
+
+    pub fn is_sensitive(&self, name: String) -> bool {
+        // checks if an entry requires the master password before revealing
+        db_interface::is_sensitive(self.connection.as_ref().unwrap(), name)
+    }
+
+    pub fn set_sensitive(&self, name: String, sensitive: bool) {
+        // marks or unmarks an entry as requiring the master password
+        db_interface::set_sensitive(self.connection.as_ref().unwrap(), name, sensitive)
+            .expect("Failed to update sensitivity");
+        log::info!("Changed sensitivity of entry.");
+    }
+
+    pub fn set_template_reauthenticate(&self, template: &Template, reauthenticate: bool) -> Result<(), String> {
+        // makes entries of a template require the master password or not
+        db_interface::set_template_reauthenticate(self.connection.as_ref().unwrap(), &template.db_name, reauthenticate)
+            .map_err(|e| e.to_string())?;
+        log::info!("Changed re-authentication of a template.");
+        Ok(())
+    }
+
+    pub fn get_favorites(&self) -> HashSet<String> {
+        // names of all favorite entries
+        match db_interface::get_favorites(self.connection.as_ref().unwrap()) {
+            Ok(names) => names.into_iter().collect(),
+            Err(error) => {
+                log::error!("Failed to read favorites: {error}");
+                HashSet::new()
+            }
+        }
+    }
+
+    pub fn set_favorite(&self, name: &str, favorite: bool) -> Result<(), String> {
+        // pins or unpins an entry at the top of the list
+        db_interface::set_favorite(self.connection.as_ref().unwrap(), name, favorite).map_err(|e| e.to_string())?;
+        log::info!("Changed favorites.");
+        Ok(())
+    }
+
+    pub fn update_field(&self, name: &str, index: usize, value: &SecretString, key: &[u8], keep_history: bool) -> Result<(), String> {
+        // stores a new value for a field of an entry, the old one is kept in its history if asked for
+        let conn = self.connection.as_ref().unwrap();
+        redact::register(value.as_str());
+
+        let (table, column) = db_interface::get_field_location(conn, name, index).map_err(|e| e.to_string())?;
+        db_interface::update_entry(conn, &table, name, &column, value, key, keep_history).map_err(|e| e.to_string())?;
+        log::info!("Changed a field of an entry.");
+        Ok(())
+    }
+
+    pub fn get_field_history(&self, name: &str, index: usize, key: &[u8]) -> Result<Vec<HistoryValue>, String> {
+        // returns the previous values of a field of an entry, newest first
+        let conn = self.connection.as_ref().unwrap();
+
+        let (table, column) = db_interface::get_field_location(conn, name, index).map_err(|e| e.to_string())?;
+        let values = db_interface::get_field_history(conn, &table, name, &column, key).map_err(|e| e.to_string())?;
+        values.iter().for_each(|v| redact::register(v.value.as_str()));
+        Ok(values)
+    }
+
+    pub fn restore_field_value(&self, id: i64) -> Result<(), String> {
+        // puts a previous value back into its field, the current one moves into the history
+        db_interface::restore_history_value(self.connection.as_ref().unwrap(), id).map_err(|e| e.to_string())?;
+        log::info!("Restored a previous value of an entry.");
+        Ok(())
+    }
+
+    pub fn prune_history(&self, settings: &VaultSettings) {
+        // removes previous values beyond the limits of the vault settings
+        let cutoff = settings.history_cutoff().map(metadata::format);
+        match db_interface::prune_history(self.connection.as_ref().unwrap(), settings.history_keep, cutoff.as_deref()) {
+            Ok(0) => {}
+            Ok(removed) => log::info!("Removed {removed} previous values from the field history."),
+            Err(error) => log::error!("Failed to prune the field history: {error}"),
+        }
+    }
+
+    pub fn get_entry_metadata(&self) -> HashMap<String, EntryMetadata> {
+        // timestamps of all entries by their name
+        match db_interface::get_all_metadata(self.connection.as_ref().unwrap()) {
+            Ok(all_metadata) => all_metadata.into_iter().collect(),
+            Err(error) => {
+                log::error!("Failed to read entry timestamps: {error}");
+                HashMap::new()
+            }
+        }
+    }
+
+    pub fn get_entry_usage(&self) -> HashMap<String, u32> {
+        // how often all used entries were revealed or copied by their name
+        match db_interface::get_usage(self.connection.as_ref().unwrap()) {
+            Ok(usage) => usage.into_iter().collect(),
+            Err(error) => {
+                log::error!("Failed to read entry usage: {error}");
+                HashMap::new()
+            }
+        }
+    }
+
+    pub fn touch_entry(&self, name: &str, timestamp: Timestamp) {
+        // sets a timestamp of an entry to now and counts reveals and copies, failing must not interrupt working with the vault
+        let conn = self.connection.as_ref().unwrap();
+        if let Err(error) = db_interface::touch_metadata(conn, name, timestamp) {
+            log::error!("Failed to update entry timestamp: {error}");
+        }
+        if timestamp != Timestamp::Modified {
+            if let Err(error) = db_interface::count_use(conn, name) {
+                log::error!("Failed to count entry usage: {error}");
+            }
+        }
+    }
+
+    pub fn record(&self, action: AuditAction, entry: Option<&str>, detail: Option<&str>) {
+        // appends to the audit trail, a failing record must not interrupt working with the vault
+        let record = AuditRecord::now(action, entry, detail);
+        if let Err(error) = db_interface::insert_audit_record(self.connection.as_ref().unwrap(), &record) {
+            log::error!("Failed to write audit record: {error}");
+        }
+    }
+
+    pub fn get_audit_trail(&self) -> Result<Vec<AuditRecord>, String> {
+        // returns the audit trail newest first, names of deleted entries are kept out of the logs too
+        let records = db_interface::get_audit_records(self.connection.as_ref().unwrap())
+            .map_err(|e| e.to_string())?;
+        records.iter().filter_map(|r| r.entry.as_deref()).for_each(redact::register);
+        Ok(records)
+    }
+
+    pub fn get_vault_settings(&self) -> VaultSettings {
+        // reads the settings stored in the vault, missing ones keep their defaults
+        match db_interface::get_settings(self.connection.as_ref().unwrap()) {
+            Ok(rows) => VaultSettings::from_rows(rows),
+            Err(error) => {
+                log::error!("Failed to read vault settings: {error}");
+                VaultSettings::default()
+            }
+        }
+    }
+
+    pub fn set_vault_settings(&self, settings: &VaultSettings) -> Result<(), String> {
+        // stores all settings in the vault at once
+        let conn = self.connection.as_ref().unwrap();
+
+        let transaction = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        for (name, value) in settings.rows() {
+            db_interface::set_setting(conn, name, &value).map_err(|e| e.to_string())?;
+        }
+        transaction.commit().map_err(|e| e.to_string())?;
+
+        log::info!("Changed vault settings");
+        Ok(())
+    }
+
+    pub fn get_generator_options(&self, template: &str, element: usize) -> Option<GeneratorOptions> {
+        // generator options last used on a field of a template, None if there are none or they are damaged
+        let name = generator_setting(template, element);
+        match db_interface::get_setting(self.connection.as_ref().unwrap(), &name) {
+            Ok(value) => value.and_then(|value| match serde_json::from_str(&value) {
+                Ok(options) => Some(options),
+                Err(_) => {
+                    log::warn!("Ignored invalid value of vault setting {name}");
+                    None
+                }
+            }),
+            Err(error) => {
+                log::error!("Failed to read generator options: {error}");
+                None
+            }
+        }
+    }
+
+    pub fn set_generator_options(&self, template: &str, element: usize, options: &GeneratorOptions) -> Result<(), String> {
+        // remembers the generator options of a field of a template in the vault settings
+        let value = serde_json::to_string(options).map_err(|e| e.to_string())?;
+        db_interface::set_setting(self.connection.as_ref().unwrap(), &generator_setting(template, element), &value)
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn generator_setting(template: &str, element: usize) -> String {
+    // name of the setting holding the generator options of a template field
+    format!("generator/{template}/{element}")
+}
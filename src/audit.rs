@@ -0,0 +1,146 @@
+use chrono::Local;
+
+use crate::metadata;
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AuditAction {
+    // what happened to an entry or the vault
+    Unlocked,
+    Viewed,
+    Revealed,
+    Copied,
+    Created,
+    Edited,
+    Deleted,
+    Purged,
+    Restored,
+    VaultRestored,
+}
+
+impl AuditAction {
+    pub fn all() -> Vec<AuditAction> {
+        // returns all actions in the order of the filter
+        vec![
+            AuditAction::Unlocked,
+            AuditAction::Viewed,
+            AuditAction::Revealed,
+            AuditAction::Copied,
+            AuditAction::Created,
+            AuditAction::Edited,
+            AuditAction::Deleted,
+            AuditAction::Purged,
+            AuditAction::Restored,
+            AuditAction::VaultRestored,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        // name stored in the vault, never change existing ones
+        match self {
+            AuditAction::Unlocked => "unlocked",
+            AuditAction::Viewed => "viewed",
+            AuditAction::Revealed => "revealed",
+            AuditAction::Copied => "copied",
+            AuditAction::Created => "created",
+            AuditAction::Edited => "edited",
+            AuditAction::Deleted => "deleted",
+            AuditAction::Purged => "purged",
+            AuditAction::Restored => "restored",
+            AuditAction::VaultRestored => "vault restored",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AuditRecord {
+    // a single row of the audit trail, time in the format of all times stored in the vault
+    pub id: i64,
+    pub time: String,
+    pub action: String,
+    pub entry: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl AuditRecord {
+    pub fn now(action: AuditAction, entry: Option<&str>, detail: Option<&str>) -> AuditRecord {
+        // creates a record of something that just happened, the id is given by the vault
+        AuditRecord {
+            id: 0,
+            time: metadata::now(),
+            action: action.name().to_string(),
+            entry: entry.map(str::to_string),
+            detail: detail.map(str::to_string),
+        }
+    }
+
+    pub fn local_time(&self) -> String {
+        // time for display in the local timezone, falls back to the stored text
+        metadata::parse(&self.time)
+            .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| self.time.clone())
+    }
+
+    pub fn matches(&self, action: Option<AuditAction>, search: &str) -> bool {
+        // checks the record against the action filter and a case insensitive search text
+        let search = search.to_lowercase();
+
+        action.is_none_or(|action| self.action == action.name())
+            && [self.entry.as_deref(), self.detail.as_deref()]
+                .into_iter()
+                .flatten()
+                .chain(std::iter::once(self.action.as_str()))
+                .any(|text| text.to_lowercase().contains(&search))
+    }
+}
+
+pub fn to_csv<'a>(records: impl Iterator<Item = &'a AuditRecord>) -> String {
+    // formats records for an export with a header line
+    let mut csv = String::from("id,time,action,entry,detail\n");
+
+    for record in records {
+        let fields = [
+            record.id.to_string(),
+            record.time.clone(),
+            record.action.clone(),
+            record.entry.clone().unwrap_or_default(),
+            record.detail.clone().unwrap_or_default(),
+        ];
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<String>>().join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    // quotes a field if needed, leading formula characters are escaped for spreadsheet programs
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_use_the_time_format_of_the_vault() {
+        let record = AuditRecord::now(AuditAction::Unlocked, None, None);
+        assert_eq!(record.time.len(), "2024-01-10T12:00:00Z".len());
+        assert!(record.time.ends_with('Z'));
+        assert_eq!(metadata::format(metadata::parse(&record.time).unwrap()), record.time);
+
+        // records written by earlier versions are still shown
+        let old = AuditRecord { time: "2024-01-10T12:00:00.123456789+00:00".to_string(), ..record };
+        assert_ne!(old.local_time(), old.time);
+    }
+}
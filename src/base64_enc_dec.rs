@@ -1,13 +1,20 @@
-use base64::{encode, decode};
+use base64::{engine::general_purpose::STANDARD, Engine};
 
-//encode() and decode() are deprecated but the reason is not of great essence here in my opinion.
-//We only use base64 to avoid SQL-injections so default mode serves us well enough.
+//We only use base64 to avoid SQL-injections so the standard engine serves us well enough.
 //Check https://github.com/marshallpierce/rust-base64/issues/213 for further information.
     
 pub fn encode_base64<T>(input: T) -> String where T: AsRef<[u8]> {
-    encode(input)
+    STANDARD.encode(input)
 }
 
 pub fn decode_base64<T>(input: T) -> String where T: AsRef<[u8]>{
-    String::from_utf8(decode(input).unwrap()).expect("Item could not be processed.")
-}
\ No newline at end of file
+    String::from_utf8(decode_base64_bytes(input)).expect("Item could not be processed.")
+}
+
+pub fn decode_base64_bytes<T>(input: T) -> Vec<u8> where T: AsRef<[u8]> {
+    STANDARD.decode(input).unwrap()
+}
+
+pub fn try_decode_base64_bytes<T>(input: T) -> Option<Vec<u8>> where T: AsRef<[u8]> {
+    STANDARD.decode(input).ok()
+}
@@ -0,0 +1,163 @@
+use std::{
+    error::Error,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+
+use crate::{
+    app_db_conn::AppDBConnector,
+    file_manager::{resolve_vault_path, FileManager},
+    key_processor::derive_key,
+    secret::SecretString,
+};
+
+
+pub const USAGE: &str = "\
+Usage: rustword_manager [OPTIONS]
+
+Options:
+  --vault <PATH>  Open the vault stored at PATH instead of the default one
+  --doctor        Check the vault for damage and offer safe fixes
+  --fix           Apply the fixes of --doctor without asking
+  -h, --help      Print this help";
+
+pub enum Command {
+    // what the application was started for
+    Run,
+    Doctor { fix: bool },
+    Help,
+}
+
+pub struct Arguments {
+    // parsed command line
+    pub command: Command,
+    pub vault: Option<PathBuf>,
+}
+
+pub fn parse_args<I>(mut args: I) -> Result<Arguments, String>
+where
+    I: Iterator<Item = String>,
+{
+    // parses the command line arguments without the programm name
+    let (mut doctor, mut fix) = (false, false);
+    let mut vault = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vault" => match args.next() {
+                Some(path) => vault = Some(resolve_vault_path(&path)),
+                None => return Err("--vault needs the path of a vault file".to_string()),
+            },
+            "--doctor" => doctor = true,
+            "--fix" => fix = true,
+            "-h" | "--help" => return Ok(Arguments { command: Command::Help, vault }),
+            _ => return Err(format!("Unknown argument '{arg}'")),
+        }
+    }
+
+    let command = match (doctor, fix) {
+        (true, fix) => Command::Doctor { fix },
+        (false, true) => return Err("--fix can only be used together with --doctor".to_string()),
+        (false, false) => Command::Run,
+    };
+
+    Ok(Arguments { command, vault })
+}
+
+pub fn run_doctor(file_manager: &mut FileManager, fix: bool) -> Result<bool, Box<dyn Error>> {
+    // checks the selected vault from the command line, returns weather it is healthy afterwards
+    if !file_manager.vault.exists() {
+        return Err(format!("No vault found at {}", file_manager.vault.path.display()).into());
+    }
+    if !file_manager.vault.acquire_lock()? {
+        return Err("The vault is opened by another instance".into());
+    }
+
+    println!("Checking vault '{}'", file_manager.vault.name);
+    let password = SecretString::new(rpassword::prompt_password("Master password: ")?);
+    println!("Deriving key...");
+    let key = derive_key(&password, &file_manager.vault.get_salt()?);
+    drop(password);
+
+    let mut db_manager = AppDBConnector::new(file_manager.vault.path.clone());
+    if !db_manager.check_key_correct(&key) {
+        return Err("Incorrect master password".into());
+    }
+    db_manager.connect_to_db(&key)?;
+
+    println!("Checking vault...\n");
+    let report = db_manager.diagnose(&key)?;
+    for line in report.lines() {
+        println!("{line}");
+    }
+
+    let fixes = report.fixes();
+    if fixes.is_empty() {
+        db_manager.disconnect_from_db();
+        return Ok(report.is_healthy());
+    }
+
+    println!("\nAvailable fixes:");
+    for fix in fixes.iter() {
+        println!("  {}", fix.description());
+    }
+
+    if !fix && !confirm("Apply these fixes? A verified backup is created before each fix. [y/N] ")? {
+        println!("Nothing changed.");
+        db_manager.disconnect_from_db();
+        return Ok(false);
+    }
+
+    println!();
+    for line in db_manager.repair(&key, &report, &mut file_manager.vault)? {
+        println!("{line}");
+    }
+
+    let healthy = db_manager.diagnose(&key)?.is_healthy();
+    db_manager.disconnect_from_db();
+
+    Ok(healthy)
+}
+
+fn confirm(question: &str) -> io::Result<bool> {
+    // asks a yes or no question on the terminal, defaults to no
+    print!("{question}");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Arguments, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert!(matches!(parse(&[]).unwrap().command, Command::Run));
+        assert!(matches!(parse(&["--doctor"]).unwrap().command, Command::Doctor { fix: false }));
+        assert!(matches!(parse(&["--fix", "--doctor"]).unwrap().command, Command::Doctor { fix: true }));
+        assert!(matches!(parse(&["--doctor", "-h"]).unwrap().command, Command::Help));
+        assert!(matches!(parse(&["--help", "--unknown"]).unwrap().command, Command::Help));
+    }
+
+    #[test]
+    fn parses_the_vault_path() {
+        let arguments = parse(&["--vault", "/tmp/work.sqlite3", "--doctor"]).unwrap();
+        assert_eq!(arguments.vault, Some(resolve_vault_path("/tmp/work.sqlite3")));
+        assert!(parse(&["--doctor"]).unwrap().vault.is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert_eq!(parse(&["--vault"]).err().unwrap(), "--vault needs the path of a vault file");
+        assert_eq!(parse(&["--fix"]).err().unwrap(), "--fix can only be used together with --doctor");
+        assert_eq!(parse(&["--doctor", "vault"]).err().unwrap(), "Unknown argument 'vault'");
+    }
+}
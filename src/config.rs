@@ -0,0 +1,833 @@
+use crate::metadata::EntrySort;
+use chrono::{DateTime, Utc};
+use log::LevelFilter;
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::{collections::HashMap, fs, io, path::Path, time::Duration};
+
+
+pub const CONFIG_NAME: &str = "config.toml";
+
+// allowed ranges of the numeric settings
+const POLL_RATE_RANGE: (u64, u64) = (10, 1000);
+const CLIPBOARD_TIMEOUT_RANGE: (u64, u64) = (1, 3600);
+const PASSWORD_LENGTH_RANGE: (u64, u64) = (8, 128);
+const LOG_SIZE_RANGE: (u64, u64) = (16, 1024 * 1024);
+const LOG_FILES_RANGE: (u64, u64) = (1, 100);
+const BACKUP_RECENT_RANGE: (u64, u64) = (1, 1000);
+const BACKUP_DAILY_RANGE: (u64, u64) = (0, 365);
+const BACKUP_WEEKLY_RANGE: (u64, u64) = (0, 520);
+const BACKUP_INTERVAL_RANGE: (u64, u64) = (0, 1440);
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    // contents of the configuration file as written by the user
+    general: GeneralSection,
+    logging: LoggingSection,
+    clipboard: ClipboardSection,
+    generator: GeneratorSection,
+    backup: BackupSection,
+    colors: ColorSection,
+    keys: KeySection,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GeneralSection {
+    poll_rate_ms: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LoggingSection {
+    level: String,
+    format: String,
+    max_size_kb: u64,
+    keep_files: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ClipboardSection {
+    timeout_secs: u64,
+    backend: String,
+    fields: HashMap<String, u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GeneratorSection {
+    password_length: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct BackupSection {
+    keep_recent: u64,
+    keep_daily: u64,
+    keep_weekly: u64,
+    interval_mins: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ColorSection {
+    text: String,
+    item: String,
+    selected: String,
+    highlight: String,
+    success: String,
+    success_dim: String,
+    danger: String,
+    danger_dim: String,
+    unfocused: String,
+    disabled: String,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeySection {
+    copy: String,
+    sensitive: String,
+    edit: String,
+    history: String,
+    folder: String,
+    tags: String,
+    favorite: String,
+    repair: String,
+    restore_vault: String,
+    purge: String,
+    new_vault: String,
+    export: String,
+    sort: String,
+    group: String,
+    filter_tag: String,
+    next_favorite: String,
+    next_recent: String,
+    copy_username: String,
+    copy_password: String,
+    copy_sequence: String,
+    clear_clipboard: String,
+    generator: String,
+}
+
+impl Default for ConfigFile {
+    fn default() -> ConfigFile {
+        // the values used before the configuration file existed
+        ConfigFile {
+            general: GeneralSection { poll_rate_ms: 100 },
+            logging: LoggingSection {
+                level: "info".to_string(),
+                format: "text".to_string(),
+                max_size_kb: 1024,
+                keep_files: 5,
+            },
+            clipboard: ClipboardSection {
+                timeout_secs: 30,
+                backend: "auto".to_string(),
+                fields: HashMap::new(),
+            },
+            generator: GeneratorSection { password_length: 24 },
+            backup: BackupSection {
+                keep_recent: 10,
+                keep_daily: 7,
+                keep_weekly: 4,
+                interval_mins: 5,
+            },
+            colors: ColorSection {
+                text: "white".to_string(),
+                item: "yellow".to_string(),
+                selected: "light-yellow".to_string(),
+                highlight: "light-blue".to_string(),
+                success: "light-green".to_string(),
+                success_dim: "green".to_string(),
+                danger: "light-red".to_string(),
+                danger_dim: "red".to_string(),
+                unfocused: "gray".to_string(),
+                disabled: "dark-gray".to_string(),
+            },
+            keys: KeySection {
+                copy: "c".to_string(),
+                sensitive: "s".to_string(),
+                edit: "e".to_string(),
+                history: "h".to_string(),
+                folder: "m".to_string(),
+                tags: "t".to_string(),
+                favorite: "f".to_string(),
+                repair: "f".to_string(),
+                restore_vault: "a".to_string(),
+                purge: "p".to_string(),
+                new_vault: "n".to_string(),
+                export: "e".to_string(),
+                sort: "o".to_string(),
+                group: "g".to_string(),
+                filter_tag: "t".to_string(),
+                next_favorite: "f".to_string(),
+                next_recent: "r".to_string(),
+                copy_username: "u".to_string(),
+                copy_password: "p".to_string(),
+                copy_sequence: "l".to_string(),
+                clear_clipboard: "x".to_string(),
+                generator: "g".to_string(),
+            },
+        }
+    }
+}
+
+impl Default for GeneralSection {
+    fn default() -> GeneralSection {
+        ConfigFile::default().general
+    }
+}
+
+impl Default for LoggingSection {
+    fn default() -> LoggingSection {
+        ConfigFile::default().logging
+    }
+}
+
+impl Default for ClipboardSection {
+    fn default() -> ClipboardSection {
+        ConfigFile::default().clipboard
+    }
+}
+
+impl Default for GeneratorSection {
+    fn default() -> GeneratorSection {
+        ConfigFile::default().generator
+    }
+}
+
+impl Default for BackupSection {
+    fn default() -> BackupSection {
+        ConfigFile::default().backup
+    }
+}
+
+impl Default for ColorSection {
+    fn default() -> ColorSection {
+        ConfigFile::default().colors
+    }
+}
+
+impl Default for KeySection {
+    fn default() -> KeySection {
+        ConfigFile::default().keys
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    // colors of the interface by their meaning
+    pub text: Color,
+    pub item: Color,
+    pub selected: Color,
+    pub highlight: Color,
+    pub success: Color,
+    pub success_dim: Color,
+    pub danger: Color,
+    pub danger_dim: Color,
+    pub unfocused: Color,
+    pub disabled: Color,
+}
+
+#[derive(Clone, Copy)]
+pub struct KeyBindings {
+    // letters triggering actions, new_vault, export and the keys of the entries list from sort on are pressed
+    // together with ctrl
+    pub copy: char,
+    pub sensitive: char,
+    pub edit: char,
+    pub history: char,
+    pub folder: char,
+    pub tags: char,
+    pub favorite: char,
+    pub repair: char,
+    pub restore_vault: char,
+    pub purge: char,
+    pub new_vault: char,
+    pub export: char,
+    pub sort: char,
+    pub group: char,
+    pub filter_tag: char,
+    pub next_favorite: char,
+    pub next_recent: char,
+    pub copy_username: char,
+    pub copy_password: char,
+    pub copy_sequence: char,
+    pub clear_clipboard: char,
+    pub generator: char,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    // how log lines are written
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClipboardKind {
+    // where copied values are put, auto picks one that works in the current session
+    Auto,
+    System,
+    Osc52,
+    Memory,
+}
+
+#[derive(Clone, Copy)]
+pub struct LogSettings {
+    // level, format and retention of the log files, max_size in bytes
+    pub level: LevelFilter,
+    pub format: LogFormat,
+    pub max_size: u64,
+    pub keep_files: u32,
+}
+
+#[derive(Clone, Copy)]
+pub struct BackupPolicy {
+    // how many automatic backups are kept and how often changes get backed up at most
+    pub keep_recent: usize,
+    pub keep_daily: usize,
+    pub keep_weekly: usize,
+    pub interval: Duration,
+}
+
+pub struct Config {
+    // validated settings of the configuration file
+    pub poll_rate: Duration,
+    pub logging: LogSettings,
+    pub clipboard_timeout: Duration,
+    pub clipboard_backend: ClipboardKind,
+    pub clipboard_fields: HashMap<String, Duration>,
+    pub password_length: usize,
+    pub backups: BackupPolicy,
+    pub theme: Theme,
+    pub keys: KeyBindings,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::validate(ConfigFile::default()).expect("Default configuration is invalid")
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Config, String> {
+        // reads the configuration file, a missing file means default settings
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+
+        toml::from_str::<ConfigFile>(&content)
+            .map_err(|e| e.to_string())
+            .and_then(Config::validate)
+            .map_err(|e| format!("Invalid configuration in {}:\n{}", path.display(), e.trim_end()))
+    }
+
+    pub fn clipboard_timeout(&self, template: &str, field: &str) -> Duration {
+        // time a copied field stays on the clipboard, its own one or the global timeout
+        self.clipboard_fields
+            .get(&format!("{template}/{field}"))
+            .copied()
+            .unwrap_or(self.clipboard_timeout)
+    }
+
+    fn validate(file: ConfigFile) -> Result<Config, String> {
+        // checks every value and converts it into its type
+        let colors = &file.colors;
+        let keys = &file.keys;
+
+        let config = Config {
+            poll_rate: Duration::from_millis(in_range(
+                "general.poll_rate_ms",
+                file.general.poll_rate_ms,
+                POLL_RATE_RANGE,
+            )?),
+            logging: LogSettings {
+                level: file.logging.level.parse().map_err(|_| {
+                    format!(
+                        "logging.level must be one of off, error, warn, info, debug or trace, got '{}'",
+                        file.logging.level
+                    )
+                })?,
+                format: match file.logging.format.as_str() {
+                    "text" => LogFormat::Text,
+                    "json" => LogFormat::Json,
+                    other => return Err(format!("logging.format must be text or json, got '{other}'")),
+                },
+                max_size: in_range("logging.max_size_kb", file.logging.max_size_kb, LOG_SIZE_RANGE)? * 1024,
+                keep_files: in_range("logging.keep_files", file.logging.keep_files, LOG_FILES_RANGE)? as u32,
+            },
+            clipboard_timeout: Duration::from_secs(in_range(
+                "clipboard.timeout_secs",
+                file.clipboard.timeout_secs,
+                CLIPBOARD_TIMEOUT_RANGE,
+            )?),
+            clipboard_backend: match file.clipboard.backend.as_str() {
+                "auto" => ClipboardKind::Auto,
+                "system" => ClipboardKind::System,
+                "osc52" => ClipboardKind::Osc52,
+                "memory" => ClipboardKind::Memory,
+                other => {
+                    return Err(format!(
+                        "clipboard.backend must be one of auto, system, osc52 or memory, got '{other}'"
+                    ))
+                }
+            },
+            clipboard_fields: file
+                .clipboard
+                .fields
+                .iter()
+                .map(|(field, secs)| {
+                    if !field.contains('/') {
+                        return Err(format!("clipboard.fields must name fields as \"Template/Field\", got '{field}'"));
+                    }
+                    let secs = in_range(&format!("clipboard.fields.\"{field}\""), *secs, CLIPBOARD_TIMEOUT_RANGE)?;
+                    Ok((field.clone(), Duration::from_secs(secs)))
+                })
+                .collect::<Result<_, String>>()?,
+            password_length: in_range(
+                "generator.password_length",
+                file.generator.password_length,
+                PASSWORD_LENGTH_RANGE,
+            )? as usize,
+            backups: BackupPolicy {
+                keep_recent: in_range("backup.keep_recent", file.backup.keep_recent, BACKUP_RECENT_RANGE)? as usize,
+                keep_daily: in_range("backup.keep_daily", file.backup.keep_daily, BACKUP_DAILY_RANGE)? as usize,
+                keep_weekly: in_range("backup.keep_weekly", file.backup.keep_weekly, BACKUP_WEEKLY_RANGE)? as usize,
+                interval: Duration::from_secs(
+                    in_range("backup.interval_mins", file.backup.interval_mins, BACKUP_INTERVAL_RANGE)? * 60,
+                ),
+            },
+            theme: Theme {
+                text: color("colors.text", &colors.text)?,
+                item: color("colors.item", &colors.item)?,
+                selected: color("colors.selected", &colors.selected)?,
+                highlight: color("colors.highlight", &colors.highlight)?,
+                success: color("colors.success", &colors.success)?,
+                success_dim: color("colors.success_dim", &colors.success_dim)?,
+                danger: color("colors.danger", &colors.danger)?,
+                danger_dim: color("colors.danger_dim", &colors.danger_dim)?,
+                unfocused: color("colors.unfocused", &colors.unfocused)?,
+                disabled: color("colors.disabled", &colors.disabled)?,
+            },
+            keys: KeyBindings {
+                copy: key("keys.copy", &keys.copy)?,
+                sensitive: key("keys.sensitive", &keys.sensitive)?,
+                edit: key("keys.edit", &keys.edit)?,
+                history: key("keys.history", &keys.history)?,
+                folder: key("keys.folder", &keys.folder)?,
+                tags: key("keys.tags", &keys.tags)?,
+                favorite: key("keys.favorite", &keys.favorite)?,
+                repair: key("keys.repair", &keys.repair)?,
+                restore_vault: key("keys.restore_vault", &keys.restore_vault)?,
+                purge: key("keys.purge", &keys.purge)?,
+                new_vault: key("keys.new_vault", &keys.new_vault)?,
+                export: key("keys.export", &keys.export)?,
+                sort: key("keys.sort", &keys.sort)?,
+                group: key("keys.group", &keys.group)?,
+                filter_tag: key("keys.filter_tag", &keys.filter_tag)?,
+                next_favorite: key("keys.next_favorite", &keys.next_favorite)?,
+                next_recent: key("keys.next_recent", &keys.next_recent)?,
+                copy_username: key("keys.copy_username", &keys.copy_username)?,
+                copy_password: key("keys.copy_password", &keys.copy_password)?,
+                copy_sequence: key("keys.copy_sequence", &keys.copy_sequence)?,
+                clear_clipboard: key("keys.clear_clipboard", &keys.clear_clipboard)?,
+                generator: key("keys.generator", &keys.generator)?,
+            },
+        };
+
+        // keys shown on the same page must not collide
+        let bindings = config.keys;
+        let entry_keys = [
+            ("keys.copy", bindings.copy),
+            ("keys.sensitive", bindings.sensitive),
+            ("keys.edit", bindings.edit),
+            ("keys.history", bindings.history),
+            ("keys.folder", bindings.folder),
+            ("keys.tags", bindings.tags),
+            ("keys.favorite", bindings.favorite),
+        ];
+        let list_keys = [
+            ("keys.sort", bindings.sort),
+            ("keys.group", bindings.group),
+            ("keys.filter_tag", bindings.filter_tag),
+            ("keys.next_favorite", bindings.next_favorite),
+            ("keys.next_recent", bindings.next_recent),
+            ("keys.copy_username", bindings.copy_username),
+            ("keys.copy_password", bindings.copy_password),
+            ("keys.copy_sequence", bindings.copy_sequence),
+        ];
+        for keys in [&entry_keys[..], &list_keys[..]] {
+            for (i, (name, binding)) in keys.iter().enumerate() {
+                if let Some((other, _)) = keys[i + 1..].iter().find(|(_, b)| b == binding) {
+                    return Err(format!("{name} and {other} are both bound to '{binding}'"));
+                }
+            }
+        }
+        if bindings.repair == bindings.restore_vault {
+            return Err(format!("keys.repair and keys.restore_vault are both bound to '{}'", bindings.repair));
+        }
+        let ctrl_keys = [
+            ("keys.new_vault", bindings.new_vault),
+            ("keys.export", bindings.export),
+            ("keys.sort", bindings.sort),
+            ("keys.group", bindings.group),
+            ("keys.filter_tag", bindings.filter_tag),
+            ("keys.next_favorite", bindings.next_favorite),
+            ("keys.next_recent", bindings.next_recent),
+            ("keys.copy_username", bindings.copy_username),
+            ("keys.copy_password", bindings.copy_password),
+            ("keys.copy_sequence", bindings.copy_sequence),
+            ("keys.generator", bindings.generator),
+        ];
+        for (name, binding) in ctrl_keys.into_iter().chain([("keys.clear_clipboard", bindings.clear_clipboard)]) {
+            if let Some(reason) = terminal_ctrl_key(binding) {
+                return Err(format!("{name} can't be '{binding}', {reason}, choose another key"));
+            }
+        }
+        // clearing the clipboard works on every page
+        if let Some((name, _)) = ctrl_keys.iter().find(|(_, b)| *b == bindings.clear_clipboard) {
+            return Err(format!(
+                "keys.clear_clipboard and {name} are both bound to '{}'",
+                bindings.clear_clipboard
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+fn terminal_ctrl_key(binding: char) -> Option<&'static str> {
+    // keys that never reach the application together with ctrl, as terminals use them or send them as other keys
+    match binding {
+        'c' | 'z' => Some("ctrl+c and ctrl+z are used by the terminal"),
+        'h' => Some("terminals send ctrl+h as Backspace"),
+        'i' => Some("terminals send ctrl+i as Tab"),
+        'm' | 'j' => Some("terminals send ctrl+m and ctrl+j as Enter"),
+        _ => None,
+    }
+}
+
+fn in_range(name: &str, value: u64, (min, max): (u64, u64)) -> Result<u64, String> {
+    // checks a numeric setting against its allowed range
+    if (min..=max).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("{name} must be between {min} and {max}, got {value}"))
+    }
+}
+
+fn color(name: &str, value: &str) -> Result<Color, String> {
+    // accepts color names like "light-yellow", "#rrggbb" and indexes of the 256 color palette
+    value.parse().map_err(|_| {
+        format!("{name} must be a color name, a hex color like \"#ffaa00\" or a number up to 255, got '{value}'")
+    })
+}
+
+fn key(name: &str, value: &str) -> Result<char, String> {
+    // accepts a single lowercase letter or digit
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_lowercase() || c.is_ascii_digit() => Ok(c),
+        _ => Err(format!("{name} must be a single lowercase letter or digit, got '{value}'")),
+    }
+}
+
+// steps offered when changing the vault settings, 0 disables the auto-lock
+const AUTO_LOCK_STEPS: [u64; 8] = [0, 1, 2, 5, 10, 15, 30, 60];
+const MIN_PASSWORD_LENGTH_STEP: usize = 4;
+// steps of the field history, 0 days keeps previous values forever
+const HISTORY_KEEP_STEPS: [usize; 7] = [1, 3, 5, 10, 20, 50, 100];
+const HISTORY_DAYS_STEPS: [u64; 7] = [0, 7, 30, 90, 180, 365, 730];
+// steps of the trash retention, 0 days keeps deleted entries until they get purged by hand
+const TRASH_DAYS_STEPS: [u64; 8] = [0, 1, 7, 14, 30, 60, 90, 365];
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct VaultSettings {
+    // settings stored inside the encrypted vault
+    pub auto_lock_minutes: u64,
+    pub min_password_length: usize,
+    pub password_symbols: bool,
+    pub history_keep: usize,
+    pub history_days: u64,
+    pub trash_days: u64,
+    // how the entries list was shown last time, changed from the list instead of the settings page
+    pub entry_sort: EntrySort,
+    pub group_by_template: bool,
+}
+
+impl Default for VaultSettings {
+    fn default() -> VaultSettings {
+        VaultSettings {
+            auto_lock_minutes: 10,
+            min_password_length: 16,
+            password_symbols: true,
+            history_keep: 10,
+            history_days: 0,
+            trash_days: 30,
+            entry_sort: EntrySort::Name,
+            group_by_template: false,
+        }
+    }
+}
+
+impl VaultSettings {
+    pub fn from_rows(rows: Vec<(String, String)>) -> VaultSettings {
+        // reads the stored rows, unknown or damaged values keep their defaults
+        let mut settings = VaultSettings::default();
+
+        for (name, value) in rows {
+            let valid = match name.as_str() {
+                "auto_lock_minutes" => value.parse().map(|v| settings.auto_lock_minutes = v).is_ok(),
+                "min_password_length" => value
+                    .parse()
+                    .ok()
+                    .filter(|v| (PASSWORD_LENGTH_RANGE.0..=PASSWORD_LENGTH_RANGE.1).contains(&(*v as u64)))
+                    .map(|v| settings.min_password_length = v)
+                    .is_some(),
+                "password_symbols" => value.parse().map(|v| settings.password_symbols = v).is_ok(),
+                "history_keep" => value
+                    .parse()
+                    .ok()
+                    .filter(|v| *v > 0)
+                    .map(|v| settings.history_keep = v)
+                    .is_some(),
+                "history_days" => value.parse().map(|v| settings.history_days = v).is_ok(),
+                "trash_days" => value.parse().map(|v| settings.trash_days = v).is_ok(),
+                "entry_sort" => EntrySort::from_key(&value).map(|v| settings.entry_sort = v).is_some(),
+                "group_by_template" => value.parse().map(|v| settings.group_by_template = v).is_ok(),
+                _ => true,
+            };
+
+            if !valid {
+                log::warn!("Ignored invalid value of vault setting {name}");
+            }
+        }
+
+        settings
+    }
+
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        // settings as stored in the vault
+        vec![
+            ("auto_lock_minutes", self.auto_lock_minutes.to_string()),
+            ("min_password_length", self.min_password_length.to_string()),
+            ("password_symbols", self.password_symbols.to_string()),
+            ("history_keep", self.history_keep.to_string()),
+            ("history_days", self.history_days.to_string()),
+            ("trash_days", self.trash_days.to_string()),
+            ("entry_sort", self.entry_sort.key().to_string()),
+            ("group_by_template", self.group_by_template.to_string()),
+        ]
+    }
+
+    pub fn auto_lock(&self) -> Option<Duration> {
+        // time without input after which the vault gets locked
+        (self.auto_lock_minutes > 0).then(|| Duration::from_secs(self.auto_lock_minutes * 60))
+    }
+
+    pub fn password_length(&self, configured: usize) -> usize {
+        // generated passwords are never shorter than the policy of the vault
+        configured.max(self.min_password_length)
+    }
+
+    pub fn history_cutoff(&self) -> Option<DateTime<Utc>> {
+        // previous values replaced before this time get removed
+        (self.history_days > 0).then(|| Utc::now() - chrono::Duration::days(self.history_days as i64))
+    }
+
+    pub fn trash_cutoff(&self) -> Option<DateTime<Utc>> {
+        // entries moved to the trash before this time get purged
+        (self.trash_days > 0).then(|| Utc::now() - chrono::Duration::days(self.trash_days as i64))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VaultSetting {
+    // entries of the vault settings list
+    AutoLock,
+    MinPasswordLength,
+    PasswordSymbols,
+    HistoryKeep,
+    HistoryDays,
+    TrashDays,
+}
+
+impl VaultSetting {
+    pub fn all() -> Vec<VaultSetting> {
+        // returns all settings in display order
+        vec![
+            VaultSetting::AutoLock,
+            VaultSetting::MinPasswordLength,
+            VaultSetting::PasswordSymbols,
+            VaultSetting::HistoryKeep,
+            VaultSetting::HistoryDays,
+            VaultSetting::TrashDays,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        // title shown in the settings list
+        match self {
+            VaultSetting::AutoLock => "Auto-lock after",
+            VaultSetting::MinPasswordLength => "Minimum length of generated passwords",
+            VaultSetting::PasswordSymbols => "Generated passwords contain symbols",
+            VaultSetting::HistoryKeep => "Previous values kept per field",
+            VaultSetting::HistoryDays => "Keep previous values for",
+            VaultSetting::TrashDays => "Purge deleted entries after",
+        }
+    }
+
+    pub fn value(&self, settings: &VaultSettings) -> String {
+        // current value in a readable form
+        match self {
+            VaultSetting::AutoLock => match settings.auto_lock_minutes {
+                0 => "never".to_string(),
+                1 => "1 minute".to_string(),
+                minutes => format!("{minutes} minutes"),
+            },
+            VaultSetting::MinPasswordLength => format!("{} characters", settings.min_password_length),
+            VaultSetting::PasswordSymbols => if settings.password_symbols { "yes" } else { "no" }.to_string(),
+            VaultSetting::HistoryKeep => settings.history_keep.to_string(),
+            VaultSetting::HistoryDays => match settings.history_days {
+                0 => "forever".to_string(),
+                1 => "1 day".to_string(),
+                days => format!("{days} days"),
+            },
+            VaultSetting::TrashDays => match settings.trash_days {
+                0 => "never".to_string(),
+                1 => "1 day".to_string(),
+                days => format!("{days} days"),
+            },
+        }
+    }
+
+    pub fn change(&self, settings: &mut VaultSettings, increase: bool) {
+        // moves the setting one step up or down
+        match self {
+            VaultSetting::AutoLock => settings.auto_lock_minutes = step(&AUTO_LOCK_STEPS, settings.auto_lock_minutes, increase),
+            VaultSetting::MinPasswordLength => {
+                let (min, max) = PASSWORD_LENGTH_RANGE;
+                settings.min_password_length = if increase {
+                    (settings.min_password_length + MIN_PASSWORD_LENGTH_STEP).min(max as usize)
+                } else {
+                    settings.min_password_length.saturating_sub(MIN_PASSWORD_LENGTH_STEP).max(min as usize)
+                };
+            }
+            VaultSetting::PasswordSymbols => settings.password_symbols = !settings.password_symbols,
+            VaultSetting::HistoryKeep => settings.history_keep = step(&HISTORY_KEEP_STEPS, settings.history_keep, increase),
+            VaultSetting::HistoryDays => settings.history_days = step(&HISTORY_DAYS_STEPS, settings.history_days, increase),
+            VaultSetting::TrashDays => settings.trash_days = step(&TRASH_DAYS_STEPS, settings.trash_days, increase),
+        }
+    }
+}
+
+fn step<T: Copy + PartialOrd>(steps: &[T], current: T, increase: bool) -> T {
+    // the next larger or smaller step, stays at the current value at the ends
+    let next = if increase {
+        steps.iter().find(|step| **step > current)
+    } else {
+        steps.iter().rev().find(|step| **step < current)
+    };
+    next.copied().unwrap_or(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<Config, String> {
+        toml::from_str::<ConfigFile>(content).map_err(|e| e.to_string()).and_then(Config::validate)
+    }
+
+    #[test]
+    fn backup_policy_is_configurable() {
+        let config = parse("[backup]\nkeep_recent = 3\nkeep_daily = 0\ninterval_mins = 0").unwrap();
+        assert_eq!(config.backups.keep_recent, 3);
+        assert_eq!(config.backups.keep_daily, 0);
+        assert_eq!(config.backups.keep_weekly, 4);
+        assert_eq!(config.backups.interval, Duration::ZERO);
+    }
+
+    #[test]
+    fn backup_policy_is_validated() {
+        assert_eq!(
+            parse("[backup]\nkeep_recent = 0").err().unwrap(),
+            "backup.keep_recent must be between 1 and 1000, got 0",
+        );
+        assert!(parse("[backup]\ninterval_mins = 1441").is_err());
+        assert!(parse("[backup]\nkeep_monthly = 2").is_err());
+    }
+
+    #[test]
+    fn rejects_ctrl_keys_the_terminal_takes() {
+        assert_eq!(
+            parse("[keys]\nsort = \"h\"").err().unwrap(),
+            "keys.sort can't be 'h', terminals send ctrl+h as Backspace, choose another key",
+        );
+        for key in ["c", "z", "i", "m", "j"] {
+            assert!(parse(&format!("[keys]\ngenerator = \"{key}\"")).is_err(), "{key}");
+            assert!(parse(&format!("[keys]\nclear_clipboard = \"{key}\"")).is_err(), "{key}");
+        }
+        assert!(parse("[keys]\nsort = \"k\"").is_ok());
+    }
+
+    #[test]
+    fn empty_file_means_defaults() {
+        let config = parse("").unwrap();
+        let defaults = Config::default();
+        assert_eq!(config.poll_rate, defaults.poll_rate);
+        assert_eq!(config.password_length, defaults.password_length);
+        assert_eq!(config.keys.copy, defaults.keys.copy);
+        assert!(Config::load(Path::new("/nonexistent/rustword_manager/config.toml")).is_ok());
+    }
+
+    #[test]
+    fn numbers_are_checked_against_their_range() {
+        assert_eq!(
+            parse("[general]\npoll_rate_ms = 0").err().unwrap(),
+            format!("general.poll_rate_ms must be between {} and {}, got 0", POLL_RATE_RANGE.0, POLL_RATE_RANGE.1),
+        );
+        let too_long = PASSWORD_LENGTH_RANGE.1 + 1;
+        assert!(parse(&format!("[generator]\npassword_length = {too_long}")).is_err());
+        assert!(parse("[clipboard]\ntimeout_secs = -1").is_err());
+    }
+
+    #[test]
+    fn names_are_checked_against_their_choices() {
+        assert!(parse("[logging]\nlevel = \"loud\"").err().unwrap().starts_with("logging.level must be one of"));
+        assert_eq!(parse("[logging]\nformat = \"xml\"").err().unwrap(), "logging.format must be text or json, got 'xml'");
+        assert!(parse("[clipboard]\nbackend = \"x11\"").is_err());
+        assert!(parse("[colors]\ntext = \"#12345\"").is_err());
+        assert!(parse("[colors]\ntext = \"#ffaa00\"\nitem = \"light-yellow\"\nselected = \"208\"").is_ok());
+        assert!(parse("[general]\nunknown = 1").is_err());
+    }
+
+    #[test]
+    fn clipboard_timeouts_per_field() {
+        let config = parse("[clipboard]\ntimeout_secs = 20\n[clipboard.fields]\n\"Banking/IBAN\" = 5").unwrap();
+        assert_eq!(config.clipboard_timeout("Banking", "IBAN"), Duration::from_secs(5));
+        assert_eq!(config.clipboard_timeout("Banking", "Bank"), Duration::from_secs(20));
+        assert!(parse("[clipboard.fields]\nIBAN = 5").is_err());
+    }
+
+    #[test]
+    fn keys_are_single_characters_without_collisions() {
+        assert_eq!(parse("[keys]\ncopy = \"C\"").err().unwrap(), "keys.copy must be a single lowercase letter or digit, got 'C'");
+        assert!(parse("[keys]\ncopy = \"cc\"").is_err());
+
+        let defaults = Config::default().keys;
+        assert_eq!(
+            parse(&format!("[keys]\ncopy = \"{}\"", defaults.edit)).err().unwrap(),
+            format!("keys.copy and keys.edit are both bound to '{}'", defaults.edit),
+        );
+        assert!(parse(&format!("[keys]\nclear_clipboard = \"{}\"", defaults.generator)).is_err());
+        // keys of different pages may share a character
+        assert!(parse(&format!("[keys]\nsort = \"{}\"", defaults.edit)).is_ok());
+    }
+}
@@ -1,60 +1,212 @@
-use std::path::Path;
-use crate::aes_impl::{decrypt_aesgcm, encrypt_aesgcm, nonce_generator, u12_from_slice, u32_from_slice};
-use crate::base64_enc_dec::{encode_base64, decode_base64};
+use std::{path::Path, time::Duration};
+use crate::aes_impl::{encrypt_aesgcm, nonce_generator, decrypt_aesgcm, u12_from_slice, u32_from_slice};
+use crate::base64_enc_dec::{encode_base64, decode_base64, decode_base64_bytes, try_decode_base64_bytes};
 
-use rusqlite::{Connection, params, Result};
+use rusqlite::{backup::Backup, Connection, OpenFlags, OptionalExtension, params, params_from_iter, Result};
 use aes_gcm::aead::generic_array::GenericArray;
 use typenum::{U12, U32};
-
-use crate::password::generate_char_only_password;
+use zeroize::Zeroize;
+
+use crate::{
+    audit::AuditRecord,
+    history::HistoryValue,
+    metadata::{self, EntryMetadata, Timestamp},
+    password::generate_char_only_password,
+    secret::SecretString,
+    trash::{self, TrashItem},
+    types::FieldValues,
+};
 
 const SQL_INITIALIZE: &str = include_str!("./sql/initiate.sql");
+const SQL_MIGRATE: &str = include_str!("./sql/migrate.sql");
+
+const BACKEND_TABLES: [&str; 14] = [
+    "sqlite_sequence", "templates", "nonces", "descriptions", "sensitive_entries", "settings", "audit_log", "entry_metadata",
+    "field_history", "trash", "trash_values", "entry_tags", "favorite_entries", "entry_usage",
+];
 
 
-pub fn create_database(path: &Box<Path>) -> Connection {
+pub fn create_database(path: &Path) -> Connection {
     //Used when first creating a file; returns connection
     let conn: Connection = Connection::open(path)
         .expect("Failed to create db");
 
     // sent temporary key for protection while initializing
-    let temp_key = generate_char_only_password(32);
-    conn.execute_batch(&format!("PRAGMA key = '{}'", temp_key))
+    let temp_key = SecretString::new(generate_char_only_password(32));
+    conn.execute_batch(&SecretString::new(format!("PRAGMA key = '{}'", temp_key.as_str())))
         .expect("Failed to set key");
 
     // fill database with default config from .sql file
     conn.execute_batch(SQL_INITIALIZE)
         .expect("Failed to initialize database");
+    migrate_database(&conn)
+        .expect("Failed to migrate database");
+    apply_secure_settings(&conn)
+        .expect("Failed to apply database settings");
+
+    conn
+}
+
+pub fn apply_secure_settings(conn: &Connection) -> Result<()> {
+    //Zeroes deleted content inside the file. The rollback journal stays on disk so a crash in the middle
+    //of a write can be rolled back, its pages are encrypted by SQLCipher like the vault and it gets
+    //deleted after every transaction. WAL is avoided as it keeps previous versions of pages around
+    //until a checkpoint. These settings only live as long as the connection.
+    conn.pragma_update(None, "secure_delete", "ON")?;
+    conn.pragma_update_and_check(None, "journal_mode", "DELETE", |row| row.get::<usize, String>(0))?;
+    conn.pragma_update(None, "temp_store", "MEMORY")?;
+    Ok(())
+}
+
+pub fn vacuum_into(conn: &Connection, target: &Path) -> Result<()> {
+    //Writes a compacted copy without free pages to target. SQLCipher keeps key and salt for the copy.
+    let target = target.to_string_lossy().replace('\'', "''");
+    conn.execute_batch(&format!("VACUUM INTO '{}'", target))
+}
+
+pub fn backup_into(conn: &Connection, target: &Path, db_key: &str, salt: &[u8]) -> Result<()> {
+    //Copies the database with SQLite's online backup API. The copy is encrypted with the same key and
+    //salt before any page is written, so the master password it was taken with can open it.
+    let salt_hex: String = salt.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let mut copy = Connection::open(target)?;
+    copy.execute_batch(&SecretString::new(format!("PRAGMA key = '{}'", db_key)))?;
+    copy.execute_batch(&format!("PRAGMA cipher_salt = \"x'{}'\"", salt_hex))?;
+
+    let backup = Backup::new(conn, &mut copy)?;
+    backup.run_to_completion(64, Duration::ZERO, None)
+}
+
+pub fn open_read_only(db_path: &Path, db_key: &str) -> Result<Connection> {
+    //Opens a database without being able to modify it, e.g. a backup. Fails if the key is wrong.
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.execute_batch(&SecretString::new(format!("PRAGMA key = '{}'", db_key)))?;
+
+    let _: u32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master", params![], |row| row.get(0))?;
+    Ok(conn)
+}
+
+pub fn check_integrity(conn: &Connection) -> Result<Vec<String>> {
+    //Returns the messages of SQLite's integrity check, which is a single "ok" for a healthy database.
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let messages = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+    Ok(messages)
+}
+
+pub fn check_cipher_integrity(conn: &Connection) -> Result<Vec<String>> {
+    //Verifies the HMAC of every page. SQLCipher only returns rows for pages that failed the check.
+    let mut stmt = conn.prepare("PRAGMA cipher_integrity_check")?;
+    let messages = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+    Ok(messages)
+}
+
+pub fn get_entry_tables_encoded(conn: &Connection) -> Result<Vec<String>> {
+    //get_all_tables() but names stay base64-encoded, so broken names don't panic.
+    let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type='table'")?;
+    let table_names = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+
+    Ok(table_names.into_iter().filter(|name| !BACKEND_TABLES.contains(&name.as_str())).collect())
+}
 
-    return conn;
+pub fn get_descriptions_from_table(conn: &Connection, encoded_table: &str) -> Result<Vec<String>> {
+    //Returns the base64-encoded descriptions of all entries stored in a table.
+    let mut stmt = conn.prepare(&format!("SELECT description FROM \"{}\"", encoded_table))?;
+    let descriptions = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+    Ok(descriptions)
 }
 
-pub fn change_password(conn: &Connection, new_key: String) {
+pub fn get_template_structures(conn: &Connection) -> Result<Vec<(String, Vec<u8>)>> {
+    //Returns the names and raw structures of all templates, get_all_templates() without panicking.
+    let mut stmt = conn.prepare("SELECT name, structure FROM templates")?;
+    let templates = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<(String, Vec<u8>)>>>()?;
+    Ok(templates)
+}
+
+pub fn find_orphan_nonces(conn: &Connection) -> Result<Vec<String>> {
+    //Returns all nonces whose entry no longer exists.
+    let mut stmt = conn.prepare("SELECT nonce FROM nonces n WHERE NOT EXISTS \
+        (SELECT 1 FROM descriptions d WHERE d.description = n.orig_desc AND d.template = n.orig_table)")?;
+    let nonces = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+    Ok(nonces)
+}
+
+pub fn find_dangling_descriptions(conn: &Connection) -> Result<Vec<String>> {
+    //Returns all base64-encoded descriptions pointing to a table that doesn't exist.
+    let mut stmt = conn.prepare("SELECT description FROM descriptions WHERE template NOT IN \
+        (SELECT name FROM sqlite_master WHERE type='table')")?;
+    let descriptions = stmt.query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+    Ok(descriptions)
+}
+
+pub fn delete_nonces(conn: &Connection, nonces: &[String]) -> Result<usize> {
+    //Removes the given nonces in a single transaction.
+    let transaction = conn.unchecked_transaction()?;
+    let mut deleted = 0;
+    for nonce in nonces {
+        deleted += conn.execute("DELETE FROM nonces WHERE nonce = ?1", params![nonce])?;
+    }
+    transaction.commit()?;
+    Ok(deleted)
+}
+
+pub fn delete_descriptions(conn: &Connection, descriptions: &[String]) -> Result<usize> {
+    //Removes base64-encoded descriptions together with their nonces, history and flags in a single transaction.
+    let transaction = conn.unchecked_transaction()?;
+    let mut deleted = 0;
+    for description in descriptions {
+        conn.execute("DELETE FROM nonces WHERE orig_desc = ?1 AND orig_table = \
+            (SELECT template FROM descriptions WHERE description = ?1)", params![description])?;
+        conn.execute("DELETE FROM field_history WHERE orig_desc = ?1 AND orig_table = \
+            (SELECT template FROM descriptions WHERE description = ?1)", params![description])?;
+        conn.execute("DELETE FROM sensitive_entries WHERE description = ?1", params![description])?;
+        conn.execute("DELETE FROM entry_tags WHERE description = ?1", params![description])?;
+        conn.execute("DELETE FROM favorite_entries WHERE description = ?1", params![description])?;
+        conn.execute("DELETE FROM entry_usage WHERE description = ?1", params![description])?;
+        deleted += conn.execute("DELETE FROM descriptions WHERE description = ?1", params![description])?;
+    }
+    transaction.commit()?;
+    Ok(deleted)
+}
+
+pub fn migrate_database(conn: &Connection) -> Result<()> {
+    //Brings databases created by older versions up to the current structure.
+    conn.execute_batch(SQL_MIGRATE)
+}
+
+pub fn change_password(conn: &Connection, new_key: &str) {
     //Only called during database creation; sets a new password for the database.
-    conn.execute_batch(&format!("PRAGMA rekey = '{}'", new_key))
+    conn.execute_batch(&SecretString::new(format!("PRAGMA rekey = '{}'", new_key)))
         .expect("Failed to change key");
 }
 
-pub fn establish_connection(db_path: &Box<Path>, db_key: String) -> Result<Connection, rusqlite::Error> {
+pub fn establish_connection(db_path: &Path, db_key: &str) -> Result<Connection, rusqlite::Error> {
     //Connect to database. Will return an Error if it didn't work.
     let conn = Connection::open(db_path)?;
 
-    conn.execute_batch(&format!("PRAGMA key = '{}'", db_key))
+    conn.execute_batch(&SecretString::new(format!("PRAGMA key = '{}'", db_key)))
         .expect("Failed to set encryption key");
 
     //If the database was not decrypted successfully, this operation will fail and throw an Error. Else, it will work.
     let _: u32 = conn.query_row("SELECT COUNT(*) FROM sqlite_master", params![], |row| row.get(0))?;
+    migrate_database(&conn)?;
+    apply_secure_settings(&conn)?;
 
     Ok(conn)
 }
 
-pub fn validate_key(db_path: &Box<Path>, db_key: String) -> bool {
+pub fn validate_key(db_path: &Path, db_key: &str) -> bool {
     // Returns true if given key is valid.
-    let key = db_key;
     //logger::init_logger(&format!("RustwordManager_{}.log", Utc::now().format("%Y%m%d_%H%M%S"))); //PUT THIS INTO main.rs
 
     let conn = Connection::open(db_path).expect("");
 
-    conn.execute_batch(&format!("PRAGMA key = '{}'", key))
+    conn.execute_batch(&SecretString::new(format!("PRAGMA key = '{}'", db_key)))
         .expect("Failed to set encryption key");
 
     //Should be 0; default query to check if decryption failed; writing to _ is necessary because of row.get()
@@ -71,8 +223,8 @@ pub fn get_all_tables(conn: &Connection) -> Vec<String> {
 
     let filtered_table_names: Vec<String> = table_names
         .into_iter()
-        .filter(|table_name| table_name.to_string() != "sqlite_sequence" && table_name.to_string() != "templates" && table_name.to_string() != "nonces" && table_name.to_string() != "descriptions") //Exclude backend-only tables
-        .map(|table_name| decode_base64(table_name))
+        .filter(|table_name| !BACKEND_TABLES.contains(&table_name.as_str())) //Exclude backend-only tables
+        .map(decode_base64)
         .collect();
 
     filtered_table_names
@@ -85,7 +237,7 @@ pub fn get_columns_from_table(conn: &Connection, table_name: &str) -> Vec<String
         .collect::<Result<Vec<String>, _>>()
         .expect("Failed to collect results.");
 
-    let filtered_column_names: Vec<String> = column_names.into_iter().filter(|column| column.to_string() != "description")
+    let filtered_column_names: Vec<String> = column_names.into_iter().filter(|column| *column != "description")
         .collect();
     filtered_column_names
 }
@@ -102,28 +254,60 @@ pub fn _get_all_columns_total(conn: &Connection) -> Vec<String> {
     all_columns
 }
 
-pub fn filter_for_description(conn: &Connection, input: &str) -> Vec<String> { // %<Word>% is a before-and-after wildcard in SQL.
-    // Returns all descriptions from all tables that match a given filter. Can handle empty input and can also return an empty Vec.
-    let mut return_vec: Vec<String> = vec![];
-
-    let all_tables: Vec<String> = get_all_tables(conn);
-    let mut all_descriptions: Vec<String> = vec![];
-
-    for table in &all_tables {
-        let mut stmt = conn.prepare(&format!("SELECT description FROM \"{}\"", encode_base64(table))).expect("");
-        let descriptions_from_table = stmt.query_map([], |row| row.get(0)).expect("").collect::<Result<Vec<String>>>().expect("");
-        for description in descriptions_from_table {
-            all_descriptions.push(decode_base64(description));
+pub fn get_entries(conn: &Connection) -> Result<Vec<(String, String)>> {
+    //Returns the decoded name and template of every entry in a single query; damaged names are skipped.
+    let mut stmt = conn.prepare("SELECT description, template FROM descriptions")?;
+    let rows = stmt.query_map(params![], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+
+    let decode = |encoded: &str| try_decode_base64_bytes(encoded).and_then(|b| String::from_utf8(b).ok());
+    let mut entries = vec![];
+    for row in rows {
+        let (description, template) = row?;
+        if let (Some(name), Some(template)) = (decode(&description), decode(&template)) {
+            entries.push((name, template));
         }
     }
+    Ok(entries)
+}
 
-    for desc in all_descriptions.iter() {
-        if desc.to_lowercase().contains(&input.to_lowercase()) {
-            return_vec.push(desc.to_string());
-        }
+pub fn get_entry_template(conn: &Connection, description: &str) -> Result<Option<String>> {
+    //Returns the decoded template of an entry, None if the entry doesn't exist or the template name is damaged.
+    let template: Option<String> = conn.query_row("SELECT template FROM descriptions WHERE description = ?1", params![encode_base64(description)], |row| row.get(0))
+        .optional()?;
+    Ok(template.and_then(try_decode_base64_bytes).and_then(|b| String::from_utf8(b).ok()))
+}
+
+pub fn get_field_values(conn: &Connection, table_name: &str, indices: &[usize], key: &[u8]) -> Result<Vec<FieldValues>> {
+    //Returns the DECRYPTED values of the fields at the given indices for every entry of a template, the description
+    //not counted. Damaged values and names are skipped.
+    let columns = get_columns_from_table(conn, &encode_base64(table_name));
+    let mut stmt = conn.prepare("SELECT description FROM descriptions WHERE template = ?1")?;
+    let descriptions = stmt.query_map(params![encode_base64(table_name)], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>>>()?;
+
+    let mut entries = vec![];
+    for description in descriptions {
+        let Some(name) = try_decode_base64_bytes(&description).and_then(|b| String::from_utf8(b).ok()) else {
+            continue;
+        };
+        let values = select_fields(conn, table_name, &columns, &name, indices, key);
+        entries.push((name, values));
     }
+    Ok(entries)
+}
 
-    return_vec
+pub fn get_entry_field_values(conn: &Connection, table_name: &str, description: &str, indices: &[usize], key: &[u8]) -> Vec<(usize, SecretString)> {
+    //get_field_values() for a single entry.
+    let columns = get_columns_from_table(conn, &encode_base64(table_name));
+    select_fields(conn, table_name, &columns, description, indices, key)
+}
+
+fn select_fields(conn: &Connection, table_name: &str, columns: &[String], description: &str, indices: &[usize], key: &[u8]) -> Vec<(usize, SecretString)> {
+    //Decrypts the fields at the given indices of an entry, damaged values are skipped.
+    indices
+        .iter()
+        .filter_map(|index| Some((*index, try_select_entry(conn, table_name, description, columns.get(*index)?, key).ok()?)))
+        .collect()
 }
 
 pub fn get_all_templates(conn: &Connection) -> Vec<Vec<u8>> {
@@ -136,20 +320,35 @@ pub fn get_all_templates(conn: &Connection) -> Vec<Vec<u8>> {
     templates_structures
 }
 
-pub fn select_line(conn: &Connection, description: String, key: Vec<u8>) -> (String, Vec<(String, String)>) {
+pub fn select_line(conn: &Connection, description: String, key: &[u8]) -> (String, Vec<(String, SecretString)>) {
     // Dangerous: Returns table where description is found and also all columns with their corresponding DECRYPTED values. Might have to change.
     let encoded_table: String = conn.query_row(&format!("SELECT template FROM descriptions WHERE description = '{}'", encode_base64(&description)), params![], |row| row.get(0)).expect("");
     //let mut stmt = conn.prepare(&format!("SELECT * FROM \"{}\" WHERE description = '{}'", encoded_table, encode_base64(description))).expect("");
     //let args: Vec<String> = stmt.query_map([], |row| row.get(0)).expect("").collect::<Result<Vec<String>>>().expect("");
     let cols: Vec<String> = get_columns_from_table(conn, &encoded_table);
-    let mut combined_vec: Vec<(String, String)> = vec![];
+    let mut combined_vec: Vec<(String, SecretString)> = vec![];
     for col in cols.iter() { //Skip description
-        combined_vec.push((decode_base64(col), select_entry(conn, decode_base64(&encoded_table), description.clone(), col.to_string(), key.clone())))
+        combined_vec.push((decode_base64(col), select_entry(conn, decode_base64(&encoded_table), description.clone(), col.to_string(), key)))
     };
 
     (decode_base64(encoded_table), combined_vec)
 }
 
+pub fn try_select_line(conn: &Connection, description: &str, key: &[u8]) -> Result<(String, Vec<SecretString>), String> {
+    //select_line() but fails instead of panicking or returning placeholders for damaged values.
+    let encoded_table: String = conn.query_row(&format!("SELECT template FROM descriptions WHERE description = '{}'", encode_base64(description)), params![], |row| row.get(0))
+        .map_err(|_| "entry not found".to_string())?;
+    let table_name = String::from_utf8(try_decode_base64_bytes(&encoded_table).ok_or("template name is not valid base64")?)
+        .map_err(|_| "template name is not readable".to_string())?;
+
+    let mut values: Vec<SecretString> = vec![];
+    for col in get_columns_from_table(conn, &encoded_table).iter() {
+        values.push(try_select_entry(conn, &table_name, description, col, key)?);
+    }
+
+    Ok((table_name, values))
+}
+
 pub fn _select_line_encrypted(conn: &Connection, description: String) -> (String, Vec<(String, Vec<u8>)>) {
     //select_line() but doesn't decrypt. Use this combined with decrypt_single_entry() instead of select_line.
     let encoded_table: String = conn.query_row(&format!("SELECT template FROM descriptions WHERE description = '{}'", encode_base64(&description)), params![], |row| row.get(0)).expect("");
@@ -163,17 +362,20 @@ pub fn _select_line_encrypted(conn: &Connection, description: String) -> (String
     (decode_base64(encoded_table), combined_vec)
 }
 
-pub fn _decrypt_single_entry(conn: &Connection, description: String, column: String, key: Vec<u8>) -> String {
+pub fn _decrypt_single_entry(conn: &Connection, description: String, column: String, key: &[u8]) -> SecretString {
     //Decrypts just one entry instead of a whole row. Use this combined with select_line_encrypted() instead of select_line.
     let table_name: String = conn.query_row(&format!("SELECT template FROM descriptions WHERE description = '{}'", encode_base64(&description)), params![], |row| row.get(0)).expect("");
     let query_result: String = conn.query_row(&format!("SELECT \"{}\" FROM \"{}\" WHERE description = '{}'", encode_base64(&column), encode_base64(&table_name), encode_base64(&description)), params![], |row| row.get(0)).expect("");
     let stmt: String = conn.query_row(&format!("SELECT nonce FROM nonces WHERE orig_table = '{}' AND orig_entry = '{}' AND orig_desc = '{}'", encode_base64(&table_name), encode_base64(&column), encode_base64(&description)), params![], |row| row.get(0)).expect("");
-    let nonce: Vec<u8> = base64::decode(stmt).expect("");
+    let nonce: Vec<u8> = decode_base64_bytes(stmt);
 
-    let key_usable: GenericArray<u8, U32> = u32_from_slice(&key);
+    let mut key_usable: GenericArray<u8, U32> = u32_from_slice(key);
     let nonce_usable: GenericArray<u8, U12> = u12_from_slice(&nonce);
 
-    decrypt_aesgcm(&key_usable, &nonce_usable, &base64::decode(query_result).expect(""))
+    let decrypted = decrypt_aesgcm(&key_usable, &nonce_usable, &decode_base64_bytes(query_result));
+    key_usable.as_mut_slice().zeroize();
+
+    decrypted.unwrap_or_else(|| SecretString::from("Decryption failed."))
 }
 
 // IMPLEMENTING SQL COMMANDS
@@ -195,84 +397,580 @@ pub fn _create_table(conn: &Connection, table_name: String, columns: Vec<String>
     */
 }
 
-pub fn insert_entry(conn: &Connection, table_name: String, args_str: Vec<String>, key: Vec<u8>) -> Result<()> {
+pub fn insert_entry(conn: &Connection, table_name: String, args_str: &[SecretString], key: &[u8]) -> Result<()> {
     //Take input -> Encrypt using AES -> Encode in Base64 -> Store in 
     //args_str[0] is description!!!! = shown name of entry like Email, Skype, etc.!!!
-    let description = args_str[0].clone().into_bytes();
+    let description = args_str[0].as_bytes().to_vec();
     let mut enc_args_vec: Vec<Vec<u8>> = vec![];
-    let key_as_array = u32_from_slice(&key);
+    let mut key_as_array = u32_from_slice(key);
 
     enc_args_vec.push(description.clone());
 
-    let table_columns: Vec<String> = get_columns_from_table(&conn, &encode_base64(&table_name));
+    let table_columns: Vec<String> = get_columns_from_table(conn, &encode_base64(&table_name));
 
     for (col_index, arg) in args_str.iter().skip(1).enumerate() {
         loop {
             let nonce = nonce_generator(); //Generate nonces on the fly for every entry -> No nonce reuse attack
-            if conn.query_row(&format!("SELECT 1 FROM nonces WHERE nonce = '{}'", encode_base64(&nonce)), params![], |_| Ok(1)).is_err() {
+            if !nonce_in_use(conn, &nonce) {
                 //This query ensures that the generates nonce is unique; the odds of generating two same random 96 bit numbers are low, but never zero!
                 let current_col: &str = &table_columns[col_index];
-                conn.execute(&format!("INSERT INTO nonces VALUES('{}', '{}', '{}', '{}')", encode_base64(&nonce), encode_base64(&table_name), encode_base64(&description), current_col), params![]).expect("Something went wrong.");
-                let enc_arg: Vec<u8> = encrypt_aesgcm(&key_as_array, &nonce, &arg);
+                conn.execute(&format!("INSERT INTO nonces VALUES('{}', '{}', '{}', '{}')", encode_base64(nonce), encode_base64(&table_name), encode_base64(&description), current_col), params![]).expect("Something went wrong.");
+                let enc_arg: Vec<u8> = encrypt_aesgcm(&key_as_array, &nonce, arg.as_str());
                 enc_args_vec.push(enc_arg);
                 break;
             }
         }
     }
-    let args_aes_b64: Vec<String> = enc_args_vec.iter().map(|ciphertext| encode_base64(ciphertext)).collect();
+    key_as_array.as_mut_slice().zeroize();
+
+    let args_aes_b64: Vec<String> = enc_args_vec.iter().map(encode_base64).collect();
 
     let args_aes_b64_string: String = format_args(args_aes_b64); //add ' ', around all entries
 
     conn.execute(&format!("INSERT INTO \"{}\" VALUES({})", encode_base64(&table_name), args_aes_b64_string), params![])?;
 
-    conn.execute(&format!("INSERT INTO descriptions VALUES('{}', '{}')", encode_base64(&args_str[0]), encode_base64(&table_name)), params![])?;
+    conn.execute(&format!("INSERT INTO descriptions VALUES('{}', '{}')", encode_base64(args_str[0].as_str()), encode_base64(&table_name)), params![])?;
+
+    let now = metadata::now();
+    conn.execute(
+        "INSERT OR REPLACE INTO entry_metadata (description, created, modified) VALUES (?1, ?2, ?2)",
+        params![encode_base64(args_str[0].as_str()), now],
+    )?;
 
     Ok(())
 }
 
-pub fn select_entry(conn: &Connection, table_name: String, description: String, column: String, key: Vec<u8>) -> String {
+pub fn select_entry(conn: &Connection, table_name: String, description: String, column: String, key: &[u8]) -> SecretString {
     //Inverse order: Decode from Base64 -> Decrypt using AES and given nonce -> return lé value
-    let query_result: String = conn.query_row(&format!("SELECT \"{}\" FROM \"{}\" WHERE description = '{}'", column, encode_base64(&table_name), encode_base64(&description)), params![], |row| row.get(0)).expect("");
-    let stmt: String = conn.query_row(&format!("SELECT nonce FROM nonces WHERE orig_table = '{}' AND orig_entry = '{}' AND orig_desc = '{}'", encode_base64(&table_name), &column, encode_base64(&description)), params![], |row| row.get(0)).expect("");
-    let nonce: Vec<u8> = base64::decode(stmt).expect("");
+    try_select_entry(conn, &table_name, &description, &column, key).unwrap_or_else(|error| {
+        log::warn!("Failed to read entry: {}", error);
+        SecretString::from("Decryption failed.")
+    })
+}
+
+pub fn try_select_entry(conn: &Connection, table_name: &str, description: &str, column: &str, key: &[u8]) -> Result<SecretString, String> {
+    //select_entry() but reports what went wrong instead of returning a placeholder.
+    let query_result: String = conn.query_row(&format!("SELECT \"{}\" FROM \"{}\" WHERE description = '{}'", column, encode_base64(table_name), encode_base64(description)), params![], |row| row.get(0))
+        .map_err(|_| "value is missing".to_string())?;
+    let stmt: String = conn.query_row(&format!("SELECT nonce FROM nonces WHERE orig_table = '{}' AND orig_entry = '{}' AND orig_desc = '{}'", encode_base64(table_name), column, encode_base64(description)), params![], |row| row.get(0))
+        .map_err(|_| "nonce is missing".to_string())?;
+
+    let nonce: Vec<u8> = try_decode_base64_bytes(stmt).ok_or("nonce is not valid base64")?;
+    let ciphertext: Vec<u8> = try_decode_base64_bytes(query_result).ok_or("value is not valid base64")?;
+    if nonce.len() != 12 {
+        return Err("nonce has an invalid length".to_string());
+    }
 
-    let key_usable: GenericArray<u8, U32> = u32_from_slice(&key);
+    let mut key_usable: GenericArray<u8, U32> = u32_from_slice(key);
     let nonce_usable: GenericArray<u8, U12> = u12_from_slice(&nonce);
 
-    decrypt_aesgcm(&key_usable, &nonce_usable, &base64::decode(query_result).expect(""))
+    let decrypted = decrypt_aesgcm(&key_usable, &nonce_usable, &ciphertext);
+    key_usable.as_mut_slice().zeroize();
+
+    decrypted.ok_or_else(|| "decryption failed".to_string())
+}
+
+pub fn trash_entry(conn: &Connection, description: String) -> Result<i64> {
+    //Moves an entry into the trash in a single transaction. Values keep their ciphertext and nonce, so nothing
+    //gets decrypted; the name is freed for new entries.
+    let enc_desc = encode_base64(&description);
+    let transaction = conn.unchecked_transaction()?;
+    let enc_table: String = conn.query_row("SELECT template FROM descriptions WHERE description = ?1", params![enc_desc], |row| row.get(0))?;
+
+    conn.execute(
+        "INSERT INTO trash (description, template, sensitive, created, modified, revealed, copied, deleted) \
+            SELECT ?1, ?2, EXISTS (SELECT 1 FROM sensitive_entries WHERE description = ?1), m.created, m.modified, m.revealed, m.copied, ?3 \
+            FROM (SELECT 1) LEFT JOIN entry_metadata m ON m.description = ?1",
+        params![enc_desc, enc_table, metadata::now()],
+    )?;
+    let id = conn.last_insert_rowid();
+
+    for column in get_columns_from_table(conn, &enc_table) {
+        conn.execute(
+            &format!("INSERT INTO trash_values (trash_id, orig_entry, value, nonce) \
+                SELECT ?4, n.orig_entry, t.\"{}\", n.nonce FROM nonces n JOIN \"{}\" t ON t.description = n.orig_desc \
+                WHERE n.orig_table = ?1 AND n.orig_desc = ?2 AND n.orig_entry = ?3", column, enc_table),
+            params![enc_table, enc_desc, column, id],
+        )?;
+    }
+    conn.execute("UPDATE field_history SET orig_desc = ?3 WHERE orig_table = ?1 AND orig_desc = ?2", params![enc_table, enc_desc, trash::trash_key(id)])?;
+    conn.execute("UPDATE entry_tags SET description = ?2 WHERE description = ?1", params![enc_desc, trash::trash_key(id)])?;
+    conn.execute("UPDATE favorite_entries SET description = ?2 WHERE description = ?1", params![enc_desc, trash::trash_key(id)])?;
+    conn.execute("UPDATE entry_usage SET description = ?2 WHERE description = ?1", params![enc_desc, trash::trash_key(id)])?;
+
+    conn.execute(&format!("DELETE FROM \"{}\" WHERE description = ?1", enc_table), params![enc_desc])?;
+    conn.execute("DELETE FROM nonces WHERE orig_table = ?1 AND orig_desc = ?2", params![enc_table, enc_desc])?;
+    conn.execute("DELETE FROM descriptions WHERE description = ?1", params![enc_desc])?;
+    conn.execute("DELETE FROM sensitive_entries WHERE description = ?1", params![enc_desc])?;
+    conn.execute("DELETE FROM entry_metadata WHERE description = ?1", params![enc_desc])?;
+    transaction.commit()?;
+
+    Ok(id)
+}
+
+pub fn rename_entry(conn: &Connection, description: &str, new_description: &str) -> Result<()> {
+    //Changes the name of an entry in every table that refers to it in a single transaction. The new name has to be available.
+    let (old, new) = (encode_base64(description), encode_base64(new_description));
+    let transaction = conn.unchecked_transaction()?;
+    let enc_table: String = conn.query_row("SELECT template FROM descriptions WHERE description = ?1", params![old], |row| row.get(0))?;
+
+    conn.execute(&format!("UPDATE \"{}\" SET description = ?2 WHERE description = ?1", enc_table), params![old, new])?;
+    conn.execute("UPDATE nonces SET orig_desc = ?3 WHERE orig_table = ?1 AND orig_desc = ?2", params![enc_table, old, new])?;
+    conn.execute("UPDATE field_history SET orig_desc = ?3 WHERE orig_table = ?1 AND orig_desc = ?2", params![enc_table, old, new])?;
+    conn.execute("UPDATE descriptions SET description = ?2 WHERE description = ?1", params![old, new])?;
+    conn.execute("UPDATE sensitive_entries SET description = ?2 WHERE description = ?1", params![old, new])?;
+    conn.execute("UPDATE entry_metadata SET description = ?2 WHERE description = ?1", params![old, new])?;
+    conn.execute("UPDATE entry_tags SET description = ?2 WHERE description = ?1", params![old, new])?;
+    conn.execute("UPDATE favorite_entries SET description = ?2 WHERE description = ?1", params![old, new])?;
+    conn.execute("UPDATE entry_usage SET description = ?2 WHERE description = ?1", params![old, new])?;
+    transaction.commit()
+}
+
+pub fn get_all_tags(conn: &Connection) -> Result<Vec<(String, String)>> {
+    //Returns all tags as decoded entry name and tag; tags of trashed entries and damaged names are skipped.
+    let mut stmt = conn.prepare("SELECT description, tag FROM entry_tags ORDER BY tag")?;
+    let rows = stmt.query_map(params![], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+
+    let mut tags = vec![];
+    for row in rows {
+        let (description, tag) = row?;
+        if let Some(name) = try_decode_base64_bytes(&description).and_then(|b| String::from_utf8(b).ok()) {
+            tags.push((name, tag));
+        }
+    }
+    Ok(tags)
+}
+
+pub fn get_tags(conn: &Connection, description: &str) -> Result<Vec<String>> {
+    //Returns the tags of a single entry sorted by name.
+    let mut stmt = conn.prepare("SELECT tag FROM entry_tags WHERE description = ?1 ORDER BY tag")?;
+    let rows = stmt.query_map(params![encode_base64(description)], |row| row.get(0))?;
+    rows.collect()
+}
+
+pub fn set_tags(conn: &Connection, description: &str, tags: &[String]) -> Result<()> {
+    //Replaces all tags of an entry in a single transaction.
+    let transaction = conn.unchecked_transaction()?;
+    conn.execute("DELETE FROM entry_tags WHERE description = ?1", params![encode_base64(description)])?;
+    for tag in tags {
+        conn.execute("INSERT OR IGNORE INTO entry_tags (description, tag) VALUES (?1, ?2)", params![encode_base64(description), tag])?;
+    }
+    transaction.commit()
+}
+
+pub fn get_favorites(conn: &Connection) -> Result<Vec<String>> {
+    //Returns the decoded names of all favorite entries; trashed entries and damaged names are skipped.
+    let mut stmt = conn.prepare("SELECT description FROM favorite_entries")?;
+    let rows = stmt.query_map(params![], |row| row.get::<_, String>(0))?;
+
+    let mut names = vec![];
+    for row in rows {
+        if let Some(name) = try_decode_base64_bytes(&row?).and_then(|b| String::from_utf8(b).ok()) {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+pub fn is_favorite(conn: &Connection, description: &str) -> Result<bool> {
+    //Returns true if an entry is pinned at the top of the list.
+    conn.query_row("SELECT EXISTS (SELECT 1 FROM favorite_entries WHERE description = ?1)", params![encode_base64(description)], |row| row.get(0))
+}
+
+pub fn set_favorite(conn: &Connection, description: &str, favorite: bool) -> Result<()> {
+    //Pins or unpins an entry at the top of the list.
+    if favorite {
+        conn.execute("INSERT OR IGNORE INTO favorite_entries VALUES(?1)", params![encode_base64(description)])?;
+    } else {
+        conn.execute("DELETE FROM favorite_entries WHERE description = ?1", params![encode_base64(description)])?;
+    }
+    Ok(())
+}
+
+pub fn get_usage(conn: &Connection) -> Result<Vec<(String, u32)>> {
+    //Returns how often entries were used by decoded name; trashed entries and damaged names are skipped.
+    let mut stmt = conn.prepare("SELECT description, uses FROM entry_usage")?;
+    let rows = stmt.query_map(params![], |row| Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?)))?;
+
+    let mut usage = vec![];
+    for row in rows {
+        let (description, uses) = row?;
+        if let Some(name) = try_decode_base64_bytes(&description).and_then(|b| String::from_utf8(b).ok()) {
+            usage.push((name, uses));
+        }
+    }
+    Ok(usage)
+}
+
+pub fn get_uses(conn: &Connection, description: &str) -> Result<u32> {
+    //Returns how often a single entry was used, 0 if never.
+    conn.query_row("SELECT coalesce((SELECT uses FROM entry_usage WHERE description = ?1), 0)", params![encode_base64(description)], |row| row.get(0))
 }
 
-pub fn delete_entry(conn: &Connection, description: String) {
-    //Deletes an entry and all associated information from other tables.
-    let enc_table: String = conn.query_row(&format!("SELECT template FROM descriptions WHERE description = '{}'", encode_base64(&description)), params![], |row| row.get(0)).expect("");
-    conn.execute(&format!("DELETE FROM \"{}\" WHERE description = '{}'", enc_table, encode_base64(&description)), params![]).expect("");
-    conn.execute(&format!("DELETE FROM nonces WHERE orig_table = '{}' AND orig_desc = '{}'", enc_table, encode_base64(&description)), params![]).expect("");
-    conn.execute(&format!("DELETE FROM descriptions WHERE description = '{}'", encode_base64(&description)), params![]).expect("");
+pub fn count_use(conn: &Connection, description: &str) -> Result<()> {
+    //Adds one to the number of times an entry was used.
+    conn.execute(
+        "INSERT INTO entry_usage (description, uses) VALUES (?1, 1) ON CONFLICT (description) DO UPDATE SET uses = uses + 1",
+        params![encode_base64(description)],
+    )?;
+    Ok(())
+}
+
+pub fn get_trash(conn: &Connection) -> Result<Vec<TrashItem>> {
+    //Returns all trashed entries, most recently deleted first. Damaged names are shown encoded.
+    let mut stmt = conn.prepare("SELECT id, description, template, deleted FROM trash ORDER BY id DESC")?;
+    let rows = stmt.query_map(params![], |row| {
+        let decode = |encoded: String| try_decode_base64_bytes(&encoded).and_then(|b| String::from_utf8(b).ok()).unwrap_or(encoded);
+        Ok(TrashItem {
+            id: row.get(0)?,
+            name: decode(row.get(1)?),
+            template: decode(row.get(2)?),
+            deleted: metadata::parse(&row.get::<_, String>(3)?),
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn restore_from_trash(conn: &Connection, id: i64, description: &str) -> Result<()> {
+    //Moves a trashed entry back under the given name, which has to be available, in a single transaction.
+    let enc_desc = encode_base64(description);
+    let (enc_table, sensitive): (String, bool) = conn.query_row("SELECT template, sensitive FROM trash WHERE id = ?1", params![id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut stmt = conn.prepare("SELECT orig_entry, value, nonce FROM trash_values WHERE trash_id = ?1")?;
+    let values = stmt.query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<(String, String, String)>>>()?;
+
+    let transaction = conn.unchecked_transaction()?;
+    let columns: Vec<String> = values.iter().map(|(column, _, _)| format!(", \"{}\"", column)).collect();
+    let placeholders: Vec<String> = (0..values.len()).map(|i| format!(", ?{}", i + 2)).collect();
+    conn.execute(
+        &format!("INSERT INTO \"{}\" (description{}) VALUES (?1{})", enc_table, columns.concat(), placeholders.concat()),
+        params_from_iter(std::iter::once(&enc_desc).chain(values.iter().map(|(_, value, _)| value))),
+    )?;
+    for (column, _, nonce) in values.iter() {
+        conn.execute("INSERT INTO nonces VALUES(?1, ?2, ?3, ?4)", params![nonce, enc_table, enc_desc, column])?;
+    }
+
+    conn.execute("INSERT INTO descriptions VALUES(?1, ?2)", params![enc_desc, enc_table])?;
+    if sensitive {
+        conn.execute("INSERT OR IGNORE INTO sensitive_entries VALUES(?1)", params![enc_desc])?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO entry_metadata (description, created, modified, revealed, copied) \
+            SELECT ?1, coalesce(created, ?3), coalesce(modified, ?3), revealed, copied FROM trash WHERE id = ?2",
+        params![enc_desc, id, metadata::now()],
+    )?;
+    conn.execute("UPDATE field_history SET orig_desc = ?3 WHERE orig_table = ?1 AND orig_desc = ?2", params![enc_table, trash::trash_key(id), enc_desc])?;
+    conn.execute("UPDATE entry_tags SET description = ?2 WHERE description = ?1", params![trash::trash_key(id), enc_desc])?;
+    conn.execute("UPDATE favorite_entries SET description = ?2 WHERE description = ?1", params![trash::trash_key(id), enc_desc])?;
+    conn.execute("UPDATE entry_usage SET description = ?2 WHERE description = ?1", params![trash::trash_key(id), enc_desc])?;
+
+    conn.execute("DELETE FROM trash_values WHERE trash_id = ?1", params![id])?;
+    conn.execute("DELETE FROM trash WHERE id = ?1", params![id])?;
+    transaction.commit()
+}
+
+pub fn purge_from_trash(conn: &Connection, ids: &[i64]) -> Result<usize> {
+    //Permanently removes trashed entries with their values and history in a single transaction.
+    let transaction = conn.unchecked_transaction()?;
+    let mut purged = 0;
+    for id in ids {
+        conn.execute("DELETE FROM field_history WHERE orig_desc = ?1", params![trash::trash_key(*id)])?;
+        conn.execute("DELETE FROM entry_tags WHERE description = ?1", params![trash::trash_key(*id)])?;
+        conn.execute("DELETE FROM favorite_entries WHERE description = ?1", params![trash::trash_key(*id)])?;
+        conn.execute("DELETE FROM entry_usage WHERE description = ?1", params![trash::trash_key(*id)])?;
+        conn.execute("DELETE FROM trash_values WHERE trash_id = ?1", params![id])?;
+        purged += conn.execute("DELETE FROM trash WHERE id = ?1", params![id])?;
+    }
+    transaction.commit()?;
+    Ok(purged)
+}
+
+pub fn get_expired_trash(conn: &Connection, deleted_before: &str) -> Result<Vec<TrashItem>> {
+    //Returns the trashed entries deleted before the given time.
+    Ok(get_trash(conn)?
+        .into_iter()
+        .filter(|item| item.deleted.is_some_and(|deleted| metadata::format(deleted).as_str() < deleted_before))
+        .collect())
+}
+
+pub fn is_sensitive(conn: &Connection, description: String) -> bool {
+    //Returns true if an entry requires the master password before revealing it.
+    conn.query_row(&format!("SELECT 1 FROM sensitive_entries WHERE description = '{}'", encode_base64(description)), params![], |_| Ok(1)).is_ok()
+}
+
+pub fn set_sensitive(conn: &Connection, description: String, sensitive: bool) -> Result<()> {
+    //Marks or unmarks an entry as requiring the master password.
+    if sensitive {
+        conn.execute(&format!("INSERT OR IGNORE INTO sensitive_entries VALUES('{}')", encode_base64(description)), params![])?;
+    } else {
+        conn.execute(&format!("DELETE FROM sensitive_entries WHERE description = '{}'", encode_base64(description)), params![])?;
+    }
+    Ok(())
+}
+
+pub fn set_template_reauthenticate(conn: &Connection, db_name: &str, reauthenticate: bool) -> Result<()> {
+    //Changes whether entries of a template require the master password; damaged templates are left alone.
+    conn.execute(
+        "UPDATE templates
+         SET structure = CAST(json_set(CAST(structure AS TEXT), '$.reauthenticate', json(?1)) AS BLOB)
+         WHERE CASE
+                   WHEN json_valid(CAST(structure AS TEXT)) THEN json_extract(CAST(structure AS TEXT), '$.db_name') = ?2
+                   ELSE 0 END",
+        params![reauthenticate.to_string(), db_name],
+    )?;
+    Ok(())
 }
 
-pub fn _update_entry(conn: &Connection, table_name: String, description: String, edited_entry: String, edited_column: String, key: Vec<u8>) -> Result<()> {
-    //Not yet used nor tested!
-    let key_usable: GenericArray<u8, U32> = u32_from_slice(&key);
+pub fn get_settings(conn: &Connection) -> Result<Vec<(String, String)>> {
+    //Returns all settings stored in the vault as name and value.
+    let mut stmt = conn.prepare("SELECT name, value FROM settings")?;
+    let rows = stmt.query_map(params![], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+pub fn get_setting(conn: &Connection, name: &str) -> Result<Option<String>> {
+    //Returns a single setting stored in the vault, None if it was never set.
+    conn.query_row("SELECT value FROM settings WHERE name = ?1", params![name], |row| row.get(0))
+        .optional()
+}
+
+pub fn set_setting(conn: &Connection, name: &str, value: &str) -> Result<()> {
+    //Stores a setting in the vault, replacing its old value.
+    conn.execute("INSERT OR REPLACE INTO settings (name, value) VALUES (?1, ?2)", params![name, value])?;
+    Ok(())
+}
+
+pub fn get_all_metadata(conn: &Connection) -> Result<Vec<(String, EntryMetadata)>> {
+    //Returns the timestamps of all entries with decoded names; rows with damaged names or times are skipped.
+    let mut stmt = conn.prepare("SELECT description, created, modified, revealed, copied FROM entry_metadata")?;
+    let rows = stmt.query_map(params![], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<String>>(4)?,
+        ))
+    })?;
+
+    let mut all_metadata = vec![];
+    for row in rows {
+        let (description, created, modified, revealed, copied) = row?;
+        let name = try_decode_base64_bytes(&description).and_then(|b| String::from_utf8(b).ok());
+
+        if let (Some(name), Some(entry_metadata)) = (name, parse_metadata(&created, &modified, revealed, copied)) {
+            all_metadata.push((name, entry_metadata));
+        }
+    }
+    Ok(all_metadata)
+}
+
+pub fn get_metadata(conn: &Connection, description: &str) -> Result<Option<EntryMetadata>> {
+    //Returns the timestamps of a single entry, None if it has none or they are damaged.
+    let row = conn.query_row(
+        "SELECT created, modified, revealed, copied FROM entry_metadata WHERE description = ?1",
+        params![encode_base64(description)],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get(2)?, row.get(3)?)),
+    ).optional()?;
+
+    Ok(row.and_then(|(created, modified, revealed, copied)| parse_metadata(&created, &modified, revealed, copied)))
+}
+
+fn parse_metadata(created: &str, modified: &str, revealed: Option<String>, copied: Option<String>) -> Option<EntryMetadata> {
+    //Reads stored timestamps, None if the required ones are damaged.
+    Some(EntryMetadata {
+        created: metadata::parse(created)?,
+        modified: metadata::parse(modified)?,
+        revealed: revealed.as_deref().and_then(metadata::parse),
+        copied: copied.as_deref().and_then(metadata::parse),
+    })
+}
+
+pub fn set_metadata(conn: &Connection, description: &str, entry_metadata: &EntryMetadata) -> Result<()> {
+    //Stores all timestamps of an entry, replacing existing ones.
+    conn.execute(
+        "INSERT OR REPLACE INTO entry_metadata (description, created, modified, revealed, copied) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            encode_base64(description),
+            metadata::format(entry_metadata.created),
+            metadata::format(entry_metadata.modified),
+            entry_metadata.revealed.map(metadata::format),
+            entry_metadata.copied.map(metadata::format),
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn touch_metadata(conn: &Connection, description: &str, timestamp: Timestamp) -> Result<()> {
+    //Sets one timestamp of an entry to the current time.
+    conn.execute(
+        &format!("UPDATE entry_metadata SET {} = ?1 WHERE description = ?2", timestamp.column()),
+        params![metadata::now(), encode_base64(description)],
+    )?;
+    Ok(())
+}
+
+pub fn insert_audit_record(conn: &Connection, record: &AuditRecord) -> Result<()> {
+    //Appends a record to the audit trail. Entry names are stored encoded like in all other tables.
+    conn.execute(
+        "INSERT INTO audit_log (time, action, entry, detail) VALUES (?1, ?2, ?3, ?4)",
+        params![record.time, record.action, record.entry.as_ref().map(encode_base64), record.detail],
+    )?;
+    Ok(())
+}
+
+pub fn append_audit_records(conn: &Connection, records: &[AuditRecord]) -> Result<()> {
+    //Adds records of another copy of the vault while keeping their ids; already present ids are skipped.
+    let transaction = conn.unchecked_transaction()?;
+    for record in records {
+        conn.execute(
+            "INSERT OR IGNORE INTO audit_log (id, time, action, entry, detail) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![record.id, record.time, record.action, record.entry.as_ref().map(encode_base64), record.detail],
+        )?;
+    }
+    transaction.commit()
+}
+
+pub fn get_audit_records(conn: &Connection) -> Result<Vec<AuditRecord>> {
+    //Returns the whole audit trail, newest first. Damaged names are shown encoded instead of hiding the record.
+    let mut stmt = conn.prepare("SELECT id, time, action, entry, detail FROM audit_log ORDER BY id DESC")?;
+    let rows = stmt.query_map(params![], |row| {
+        let entry: Option<String> = row.get(3)?;
+        Ok(AuditRecord {
+            id: row.get(0)?,
+            time: row.get(1)?,
+            action: row.get(2)?,
+            entry: entry.map(|e| try_decode_base64_bytes(&e).and_then(|b| String::from_utf8(b).ok()).unwrap_or(e)),
+            detail: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn get_field_location(conn: &Connection, description: &str, index: usize) -> Result<(String, String)> {
+    //Returns the encoded table and column of the field at index of an entry, the description not counted.
+    let encoded_table: String = conn.query_row("SELECT template FROM descriptions WHERE description = ?1", params![encode_base64(description)], |row| row.get(0))?;
+    let column = get_columns_from_table(conn, &encoded_table).into_iter().nth(index)
+        .ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+    Ok((encoded_table, column))
+}
+
+pub fn update_entry(conn: &Connection, encoded_table: &str, description: &str, column: &str, edited_entry: &SecretString, key: &[u8], keep_history: bool) -> Result<()> {
+    //Encrypts a new value for one field. The old value moves into the field history together with its nonce
+    //if keep_history is set, otherwise it gets overwritten in the vault file because of secure_delete; the
+    //rollback journal only ever holds it encrypted and is deleted once the transaction ends.
+    let mut key_usable: GenericArray<u8, U32> = u32_from_slice(key);
     let nonce_usable: GenericArray<u8, U12> = loop {
         let nonce = nonce_generator(); //Generate nonces on the fly for every entry -> No nonce reuse attack
-        if conn.query_row(&format!("SELECT 1 FROM nonces WHERE nonce = '{}'", encode_base64(&nonce)), params![], |_| Ok(1)).is_err() {
-            //This query ensures that the generates nonce is unique; the odds of generating two same random 96 bit numbers are low, but never zero!
-            break (nonce);
+        if !nonce_in_use(conn, &nonce) {
+            break nonce;
         }
     };
 
-    let enc_message: Vec<u8> = encrypt_aesgcm(&key_usable, &nonce_usable, &edited_entry);
+    let enc_message: Vec<u8> = encrypt_aesgcm(&key_usable, &nonce_usable, edited_entry.as_str());
+    key_usable.as_mut_slice().zeroize();
 
-    conn.execute(&format!("UPDATE \"{}\" SET \"{}\" = '{}' WHERE description = '{}'", encode_base64(&table_name), encode_base64(&edited_column), encode_base64(&enc_message), encode_base64(&description)), params![])?;
-    //If the database crashes between these queries, the database is going to be corrupted lol
-    conn.execute(&format!("DELETE FROM nonces WHERE orig_table = '{}' AND orig_desc = '{}'", encode_base64(&table_name), encode_base64(&description)), params![]).expect("How did this not work, what");
-    conn.execute(&format!("INSERT INTO nonces VALUES('{}', '{}', '{}', '{}')", encode_base64(&nonce_usable), encode_base64(&table_name), encode_base64(&description), encode_base64(&edited_column)), params![]).expect("Something went wrong.");
+    //All queries run in one transaction, so a crash in between can not corrupt the entry.
+    let transaction = conn.unchecked_transaction()?;
+    if keep_history {
+        archive_value(conn, encoded_table, &encode_base64(description), column)?;
+    }
+    replace_value(conn, encoded_table, &encode_base64(description), column, &encode_base64(&enc_message), &encode_base64(nonce_usable))?;
+    transaction.commit()
+}
 
-    Ok(())
+pub fn get_field_history(conn: &Connection, encoded_table: &str, description: &str, column: &str, key: &[u8]) -> Result<Vec<HistoryValue>> {
+    //Returns the DECRYPTED previous values of a field, newest first. Damaged values get a placeholder like select_entry().
+    let mut stmt = conn.prepare("SELECT id, value, nonce, replaced FROM field_history \
+        WHERE orig_table = ?1 AND orig_desc = ?2 AND orig_entry = ?3 ORDER BY id DESC")?;
+    let rows = stmt.query_map(params![encoded_table, encode_base64(description), column], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?))
+    })?;
+
+    let mut key_usable: GenericArray<u8, U32> = u32_from_slice(key);
+    let mut values = vec![];
+    for row in rows {
+        let (id, value, nonce, replaced) = row?;
+        let decrypted = match (try_decode_base64_bytes(value), try_decode_base64_bytes(nonce)) {
+            (Some(ciphertext), Some(nonce)) if nonce.len() == 12 => decrypt_aesgcm(&key_usable, &u12_from_slice(&nonce), &ciphertext),
+            _ => None,
+        };
+
+        values.push(HistoryValue {
+            id,
+            replaced: metadata::parse(&replaced),
+            value: decrypted.unwrap_or_else(|| {
+                log::warn!("Failed to read previous value of an entry");
+                SecretString::from("Decryption failed.")
+            }),
+        });
+    }
+    key_usable.as_mut_slice().zeroize();
+
+    Ok(values)
+}
+
+pub fn restore_history_value(conn: &Connection, id: i64) -> Result<()> {
+    //Swaps a previous value back into its field, the current value moves into the history in the same transaction.
+    let (encoded_table, encoded_desc, column, value, nonce): (String, String, String, String, String) = conn.query_row(
+        "SELECT orig_table, orig_desc, orig_entry, value, nonce FROM field_history WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+    )?;
+
+    let transaction = conn.unchecked_transaction()?;
+    conn.execute("DELETE FROM field_history WHERE id = ?1", params![id])?;
+    archive_value(conn, &encoded_table, &encoded_desc, &column)?;
+    replace_value(conn, &encoded_table, &encoded_desc, &column, &value, &nonce)?;
+    transaction.commit()
+}
+
+pub fn prune_history(conn: &Connection, keep: usize, replaced_before: Option<&str>) -> Result<usize> {
+    //Removes all but the newest keep previous values of each field and those replaced before the given time.
+    let transaction = conn.unchecked_transaction()?;
+    let mut deleted = conn.execute(
+        "DELETE FROM field_history WHERE id IN (SELECT id FROM (SELECT id, ROW_NUMBER() OVER \
+            (PARTITION BY orig_table, orig_desc, orig_entry ORDER BY id DESC) AS position FROM field_history) WHERE position > ?1)",
+        params![keep as i64],
+    )?;
+    if let Some(time) = replaced_before {
+        deleted += conn.execute("DELETE FROM field_history WHERE replaced < ?1", params![time])?;
+    }
+    transaction.commit()?;
+    Ok(deleted)
 }
 
 //HELPER FUNCTIONS
 
+fn nonce_in_use(conn: &Connection, nonce: &GenericArray<u8, U12>) -> bool {
+    //Nonces of previous and trashed values stay in use, they are still needed to decrypt them.
+    conn.query_row(
+        "SELECT 1 FROM nonces WHERE nonce = ?1 UNION SELECT 1 FROM field_history WHERE nonce = ?1 UNION SELECT 1 FROM trash_values WHERE nonce = ?1",
+        params![encode_base64(nonce)],
+        |_| Ok(1),
+    ).is_ok()
+}
+
+fn archive_value(conn: &Connection, encoded_table: &str, encoded_desc: &str, column: &str) -> Result<()> {
+    //Copies the current ciphertext of a field together with its nonce into the field history.
+    conn.execute(
+        &format!("INSERT INTO field_history (orig_table, orig_desc, orig_entry, value, nonce, replaced) \
+            SELECT n.orig_table, n.orig_desc, n.orig_entry, t.\"{}\", n.nonce, ?4 FROM nonces n JOIN \"{}\" t ON t.description = n.orig_desc \
+            WHERE n.orig_table = ?1 AND n.orig_desc = ?2 AND n.orig_entry = ?3", column, encoded_table),
+        params![encoded_table, encoded_desc, column, metadata::now()],
+    )?;
+    Ok(())
+}
+
+fn replace_value(conn: &Connection, encoded_table: &str, encoded_desc: &str, column: &str, value: &str, nonce: &str) -> Result<()> {
+    //Stores an encrypted value of a field together with its nonce, everything already base64-encoded.
+    conn.execute(&format!("UPDATE \"{}\" SET \"{}\" = ?1 WHERE description = ?2", encoded_table, column), params![value, encoded_desc])?;
+    conn.execute("DELETE FROM nonces WHERE orig_table = ?1 AND orig_desc = ?2 AND orig_entry = ?3", params![encoded_table, encoded_desc, column])?;
+    conn.execute("INSERT INTO nonces VALUES(?1, ?2, ?3, ?4)", params![nonce, encoded_table, encoded_desc, column])?;
+    Ok(())
+}
+
 fn format_args(args_vec: Vec<String>) -> String {
     let formatted_args: String = args_vec
         .iter()
@@ -292,7 +990,106 @@ pub fn _decode_vec_string_b64(encoded_vec: Vec<String>) -> Vec<String> {
     //Decode a full vector of base64-encoded values
     let decoded_vec: Vec<String> = encoded_vec
         .iter()
-        .map(|encoded_column| decode_base64(encoded_column.to_string()))
+        .map(decode_base64)
         .collect();
     decoded_vec
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    const KEY: [u8; 32] = [7; 32];
+    const TABLE: &str = "tp_web_credential";
+    const USERNAME: &str = "Y2xlYXJfMQ==";
+    const PASSWORD: &str = "aGlkZGVuXzE=";
+
+    fn test_vault(name: &str) -> Connection {
+        // fresh vault with a web credential that has tags, a favorite, uses and a previous password
+        let path = env::temp_dir().join(format!("rwm-db-{name}-{}.sqlite3", std::process::id()));
+        fs::remove_file(&path).unwrap_or(());
+        let conn = create_database(&path);
+
+        insert(&conn, "GitHub", "alice", "hunter2");
+        set_tags(&conn, "GitHub", &["work".to_string()]).unwrap();
+        set_favorite(&conn, "GitHub", true).unwrap();
+        set_sensitive(&conn, "GitHub".to_string(), true).unwrap();
+        count_use(&conn, "GitHub").unwrap();
+        update_entry(&conn, &encode_base64(TABLE), "GitHub", PASSWORD, &SecretString::from("hunter3"), &KEY, true).unwrap();
+        conn
+    }
+
+    fn insert(conn: &Connection, name: &str, username: &str, password: &str) {
+        let values = [name, username, password].map(SecretString::from);
+        insert_entry(conn, TABLE.to_string(), &values, &KEY).unwrap();
+    }
+
+    fn value(conn: &Connection, name: &str, column: &str) -> String {
+        try_select_entry(conn, TABLE, name, column, &KEY).unwrap().as_str().to_string()
+    }
+
+    fn rows(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT count(*) FROM {table}"), [], |row| row.get(0)).unwrap()
+    }
+
+    fn assert_dependents_of_github(conn: &Connection, name: &str) {
+        // everything that belonged to the original entry now belongs to name
+        assert_eq!(get_tags(conn, name).unwrap(), vec!["work"]);
+        assert!(is_favorite(conn, name).unwrap());
+        assert!(is_sensitive(conn, name.to_string()));
+        assert_eq!(get_uses(conn, name).unwrap(), 1);
+        let history = get_field_history(conn, &encode_base64(TABLE), name, PASSWORD, &KEY).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].value.as_str(), "hunter2");
+    }
+
+    #[test]
+    fn trashed_entries_are_restored_with_their_values() {
+        let conn = test_vault("restore");
+        let id = trash_entry(&conn, "GitHub".to_string()).unwrap();
+
+        assert!(check_name_available(&conn, "GitHub".to_string()));
+        assert!(get_tags(&conn, "GitHub").unwrap().is_empty());
+        assert!(!is_favorite(&conn, "GitHub").unwrap());
+        assert_eq!(get_trash(&conn).unwrap()[0].name, "GitHub");
+        assert_eq!(rows(&conn, "nonces"), 0);
+
+        restore_from_trash(&conn, id, "GitHub").unwrap();
+        assert_eq!(value(&conn, "GitHub", USERNAME), "alice");
+        assert_eq!(value(&conn, "GitHub", PASSWORD), "hunter3");
+        assert_dependents_of_github(&conn, "GitHub");
+        assert_eq!(rows(&conn, "trash") + rows(&conn, "trash_values"), 0);
+    }
+
+    #[test]
+    fn restoring_under_a_taken_name_renames_the_entry() {
+        let conn = test_vault("rename");
+        let id = trash_entry(&conn, "GitHub".to_string()).unwrap();
+        insert(&conn, "GitHub", "bob", "swordfish");
+
+        let name = trash::available_name("GitHub", |name| check_name_available(&conn, name.to_string()));
+        assert_eq!(name, "GitHub (restored)");
+        restore_from_trash(&conn, id, &name).unwrap();
+
+        assert_eq!(value(&conn, "GitHub (restored)", USERNAME), "alice");
+        assert_eq!(value(&conn, "GitHub (restored)", PASSWORD), "hunter3");
+        assert_dependents_of_github(&conn, "GitHub (restored)");
+        assert_eq!(value(&conn, "GitHub", PASSWORD), "swordfish");
+        assert!(get_tags(&conn, "GitHub").unwrap().is_empty());
+    }
+
+    #[test]
+    fn purging_removes_every_dependent_row() {
+        let conn = test_vault("purge");
+        insert(&conn, "GitLab", "carol", "letmein");
+        let id = trash_entry(&conn, "GitHub".to_string()).unwrap();
+
+        assert_eq!(purge_from_trash(&conn, &[id]).unwrap(), 1);
+        for table in ["trash", "trash_values", "field_history", "entry_tags", "favorite_entries", "entry_usage", "sensitive_entries"] {
+            assert_eq!(rows(&conn, table), 0, "{table}");
+        }
+        assert!(find_orphan_nonces(&conn).unwrap().is_empty());
+        assert_eq!(value(&conn, "GitLab", PASSWORD), "letmein");
+    }
+}
@@ -0,0 +1,261 @@
+use rusqlite::Connection;
+
+use crate::{
+    app::extras::Template,
+    base64_enc_dec::try_decode_base64_bytes,
+    db_interface,
+    redact,
+};
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DoctorFix {
+    // repairs that only remove data which can't be reached anymore
+    RemoveOrphanNonces,
+    RemoveDanglingDescriptions,
+}
+
+impl DoctorFix {
+    pub fn description(&self) -> &'static str {
+        // explanation shown before applying the fix
+        match self {
+            DoctorFix::RemoveOrphanNonces => "Remove nonces without an entry",
+            DoctorFix::RemoveDanglingDescriptions => "Remove entry names pointing to missing tables",
+        }
+    }
+
+    pub fn backup_label(&self) -> &'static str {
+        // appended to the name of the backup created before the fix
+        match self {
+            DoctorFix::RemoveOrphanNonces => "before-nonce-cleanup",
+            DoctorFix::RemoveDanglingDescriptions => "before-description-cleanup",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DoctorReport {
+    // findings of a single health check, names are base64-decoded where possible
+    pub integrity_errors: Vec<String>,
+    pub checked_fields: usize,
+    pub broken_fields: Vec<String>,
+    pub unreadable_names: Vec<String>,
+    pub orphan_nonces: Vec<String>,
+    pub dangling_descriptions: Vec<String>,
+    pub broken_templates: Vec<String>,
+}
+
+impl DoctorReport {
+    pub fn is_healthy(&self) -> bool {
+        // returns weather no problem was found at all
+        self.integrity_errors.is_empty()
+            && self.broken_fields.is_empty()
+            && self.unreadable_names.is_empty()
+            && self.orphan_nonces.is_empty()
+            && self.dangling_descriptions.is_empty()
+            && self.broken_templates.is_empty()
+    }
+
+    pub fn fixes(&self) -> Vec<DoctorFix> {
+        // returns the safe fixes for the found problems
+        let mut fixes = vec![];
+        if !self.dangling_descriptions.is_empty() {
+            fixes.push(DoctorFix::RemoveDanglingDescriptions);
+        }
+        if !self.orphan_nonces.is_empty() {
+            fixes.push(DoctorFix::RemoveOrphanNonces);
+        }
+        fixes
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        // formats the report for the terminal and the vault page
+        let mut lines = vec![];
+
+        if self.integrity_errors.is_empty() {
+            lines.push("Integrity check: ok".to_string());
+        } else {
+            lines.push(format!("Integrity check: {} problem(s)", self.integrity_errors.len()));
+            lines.extend(self.integrity_errors.iter().map(|e| format!("  {e}")));
+        }
+
+        lines.push(format!(
+            "Decrypted fields: {} of {}",
+            self.checked_fields - self.broken_fields.len(),
+            self.checked_fields,
+        ));
+        lines.extend(self.broken_fields.iter().map(|f| format!("  {f}")));
+
+        if !self.unreadable_names.is_empty() {
+            lines.push(format!("Unreadable names: {}", self.unreadable_names.len()));
+            lines.extend(self.unreadable_names.iter().map(|n| format!("  {n}")));
+        }
+
+        lines.push(format!("Nonces without an entry: {}", self.orphan_nonces.len()));
+
+        lines.push(format!("Entry names pointing to missing tables: {}", self.dangling_descriptions.len()));
+        lines.extend(self.dangling_descriptions.iter().map(|d| format!("  {}", readable(d))));
+
+        lines.push(format!("Unreadable templates: {}", self.broken_templates.len()));
+        lines.extend(self.broken_templates.iter().map(|t| format!("  {t}")));
+
+        if !self.integrity_errors.is_empty()
+            || !self.broken_fields.is_empty()
+            || !self.unreadable_names.is_empty()
+            || !self.broken_templates.is_empty()
+        {
+            lines.push(String::new());
+            lines.push("Damaged data can't be repaired automatically, restore a backup instead.".to_string());
+        }
+
+        lines
+    }
+}
+
+pub fn diagnose(conn: &Connection, key: &[u8]) -> rusqlite::Result<DoctorReport> {
+    // runs all health checks without modifying the database
+    let mut report = DoctorReport {
+        integrity_errors: db_interface::check_cipher_integrity(conn)?,
+        ..Default::default()
+    };
+    report.integrity_errors.extend(
+        db_interface::check_integrity(conn)?
+            .into_iter()
+            .filter(|message| message != "ok"),
+    );
+
+    // decrypt every field of every entry
+    for encoded_table in db_interface::get_entry_tables_encoded(conn)? {
+        let Some(table_name) = decode(&encoded_table) else {
+            report.unreadable_names.push(format!("Table {encoded_table}"));
+            continue;
+        };
+        let columns = db_interface::get_columns_from_table(conn, &encoded_table);
+
+        for encoded_description in db_interface::get_descriptions_from_table(conn, &encoded_table)? {
+            let Some(description) = decode(&encoded_description) else {
+                report.unreadable_names.push(format!("Entry {encoded_description}"));
+                continue;
+            };
+
+            for column in columns.iter() {
+                report.checked_fields += 1;
+                if let Err(error) = db_interface::try_select_entry(conn, &table_name, &description, column, key) {
+                    report.broken_fields.push(format!("{} / {}: {}", description, readable(column), error));
+                }
+            }
+        }
+    }
+
+    report.orphan_nonces = db_interface::find_orphan_nonces(conn)?;
+    report.dangling_descriptions = db_interface::find_dangling_descriptions(conn)?;
+
+    for (name, structure) in db_interface::get_template_structures(conn)? {
+        if let Err(error) = serde_json::from_slice::<Template>(&structure) {
+            report.broken_templates.push(format!("{name}: {error}"));
+        }
+    }
+
+    log::info!("Checked vault health, {} field(s) checked", report.checked_fields);
+    Ok(report)
+}
+
+pub fn apply_fix(conn: &Connection, report: &DoctorReport, fix: DoctorFix) -> rusqlite::Result<usize> {
+    // applies a fix for the problems found in the report and returns the number of removed rows
+    let removed = match fix {
+        DoctorFix::RemoveOrphanNonces => db_interface::delete_nonces(conn, &report.orphan_nonces)?,
+        DoctorFix::RemoveDanglingDescriptions => {
+            db_interface::delete_descriptions(conn, &report.dangling_descriptions)?
+        }
+    };
+
+    log::info!("Applied fix '{}', removed {} row(s)", fix.description(), removed);
+    Ok(removed)
+}
+
+fn decode(encoded: &str) -> Option<String> {
+    // decodes a base64 name, None if it got damaged
+    String::from_utf8(try_decode_base64_bytes(encoded)?).ok()
+}
+
+fn readable(encoded: &str) -> String {
+    // decoded name for display, falls back to the encoded one
+    // both forms reveal the name and are kept out of the logs
+    redact::register(encoded);
+    let name = decode(encoded).unwrap_or_else(|| encoded.to_string());
+    redact::register(&name);
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{base64_enc_dec::encode_base64, secret::SecretString};
+    use std::{env, fs};
+
+    const KEY: [u8; 32] = [7; 32];
+
+    fn test_vault(name: &str) -> Connection {
+        // fresh vault with a single web credential
+        let path = env::temp_dir().join(format!("rwm-doctor-{name}-{}.sqlite3", std::process::id()));
+        fs::remove_file(&path).unwrap_or(());
+        let conn = db_interface::create_database(&path);
+
+        let values = ["GitHub", "alice", "hunter2"].map(|value| SecretString::new(value.to_string()));
+        db_interface::insert_entry(&conn, "tp_web_credential".to_string(), &values, &KEY).unwrap();
+        conn
+    }
+
+    #[test]
+    fn fixes_remove_unreachable_rows_only() {
+        let conn = test_vault("fixes");
+        let report = diagnose(&conn, &KEY).unwrap();
+        assert!(report.is_healthy());
+        assert_eq!(report.checked_fields, 2);
+        assert!(report.fixes().is_empty());
+
+        // a nonce of a vanished entry and an entry whose table was dropped
+        conn.execute_batch(&format!(
+            "INSERT INTO nonces VALUES ('{nonce}', '{table}', '{lost}', 'aGlkZGVuXzE=');
+             INSERT INTO descriptions VALUES ('{ghost}', '{missing}');",
+            nonce = encode_base64([1u8; 12]),
+            table = encode_base64("tp_web_credential"),
+            lost = encode_base64("Lost"),
+            ghost = encode_base64("Ghost"),
+            missing = encode_base64("tp_missing"),
+        ))
+        .unwrap();
+
+        let report = diagnose(&conn, &KEY).unwrap();
+        assert!(!report.is_healthy());
+        assert_eq!(report.orphan_nonces.len(), 1);
+        assert_eq!(report.dangling_descriptions, vec![encode_base64("Ghost")]);
+        assert!(report.lines().iter().any(|line| line == "  Ghost"));
+
+        let fixes = report.fixes();
+        assert!(fixes == vec![DoctorFix::RemoveDanglingDescriptions, DoctorFix::RemoveOrphanNonces]);
+        for fix in fixes {
+            assert_eq!(apply_fix(&conn, &report, fix).unwrap(), 1);
+        }
+
+        // the real entry is untouched and still decrypts
+        let report = diagnose(&conn, &KEY).unwrap();
+        assert!(report.is_healthy());
+        assert_eq!(report.checked_fields, 2);
+    }
+
+    #[test]
+    fn damaged_fields_are_reported_without_fixes() {
+        let conn = test_vault("damaged");
+        conn.execute(
+            &format!("UPDATE \"{}\" SET \"aGlkZGVuXzE=\" = ?1", encode_base64("tp_web_credential")),
+            [encode_base64("not a ciphertext")],
+        )
+        .unwrap();
+
+        let report = diagnose(&conn, &KEY).unwrap();
+        assert_eq!(report.broken_fields.len(), 1);
+        assert!(report.fixes().is_empty());
+        assert!(report.lines().contains(&"Damaged data can't be repaired automatically, restore a backup instead.".to_string()));
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use crate::{
+    app::extras::Template,
+    metadata::{EntryMetadata, EntrySort},
+    secret::SecretString,
+};
+
+
+pub struct IndexedEntry {
+    // what is searched and shown of an entry, fields are its non-private ones by their name
+    pub template: String,
+    pub metadata: Option<EntryMetadata>,
+    pub tags: Vec<String>,
+    pub favorite: bool,
+    pub uses: u32,
+    pub fields: Vec<(String, SecretString)>,
+}
+
+#[derive(Default)]
+pub struct EntryIndex {
+    // all entries of the unlocked vault by name, the sorted names are kept until something changes
+    entries: HashMap<String, IndexedEntry>,
+    sorted: Option<(EntrySort, Vec<String>)>,
+}
+
+impl EntryIndex {
+    pub fn new(entries: HashMap<String, IndexedEntry>) -> EntryIndex {
+        // index of a freshly unlocked vault
+        EntryIndex { entries, sorted: None }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&IndexedEntry> {
+        // indexed data of an entry
+        self.entries.get(name)
+    }
+
+    pub fn insert(&mut self, name: String, entry: IndexedEntry) {
+        // adds an entry or replaces its old data
+        self.entries.insert(name, entry);
+        self.sorted = None;
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        // forgets an entry that was deleted or renamed
+        self.entries.remove(name);
+        self.sorted = None;
+    }
+
+    pub fn clear(&mut self) {
+        // forgets everything when the vault gets locked
+        self.entries.clear();
+        self.sorted = None;
+    }
+
+    pub fn sort(&mut self, sort: EntrySort) {
+        // puts all names in the given order, sorted again only after a change; ties are sorted by name
+        if self.sorted.as_ref().is_none_or(|(s, _)| *s != sort) {
+            let mut names: Vec<&String> = self.entries.keys().collect();
+            let time = |name: &String| self.metadata(name).and_then(|m| sort.time(&m));
+
+            names.sort_by(|a, b| {
+                let (first, second) = (&self.entries[*a], &self.entries[*b]);
+                match sort {
+                    EntrySort::Template => first.template.cmp(&second.template),
+                    EntrySort::MostUsed => second.uses.cmp(&first.uses),
+                    _ => time(b).cmp(&time(a)),
+                }
+                .then_with(|| a.cmp(b))
+            });
+            let names = names.into_iter().cloned().collect();
+            self.sorted = Some((sort, names));
+        }
+    }
+
+    pub fn names(&self) -> &[String] {
+        // all names in the order of the last sort
+        self.sorted.as_ref().map_or(&[][..], |(_, names)| names.as_slice())
+    }
+
+    pub fn metadata(&self, name: &str) -> Option<EntryMetadata> {
+        // timestamps of an entry
+        self.entries.get(name).and_then(|entry| entry.metadata)
+    }
+
+    pub fn tags(&self, name: &str) -> &[String] {
+        // tags of an entry, empty for unknown ones
+        self.entries.get(name).map_or(&[][..], |entry| entry.tags.as_slice())
+    }
+
+    pub fn summary(&self, name: &str) -> Option<&str> {
+        // first filled non-private field like the username or website, shown next to the name
+        let entry = self.entries.get(name)?;
+        entry.fields.iter().map(|(_, value)| value.as_str()).find(|value| !value.is_empty())
+    }
+
+    pub fn template(&self, name: &str) -> Option<&str> {
+        // display name of the template of an entry
+        self.entries.get(name).map(|entry| entry.template.as_str())
+    }
+
+    pub fn is_favorite(&self, name: &str) -> bool {
+        // checks if an entry is pinned at the top of the list
+        self.entries.get(name).is_some_and(|entry| entry.favorite)
+    }
+
+    pub fn all_tags(&self) -> Vec<&String> {
+        // every used tag once, sorted by name
+        let mut tags: Vec<&String> = self.entries.values().flat_map(|entry| entry.tags.iter()).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+}
+
+pub fn searchable_indices(template: &Template) -> Vec<usize> {
+    // indices of the non-private fields of a template, the name not counted
+    template.elements[1..]
+        .iter()
+        .enumerate()
+        .filter(|(_, element)| !element.private)
+        .map(|(index, _)| index)
+        .collect()
+}
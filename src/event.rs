@@ -1,19 +1,40 @@
-use crate::app::{states::LoginState, App};
-use crossterm::event;
-use std::{error::Error, ops::ControlFlow, time::Duration};
+use crate::{
+    app::{states::LoginState, App},
+    signals,
+};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use std::{error::Error, ops::ControlFlow};
 
 mod login;
 mod manager;
 
-const POLL_RATE: u64 = 100;
-
 pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
-    // handles events like resizing window and key presses every 100ms
+    // handles events like resizing window and key presses in the configured poll rate
     // processes depending on current app state and display
-    if event::poll(Duration::from_millis(POLL_RATE))? {
+    if event::poll(app.config.poll_rate)? {
+        let event = event::read()?;
+        app.register_activity();
+
+        // raw mode disables the terminal signals, so forward them manually
+        if let Event::Key(key) = event {
+            if key.modifiers.contains(KeyModifiers::CONTROL) {
+                match key.code {
+                    KeyCode::Char('c') => {
+                        signals::request_termination();
+                        return Ok(ControlFlow::Continue(()));
+                    }
+                    KeyCode::Char('z') => {
+                        signals::request_suspend();
+                        return Ok(ControlFlow::Continue(()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         return match app.vault_state.state {
-            LoginState::Unlocked => manager::handle_events(app),
-            _ => login::handle_events(app),
+            LoginState::Unlocked => manager::handle_events(app, event),
+            _ => login::handle_events(app, event),
         };
     }
 
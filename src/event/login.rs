@@ -1,14 +1,28 @@
 use crate::password::validate_password_strength;
 use crate::{
     app::{states::LoginState, App},
-    ui::fields::password_field,
+    secret::SecretString,
+    ui::fields::{password_field, wipe_field},
 };
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{Event, KeyCode, KeyModifiers};
 use std::{error::Error, ops::ControlFlow};
 
-pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
+pub fn handle_events(app: &mut App, event: Event) -> Result<ControlFlow<()>, Box<dyn Error>> {
     // handels events when logging in or registering
-    if let Event::Key(key) = event::read()? {
+    if let Event::Key(key) = event {
+        // entering the path of another vault captures all input
+        if app.vault_state.state == LoginState::ChooseVaultPath {
+            match key.code {
+                KeyCode::Esc => app.open_selected_vault(),
+                KeyCode::Enter => app.add_vault(),
+                _ => {
+                    app.text_fields.vault_path_input.input(key);
+                    app.vault_error = None;
+                }
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
         // check for special overall functions
         match key.code {
             // quit application
@@ -19,6 +33,15 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                 }
                 return Ok(ControlFlow::Break(()));
             }
+            // switch between known vaults
+            KeyCode::Up => app.select_previous_vault(),
+            KeyCode::Down => app.select_next_vault(),
+
+            // open or create a vault at another path
+            KeyCode::Char(c) if c == app.config.keys.new_vault && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                app.choose_vault_path()
+            }
+
             // toggle character visibility
             KeyCode::Tab | KeyCode::BackTab => {
                 let field = &mut app.text_fields.password_input;
@@ -45,11 +68,11 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                             let pw_field = &mut app.text_fields.password_input;
 
                             if validate_password_strength(pw_field).0.is_none() {
-                                app.vault_state.set_password(
-                                    app.text_fields.password_input.lines()[0].clone(),
-                                );
+                                app.vault_state.set_password(SecretString::from(
+                                    app.text_fields.password_input.lines()[0].as_str(),
+                                ));
                                 app.vault_state.state = LoginState::NewVaultConfirmNoMatch;
-                                app.text_fields.password_input = password_field();
+                                wipe_field(&mut app.text_fields.password_input, password_field());
                             }
                         }
                         _ => {
@@ -61,7 +84,6 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                             KeyCode::Enter => {
                                 if app
                                     .vault_state
-                                    .clone()
                                     .check_pw(&app.text_fields.password_input.lines()[0]) {
                                     app.setup_vault();
                                 }
@@ -70,7 +92,6 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                                 app.text_fields.password_input.input(key);
                                 if app
                                     .vault_state
-                                    .clone()
                                     .check_pw(&app.text_fields.password_input.lines()[0]) {
                                     app.vault_state.state = LoginState::NewVaultConfirmMatch;
                                 } else {
@@ -1,87 +1,187 @@
 use std::{error::Error, ops::ControlFlow};
 
-use crate::app::App;
-use crossterm::event::{self, Event, KeyCode};
+use crate::app::{extras::{FieldRole, VaultAction}, states::EntryEdit, App};
+use crate::folders::Section;
+use crate::generator::GeneratorTarget;
+use crossterm::event::{Event, KeyCode, KeyModifiers};
 use crate::ui::fields::input_field;
 
 
-pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
+pub fn handle_events(app: &mut App, event: Event) -> Result<ControlFlow<()>, Box<dyn Error>> {
     // handles events when vault is unlocked
-    if let Event::Key(key) = event::read()? {
+    if let Event::Key(key) = event {
+        // the clipboard can be cleared on every page, even while typing
+        if key.code == KeyCode::Char(app.config.keys.clear_clipboard) && key.modifiers.contains(KeyModifiers::CONTROL) {
+            app.clear_clipboard();
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        // an open password prompt captures all input
+        if app.reauth_prompt.is_some() {
+            match key.code {
+                KeyCode::Esc => app.cancel_reauth(),
+                KeyCode::Enter => app.confirm_reauth(),
+                _ => {
+                    app.text_fields.reauth_input.input(key);
+                }
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        // the password generator captures all input, typing changes the symbol set
+        if let Some(generator) = app.generator.as_mut() {
+            match key.code {
+                KeyCode::Esc => app.generator = None,
+                KeyCode::Enter => app.accept_generator(),
+                KeyCode::Up => generator.rows.previous(),
+                KeyCode::Down => generator.rows.next(),
+                KeyCode::Left => generator.change(false),
+                KeyCode::Right => generator.change(true),
+                KeyCode::Tab | KeyCode::BackTab => generator.regenerate(),
+                KeyCode::Backspace => generator.edit_symbols(None),
+                KeyCode::Char(c) => generator.edit_symbols(Some(c)),
+                _ => {}
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        // the input for a new field value, folder or tags captures all input
+        if app.entry_edit.is_some() {
+            match key.code {
+                KeyCode::Char(c) if c == app.config.keys.generator
+                    && key.modifiers.contains(KeyModifiers::CONTROL)
+                    && matches!(app.entry_edit, Some(EntryEdit::Field(_))) => {
+                    app.open_generator(GeneratorTarget::EntryEdit);
+                }
+                KeyCode::Esc => app.cancel_entry_edit(),
+                KeyCode::Enter => app.save_entry_edit(),
+                KeyCode::Tab | KeyCode::BackTab => app.toggle_field_input_mask(),
+                _ => {
+                    app.text_fields.field_input.input(key);
+                }
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        // previous values of a field
+        if app.field_history.is_some() {
+            match key.code {
+                KeyCode::Esc | KeyCode::Left => app.close_field_history(),
+                KeyCode::Up => app.field_history.as_mut().unwrap().values.previous(),
+                KeyCode::Down => app.field_history.as_mut().unwrap().values.next(),
+                KeyCode::Tab | KeyCode::BackTab => app.toggle_history_visibility(),
+                KeyCode::Enter => app.restore_history_value(),
+                KeyCode::Char(c) if c == app.config.keys.copy => app.copy_history_value(),
+                _ => {}
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
         // match inputs depending on currently displayed page
         match app.page_index.index {
             0 => {
                 match app.page_selected {
-                    // credentials left side
-                    false => match key.code {
-                        KeyCode::Esc => {
-                            if app.text_fields.search_bar.is_empty() {
-                                app.lock_vault();
-                            } else {
-                                app.text_fields.search_bar = input_field();
-                                app.update_entries();
+                    // credentials left side, an entry stays displayed only while it is highlighted
+                    false => {
+                        match key.code {
+                            KeyCode::Esc => {
+                                if app.text_fields.search_bar.is_empty() {
+                                    app.lock_vault();
+                                } else {
+                                    app.text_fields.search_bar = input_field();
+                                    app.update_entries();
+                                }
                             }
-                        }
 
-                        KeyCode::Tab => app.page_index.page_up(),
-                        KeyCode::BackTab => app.page_index.page_down(),
+                            KeyCode::Tab => app.next_page(),
+                            KeyCode::BackTab => app.previous_page(),
 
-                        KeyCode::Up => app.entries_list.previous(),
-                        KeyCode::Down => app.entries_list.next(),
+                            KeyCode::Up => {
+                                app.entries_list.previous();
+                                app.list_status = None;
+                            }
+                            KeyCode::Down => {
+                                app.entries_list.next();
+                                app.list_status = None;
+                            }
 
-                        KeyCode::Enter => {
-                            app.display_entry();
-                            app.select_entry();
-                        }
-                        KeyCode::Right => {
-                            if app.text_fields.search_bar.is_empty() {
+                            // folders open and close, entries get displayed
+                            KeyCode::Enter if app.selected_entry().is_none() => app.toggle_folder(None),
+                            KeyCode::Right if app.selected_entry().is_none() => app.toggle_folder(Some(true)),
+                            KeyCode::Left if app.text_fields.search_bar.is_empty() => app.toggle_folder(Some(false)),
+
+                            KeyCode::Enter => {
+                                app.display_entry();
+                                app.select_entry();
+                            }
+                            KeyCode::Right if app.text_fields.search_bar.is_empty() => {
                                 if app.current_entry.is_none() {
                                     app.display_entry();
                                 }
                                 app.select_entry();
-                            } else {
+                            }
+
+                            // change the order or grouping of the list or the shown tag by the configured keys together with ctrl
+                            KeyCode::Char(c) if c == app.config.keys.sort && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.change_entry_sort();
+                            }
+                            KeyCode::Char(c) if c == app.config.keys.group && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.toggle_template_groups();
+                            }
+                            KeyCode::Char(c) if c == app.config.keys.filter_tag && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.change_tag_filter();
+                            }
+
+                            // copy from the highlighted entry without opening it
+                            KeyCode::Char(c) if c == app.config.keys.copy_username && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.quick_copy(FieldRole::Username);
+                            }
+                            KeyCode::Char(c) if c == app.config.keys.copy_password && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.quick_copy(FieldRole::Password);
+                            }
+                            KeyCode::Char(c) if c == app.config.keys.copy_sequence && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.copy_sequence();
+                            }
+
+                            // go through favorites and recent entries without searching
+                            KeyCode::Char(c) if c == app.config.keys.next_favorite && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.jump_to_section(Section::Favorites);
+                            }
+                            KeyCode::Char(c) if c == app.config.keys.next_recent && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                app.jump_to_section(Section::Recent);
+                            }
+
+                            // fill input field if no matching action
+                            _ => {
                                 app.text_fields.search_bar.input(key);
                                 app.update_entries();
                             }
                         }
-
-                        // fill input field if no matching action
-                        _ => {
-                            app.text_fields.search_bar.input(key);
-                            app.update_entries();
-                        }
-                    },
+                        app.close_stale_entry();
+                    }
                     // credentials right side
                     true => match key.code {
                         KeyCode::Esc | KeyCode::Right | KeyCode::Left => {
                             app.unselect_right();
+                            app.refresh_entries();
                             app.delete_confirm = false;
                         }
 
                         // moves focus up or down on entries
                         KeyCode::Up => {
-                            app.current_entry.as_mut().unwrap().1.previous();
+                            app.current_entry.as_mut().unwrap().fields.previous();
                             app.delete_confirm = false;
                         }
                         KeyCode::Down => {
-                            app.current_entry.as_mut().unwrap().1.next();
+                            app.current_entry.as_mut().unwrap().fields.next();
                             app.delete_confirm = false;
                         }
 
-                        KeyCode::Tab | KeyCode::BackTab => {
-                            let entry = app
-                                .current_entry
-                                .as_mut()
-                                .unwrap()
-                                .1
-                                .current_item_mut()
-                                .unwrap();
-                            entry.2 = !entry.2;
-                        }
+                        KeyCode::Tab | KeyCode::BackTab => app.toggle_field_visibility(),
 
                         KeyCode::Enter => {
-                            let entries = app.current_entry.as_ref().unwrap();
-                            if entries.1.current_index().unwrap() == entries.1.items.len() - 1 {
+                            let entries = &app.current_entry.as_ref().unwrap().fields;
+                            if entries.current_index().unwrap() == entries.items.len() - 1 {
                                 // delete entry when confirmed
                                 if app.delete_confirm {
                                     app.delete_entry();
@@ -92,22 +192,28 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                             }
                         }
 
-                        // copy by pressing "c"
-                        KeyCode::Char('c') => {
-                            let entries = app.current_entry.as_ref().unwrap();
-                            if entries.1.current_index().unwrap() != entries.1.items.len() - 1 {
-                                let text = app
-                                    .current_entry
-                                    .as_ref()
-                                    .unwrap()
-                                    .1
-                                    .current_item()
-                                    .unwrap()
-                                    .1
-                                    .clone();
-                                app.copy_to_clipboard(text);
-                            }
+                        // copy, edit, history and toggle re-authentication by their configured keys
+                        KeyCode::Char(c) if c == app.config.keys.copy => app.copy_current_field(),
+                        KeyCode::Char(c) if c == app.config.keys.sensitive => app.toggle_sensitive(),
+                        KeyCode::Char(c) if c == app.config.keys.edit => {
+                            app.edit_current_field();
+                            app.delete_confirm = false;
+                        }
+                        KeyCode::Char(c) if c == app.config.keys.history => {
+                            app.show_field_history();
+                            app.delete_confirm = false;
+                        }
+
+                        // move the entry to another folder, change its tags and pin it as favorite
+                        KeyCode::Char(c) if c == app.config.keys.folder => {
+                            app.edit_entry_folder();
+                            app.delete_confirm = false;
+                        }
+                        KeyCode::Char(c) if c == app.config.keys.tags => {
+                            app.edit_entry_tags();
+                            app.delete_confirm = false;
                         }
+                        KeyCode::Char(c) if c == app.config.keys.favorite => app.toggle_favorite(),
                         _ => {}
                     },
                 }
@@ -120,8 +226,8 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                             app.lock_vault();
                         }
 
-                        KeyCode::Tab => app.page_index.page_up(),
-                        KeyCode::BackTab => app.page_index.page_down(),
+                        KeyCode::Tab => app.next_page(),
+                        KeyCode::BackTab => app.previous_page(),
 
                         KeyCode::Up => app.templates.previous(),
                         KeyCode::Down => app.templates.next(),
@@ -185,6 +291,13 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                             }
                         }
 
+                        // open the generator on a private field
+                        KeyCode::Char(c) if c == app.config.keys.generator && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            if let Some(index) = app.text_fields.edit_fields.as_ref().unwrap().current_index() {
+                                app.open_generator(GeneratorTarget::NewEntry(index));
+                            }
+                        }
+
                         KeyCode::Enter => {
                             // select next or confirm button
                             let fields = app.text_fields.edit_fields.as_ref().unwrap();
@@ -224,14 +337,170 @@ pub fn handle_events(app: &mut App) -> Result<ControlFlow<()>, Box<dyn Error>> {
                 }
             }
             2 => {
-                // template page (under construction)
+                // template page, entries of a template can be made to require the master password
                 match key.code {
                     KeyCode::Esc => {
                         app.lock_vault();
                     }
 
-                    KeyCode::Tab => app.page_index.page_up(),
-                    KeyCode::BackTab => app.page_index.page_down(),
+                    KeyCode::Tab => app.next_page(),
+                    KeyCode::BackTab => app.previous_page(),
+
+                    KeyCode::Up => app.templates.previous(),
+                    KeyCode::Down => app.templates.next(),
+                    KeyCode::Enter => app.toggle_template_reauthenticate(),
+
+                    _ => {}
+                }
+            }
+            3 => {
+                match app.page_selected {
+                    // vault maintenance page
+                    false => match key.code {
+                        KeyCode::Esc => {
+                            app.lock_vault();
+                        }
+
+                        KeyCode::Tab => app.next_page(),
+                        KeyCode::BackTab => app.previous_page(),
+
+                        KeyCode::Up => {
+                            app.vault_actions.previous();
+                            app.vault_status = None;
+                            app.doctor_report = None;
+                        }
+                        KeyCode::Down => {
+                            app.vault_actions.next();
+                            app.vault_status = None;
+                            app.doctor_report = None;
+                        }
+                        KeyCode::Enter => app.run_vault_action(),
+
+                        // apply fixes of the last health check by its configured key
+                        KeyCode::Char(c) if c == app.config.keys.repair => app.repair_vault(),
+
+                        _ => {}
+                    },
+                    // entries of an opened backup
+                    true if app.backup_entries.is_some() => match key.code {
+                        KeyCode::Esc | KeyCode::Left => app.close_backup(),
+
+                        KeyCode::Up => {
+                            app.backup_entries.as_mut().unwrap().previous();
+                            app.restore_confirm = false;
+                        }
+                        KeyCode::Down => {
+                            app.backup_entries.as_mut().unwrap().next();
+                            app.restore_confirm = false;
+                        }
+                        KeyCode::Enter => {
+                            app.restore_backup_entry();
+                            app.restore_confirm = false;
+                        }
+
+                        // restore the whole vault by pressing its configured key twice
+                        KeyCode::Char(c) if c == app.config.keys.restore_vault => app.restore_whole_vault(),
+
+                        _ => {}
+                    },
+                    // settings stored in the vault
+                    true if app.vault_actions.current_item() == Some(&VaultAction::Settings) => match key.code {
+                        KeyCode::Esc => app.leave_vault_settings(),
+
+                        KeyCode::Up => {
+                            app.settings_list.previous();
+                            app.vault_status = None;
+                        }
+                        KeyCode::Down => {
+                            app.settings_list.next();
+                            app.vault_status = None;
+                        }
+                        KeyCode::Right | KeyCode::Enter => app.change_vault_setting(true),
+                        KeyCode::Left => app.change_vault_setting(false),
+
+                        _ => {}
+                    },
+                    // list of backups
+                    true => match key.code {
+                        KeyCode::Esc | KeyCode::Left => {
+                            app.unselect_right();
+                            app.vault_status = None;
+                        }
+
+                        KeyCode::Up => {
+                            app.backups.previous();
+                            app.vault_status = None;
+                        }
+                        KeyCode::Down => {
+                            app.backups.next();
+                            app.vault_status = None;
+                        }
+                        KeyCode::Enter | KeyCode::Right => app.open_backup(),
+
+                        _ => {}
+                    },
+                }
+            }
+            4 => {
+                // audit trail, typing filters the records
+                match key.code {
+                    KeyCode::Esc => {
+                        if app.text_fields.audit_search.is_empty() {
+                            app.lock_vault();
+                        } else {
+                            app.text_fields.audit_search = input_field();
+                            app.filter_audit_trail();
+                        }
+                    }
+
+                    KeyCode::Tab => app.next_page(),
+                    KeyCode::BackTab => app.previous_page(),
+
+                    KeyCode::Up => app.audit_list.previous(),
+                    KeyCode::Down => app.audit_list.next(),
+
+                    // switch the shown action
+                    KeyCode::Right => app.change_audit_filter(true),
+                    KeyCode::Left => app.change_audit_filter(false),
+
+                    // export the shown records by the configured key together with ctrl
+                    KeyCode::Char(c) if c == app.config.keys.export && key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        app.export_audit_trail();
+                    }
+
+                    _ => {
+                        app.text_fields.audit_search.input(key);
+                        app.filter_audit_trail();
+                    }
+                }
+            }
+            5 => {
+                // trashed entries
+                match key.code {
+                    KeyCode::Esc => {
+                        app.lock_vault();
+                    }
+
+                    KeyCode::Tab => app.next_page(),
+                    KeyCode::BackTab => app.previous_page(),
+
+                    KeyCode::Up => {
+                        app.trash_list.previous();
+                        app.purge_confirm = false;
+                        app.trash_status = None;
+                    }
+                    KeyCode::Down => {
+                        app.trash_list.next();
+                        app.purge_confirm = false;
+                        app.trash_status = None;
+                    }
+                    KeyCode::Enter => {
+                        app.restore_from_trash();
+                        app.purge_confirm = false;
+                    }
+
+                    // purge permanently by pressing its configured key twice
+                    KeyCode::Char(c) if c == app.config.keys.purge => app.purge_from_trash(),
 
                     _ => {}
                 }
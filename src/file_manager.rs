@@ -1,95 +1,213 @@
-use chrono::Utc;
+use chrono::{Datelike, NaiveDateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
 use std::{
-    fs::{self, File},
-    io::{self, Read},
-    path::PathBuf,
+    collections::HashSet,
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::Mutex,
 };
 
+use crate::{config::BackupPolicy, db_interface};
 
-const PATH: [&str; 1] = ["RustwordManager"];
+mod legacy;
+
+
+const APP_DIR: &str = "rustword_manager";
 const DB_NAME: &str = "passwords.sqlite3";
+const REGISTRY_NAME: &str = "vaults.json";
+const DEFAULT_VAULT: &str = "Default";
+const EXPORT_DIR: &str = "exports";
 
-pub struct FileManager {
-    // interacts with the filesystem
-    pub filepath: PathBuf,
-    pub salt: Option<[u8; 16]>,
+// milliseconds keep backups taken within the same second apart
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+// exports and backups of older versions are named to the second
+const SECOND_TIME_FORMAT: &str = "%Y%m%d_%H%M%S";
+// log files of versions that created one per start
+const OLD_LOG_PREFIX: &str = "RWManager_";
+
+// lock file of the vault this process holds, the panic hook releases it as well
+static HELD_LOCK: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub struct BackupFile {
+    // a backup found in the backup directory
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+    pub label: Option<String>,
 }
 
-impl FileManager {
-    pub fn new() -> FileManager {
-        // create new file manager that holds path
-        FileManager {
-            filepath: FileManager::get_db_path(),
-            salt: None,
+impl BackupFile {
+    fn from_path(path: PathBuf, prefix: &str) -> Option<BackupFile> {
+        // parses names like <vault>_20240101_120000_123[_label].sqlite3, older ones lack the milliseconds
+        let name = path.file_name()?.to_str()?;
+        let stem = name.strip_prefix(prefix)?.strip_prefix('_')?.strip_suffix(".sqlite3")?;
+
+        let (created, rest) = match stem.get(..19).and_then(|time| NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT).ok()) {
+            Some(created) => (created, &stem[19..]),
+            None => (NaiveDateTime::parse_from_str(stem.get(..15)?, SECOND_TIME_FORMAT).ok()?, &stem[15..]),
+        };
+        if !rest.is_empty() && !rest.starts_with('_') {
+            return None;
         }
+        let label = rest.get(1..).filter(|l| !l.is_empty()).map(str::to_string);
+
+        Some(BackupFile { path, created, label })
     }
 
-    fn get_db_path() -> PathBuf {
-        // gets the db path if not exist
-        let mut home_dir = dirs::home_dir().expect("Failed to open Home directory");
-        for part in PATH {
-            home_dir.push(part);
+    pub fn name(&self) -> String {
+        // name shown in the restore screen
+        let created = self.created.format("%Y-%m-%d %H:%M:%S UTC");
+        match &self.label {
+            Some(label) => format!("{} ({})", created, label.replace('-', " ")),
+            None => created.to_string(),
         }
+    }
+}
 
-        home_dir
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct VaultEntry {
+    // a known vault as stored in the registry
+    pub name: String,
+    pub path: PathBuf,
+}
+
+pub struct AppDirs {
+    // directories of the application following the XDG base directories
+    pub data: PathBuf,
+    pub config: PathBuf,
+    pub state: PathBuf,
+    pub runtime: Option<PathBuf>,
+}
+
+impl AppDirs {
+    pub fn new() -> AppDirs {
+        // each directory can be overridden by an environment variable
+        AppDirs {
+            data: AppDirs::resolve("RWM_DATA_DIR", dirs::data_dir()),
+            config: AppDirs::resolve("RWM_CONFIG_DIR", dirs::config_dir()),
+            state: AppDirs::resolve("RWM_STATE_DIR", dirs::state_dir().or_else(dirs::data_local_dir)),
+            runtime: AppDirs::override_dir("RWM_RUNTIME_DIR")
+                .or_else(|| dirs::runtime_dir().map(|dir| dir.join(APP_DIR))),
+        }
     }
 
-    pub fn create_path(&self) -> io::Result<PathBuf> {
-        // creates the db path if not exists
-        fs::create_dir_all(self.filepath.as_path())?;
+    fn resolve(variable: &str, base: Option<PathBuf>) -> PathBuf {
+        // uses the override or the application directory inside the base directory
+        AppDirs::override_dir(variable).unwrap_or_else(|| {
+            base.expect("Failed to open Home directory").join(APP_DIR)
+        })
+    }
 
-        let mut filepath = self.filepath.clone();
-        filepath.push(DB_NAME);
+    fn override_dir(variable: &str) -> Option<PathBuf> {
+        // reads a directory from the environment, empty values are ignored
+        env::var_os(variable)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    }
 
-        Ok(filepath)
+    pub fn create(&self) -> io::Result<()> {
+        // creates all directories if not exist
+        for dir in [&self.data, &self.config, &self.state].into_iter().chain(self.runtime.as_ref()) {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(())
     }
+}
 
-    pub fn check_db_exist(&self) -> bool {
-        // returns a boolean weather the Database file exists
-        let mut filepath = self.filepath.clone();
-        filepath.push(DB_NAME);
+pub struct Vault {
+    // a single vault file with its own lock and salt
+    pub name: String,
+    pub path: PathBuf,
+    lock_dir: Option<PathBuf>,
+    salt: Option<[u8; 16]>,
+    locked: bool,
+}
 
-        if let Ok(metadata) = fs::metadata(filepath) {
-            return metadata.is_file();
+impl Vault {
+    pub fn new(entry: &VaultEntry, lock_dir: Option<PathBuf>) -> Vault {
+        // vault that is neither locked nor read yet
+        Vault {
+            name: entry.name.clone(),
+            path: entry.path.clone(),
+            lock_dir,
+            salt: None,
+            locked: false,
         }
+    }
 
-        false
+    pub fn entry(&self) -> VaultEntry {
+        // returns the registry entry of this vault
+        VaultEntry { name: self.name.clone(), path: self.path.clone() }
     }
 
-    pub fn check_lock_set(&self) -> io::Result<bool> {
-        // sets a file-lock if instance is running
-        // returns weather an instance is already running
+    pub fn exists(&self) -> bool {
+        // returns a boolean weather the Database file exists
+        fs::metadata(&self.path).is_ok_and(|metadata| metadata.is_file())
+    }
+
+    pub fn create_parent(&self) -> io::Result<()> {
+        // creates the directory the vault gets stored in
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(())
+    }
 
-        let mut filepath = self.filepath.clone();
-        filepath.push("lock");
+    pub fn get_lock_path(&self) -> PathBuf {
+        // path of the file marking that an instance uses this vault
+        // locks live in the runtime directory if there is one, otherwise next to the vault
+        match &self.lock_dir {
+            Some(lock_dir) => lock_dir.join(format!(
+                "{}-{:016x}.lock",
+                self.file_stem(),
+                path_hash(&self.path),
+            )),
+            None => {
+                let mut lock_path = self.path.clone().into_os_string();
+                lock_path.push(".lock");
 
-        // checks if file exists
-        let mut exists = false;
-        if let Ok(metadata) = fs::metadata(filepath.as_path()) {
-            if metadata.is_file() {
-                exists = true;
+                PathBuf::from(lock_path)
             }
         }
+    }
 
-        // locks file
-        if !exists {
-            File::create(filepath.as_path())?;
+    pub fn acquire_lock(&mut self) -> io::Result<bool> {
+        // atomically creates the lock file, returns false if another instance holds it
+        if self.locked {
+            return Ok(true);
+        }
+        self.create_parent()?;
+        if let Some(lock_dir) = &self.lock_dir {
+            fs::create_dir_all(lock_dir)?;
         }
 
-        Ok(exists)
+        // the lock file names the owning process, so only that one removes it after a panic
+        match OpenOptions::new().write(true).create_new(true).open(self.get_lock_path()) {
+            Ok(mut file) => {
+                self.locked = true;
+                *HELD_LOCK.lock().unwrap_or_else(|e| e.into_inner()) = Some(self.get_lock_path());
+                file.write_all(process::id().to_string().as_bytes())?;
+                log::info!("Acquired lock of vault");
+                Ok(true)
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(e),
+        }
     }
 
-    pub fn release_file_lock(&self) -> io::Result<()> {
-        // release the lock of the instance
-
-        let mut filepath = self.filepath.clone();
-        filepath.push("lock");
-
-        if fs::metadata(filepath.as_path()).is_ok() {
-            // Attempt to remove the file
-            fs::remove_file(filepath.as_path())?;
+    pub fn release_lock(&mut self) -> io::Result<()> {
+        // removes the lock file if this instance holds it, the panic hook may have removed it already
+        if self.locked {
+            self.locked = false;
+            HELD_LOCK.lock().unwrap_or_else(|e| e.into_inner()).take_if(|path| *path == self.get_lock_path());
+            match fs::remove_file(self.get_lock_path()) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => log::info!("Released lock of vault"),
+            }
         }
-
         Ok(())
     }
 
@@ -98,7 +216,7 @@ pub fn release_file_lock(&self) -> io::Result<()> {
         if let Some(salt) = self.salt {
             Ok(salt)
         } else {
-            let mut file = File::open(self.create_path().unwrap())?;
+            let mut file = File::open(&self.path)?;
 
             let mut buf = [0; 16];
             file.read_exact(&mut buf)?;
@@ -110,16 +228,480 @@ pub fn release_file_lock(&self) -> io::Result<()> {
         }
     }
 
-    pub fn get_logger_path(&self) -> PathBuf {
-        // creates a new logging path
+    pub fn get_backup_dir(&self) -> PathBuf {
+        // directory holding all backups, shared by vaults stored in the same directory
+        let mut backup_dir = self.path.parent().map(Path::to_path_buf).unwrap_or_default();
+        backup_dir.push("backups");
+
+        backup_dir
+    }
+
+    fn file_stem(&self) -> String {
+        // backups and locks are named after the vault file to tell vaults apart
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    fn get_backup_path(&self, label: Option<&str>) -> io::Result<PathBuf> {
+        // creates the backup directory and returns a new timestamped file path in it
+        let mut backup_path = self.get_backup_dir();
+        fs::create_dir_all(backup_path.as_path())?;
+
+        let timestamp = Utc::now().format(BACKUP_TIME_FORMAT);
+        backup_path.push(match label {
+            Some(label) => format!("{}_{}_{}.sqlite3", self.file_stem(), timestamp, label),
+            None => format!("{}_{}.sqlite3", self.file_stem(), timestamp),
+        });
+
+        Ok(backup_path)
+    }
+
+    pub fn backup_vault(&mut self, source: &Connection, db_key: &str, label: Option<&str>) -> Result<PathBuf, String> {
+        // takes a consistent backup of an open vault, only verified backups get their final name
+        let salt = self.get_salt().map_err(|e| e.to_string())?;
+        let backup_path = self.get_backup_path(label).map_err(|e| e.to_string())?;
+        let partial = backup_path.with_extension("partial");
+
+        if partial.exists() {
+            fs::remove_file(&partial).map_err(|e| e.to_string())?;
+        }
+
+        let verified = db_interface::backup_into(source, &partial, db_key, &salt)
+            .and_then(|_| db_interface::open_read_only(&partial, db_key))
+            .and_then(|copy| db_interface::check_integrity(&copy))
+            .map_err(|e| e.to_string())
+            .and_then(|messages| match messages == ["ok"] {
+                true => Ok(()),
+                false => Err(format!("Backup is damaged: {}", messages.join(", "))),
+            });
+
+        // an existing backup is never replaced by a newer state
+        let verified = verified.and_then(|_| match backup_path.exists() {
+            true => Err(format!("Backup {} exists already", backup_path.display())),
+            false => Ok(()),
+        });
+
+        if let Err(error) = verified {
+            fs::remove_file(&partial).unwrap_or(());
+            return Err(error);
+        }
+        FileManager::replace_file(&partial, &backup_path).map_err(|e| e.to_string())?;
+        log::info!("Created backup of vault");
+
+        Ok(backup_path)
+    }
+
+    pub fn list_backups(&self) -> io::Result<Vec<BackupFile>> {
+        // returns all backups of this vault with a readable name, newest first
+        let entries = match fs::read_dir(self.get_backup_dir()) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let prefix = self.file_stem();
+        let mut backups: Vec<BackupFile> = entries
+            .filter_map(|entry| BackupFile::from_path(entry.ok()?.path(), &prefix))
+            .collect();
+
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created));
+        Ok(backups)
+    }
+
+    pub fn rotate_backups(&self, policy: &BackupPolicy) -> io::Result<()> {
+        // keeps the newest automatic backups and the newest one of each of the last days and weeks,
+        // backups with a label are kept until they get removed by hand
+        let mut days = HashSet::new();
+        let mut weeks = HashSet::new();
+
+        for (index, backup) in self.list_backups()?.into_iter().filter(|b| b.label.is_none()).enumerate() {
+            let day = backup.created.date();
+            let week = (day.iso_week().year(), day.iso_week().week());
+            let mut keep = index < policy.keep_recent;
+
+            if !days.contains(&day) && days.len() < policy.keep_daily {
+                days.insert(day);
+                keep = true;
+            }
+            if !weeks.contains(&week) && weeks.len() < policy.keep_weekly {
+                weeks.insert(week);
+                keep = true;
+            }
+
+            if !keep {
+                fs::remove_file(&backup.path)?;
+                log::info!("Removed expired backup");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Vault {
+    fn drop(&mut self) {
+        // also runs while unwinding after a panic
+        if self.release_lock().is_err() {
+            log::warn!("Failed to release lock of vault");
+        }
+    }
+}
+
+pub struct FileManager {
+    // interacts with the filesystem
+    pub dirs: AppDirs,
+    pub vault: Vault,
+    registry: Vec<VaultEntry>,
+    // why an old installation was left in place, shown on the login screen
+    pub legacy_warning: Option<String>,
+}
+
+impl FileManager {
+    pub fn new() -> FileManager {
+        // create new file manager that holds the directories and the known vaults
+        let dirs = AppDirs::new();
+        let registry = FileManager::load_registry(&dirs);
+
+        FileManager {
+            vault: Vault::new(&registry[0], dirs.runtime.clone()),
+            dirs,
+            registry,
+            legacy_warning: None,
+        }
+    }
+
+    pub fn create_path(&self) -> io::Result<()> {
+        // creates the application directories if not exist
+        self.dirs.create()
+    }
+
+    pub fn migrate_legacy(&mut self) {
+        // moves an installation from the old home directory folder to the XDG directories
+        match legacy::migrate(&self.dirs) {
+            Ok(true) => {
+                self.registry = FileManager::load_registry(&self.dirs);
+                self.vault = self.vault_from(&self.registry[0]);
+            }
+            Ok(false) => {}
+            Err(error) => self.legacy_warning = Some(error),
+        }
+    }
+
+    fn load_registry(dirs: &AppDirs) -> Vec<VaultEntry> {
+        // reads the known vaults, the default vault is always known
+        let default = VaultEntry {
+            name: DEFAULT_VAULT.to_string(),
+            path: dirs.data.join(DB_NAME),
+        };
+
+        let mut registry: Vec<VaultEntry> = fs::read(dirs.config.join(REGISTRY_NAME))
+            .ok()
+            .and_then(|content| match serde_json::from_slice(&content) {
+                Ok(registry) => Some(registry),
+                Err(error) => {
+                    log::error!("Failed to read vault registry: {error}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        if !registry.iter().any(|v| v.path == default.path) {
+            registry.insert(0, default);
+        }
+        registry
+    }
+
+    pub fn registered_vaults(&self) -> &[VaultEntry] {
+        // returns all known vaults, the default one first
+        &self.registry
+    }
+
+    pub fn register_vault(&mut self, entry: VaultEntry) -> io::Result<()> {
+        // remembers a vault for the picker
+        if self.registry.iter().any(|v| v.path == entry.path) {
+            return Ok(());
+        }
+
+        self.registry.push(entry);
+        let content = serde_json::to_vec_pretty(&self.registry)?;
+        fs::write(self.dirs.config.join(REGISTRY_NAME), content)?;
+
+        log::info!("Registered new vault");
+        Ok(())
+    }
+
+    pub fn select_vault(&mut self, entry: &VaultEntry) -> io::Result<bool> {
+        // switches to another vault, returns false if another instance uses it
+        if self.vault.path != entry.path {
+            // drops the old vault which releases its lock
+            self.vault = self.vault_from(entry);
+        }
+
+        self.vault.acquire_lock()
+    }
+
+    pub fn vault_from(&self, entry: &VaultEntry) -> Vault {
+        // vault with its lock in the runtime directory of the application
+        Vault::new(entry, self.dirs.runtime.clone())
+    }
+
+    pub fn replace_file(source: &Path, target: &Path) -> io::Result<()> {
+        // atomically moves a fully written file over another one
+        File::open(source)?.sync_all()?;
+        fs::rename(source, target)?;
+
+        // persist the rename itself, only possible on unix
+        #[cfg(unix)]
+        if let Some(parent) = target.parent() {
+            File::open(parent)?.sync_all()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_export(&self, kind: &str, content: &str) -> io::Result<PathBuf> {
+        // writes an export of the selected vault into the data directory, only readable by the owner
+        let export_dir = self.dirs.data.join(EXPORT_DIR);
+        fs::create_dir_all(&export_dir)?;
+
+        let path = export_dir.join(format!(
+            "{}_{}_{}.csv",
+            self.vault.file_stem(),
+            kind,
+            Utc::now().format(SECOND_TIME_FORMAT),
+        ));
 
-        let mut logging_path = self.filepath.clone();
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
 
-        logging_path.push("logs");
-        logging_path.push(
-            &format!("RWManager_{}.log", Utc::now().format("%Y%m%d_%H%M%S"))
+        let mut file = options.open(&path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        Ok(path)
+    }
+
+    pub fn get_log_dir(&self) -> PathBuf {
+        // directory holding the current and the rotated log files
+        self.dirs.state.join("logs")
+    }
+
+    pub fn remove_old_logs(&self, keep: usize) -> io::Result<()> {
+        // older versions created a new log file on every start, only the latest of them are kept
+        let mut old_logs = fs::read_dir(self.get_log_dir())?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(OLD_LOG_PREFIX) && name.ends_with(".log"))
+            })
+            .collect::<Vec<PathBuf>>();
+
+        // the timestamp in the name sorts them by age
+        old_logs.sort();
+        let count = old_logs.len().saturating_sub(keep);
+        for path in old_logs.into_iter().take(count) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn resolve_vault_path(input: &str) -> PathBuf {
+    // expands a leading ~ and adds the file extension if missing, directories are kept as they are
+    let path = match input.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(input),
+    };
+
+    let path = if path.extension().is_none() && !path.is_dir() {
+        path.with_extension("sqlite3")
+    } else {
+        path
+    };
+
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+pub fn release_held_lock() {
+    // removes the lock file held by this process without relying on destructors, used by the panic hook;
+    // the file is left alone unless it names this process
+    let Ok(mut held) = HELD_LOCK.try_lock() else {
+        return;
+    };
+    let Some(path) = held.take() else {
+        return;
+    };
+
+    if fs::read_to_string(&path).is_ok_and(|owner| owner.trim() == process::id().to_string()) {
+        fs::remove_file(&path).unwrap_or(());
+    }
+}
+
+pub fn vault_entry_from_path(path: PathBuf) -> VaultEntry {
+    // vault named after its file
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| DEFAULT_VAULT.to_string());
+
+    VaultEntry { name, path }
+}
+
+fn path_hash(path: &Path) -> u64 {
+    // FNV-1a hash of a path, stable across builds unlike the std hasher
+    path.as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{thread, time::Duration};
+
+    fn test_vault(name: &str) -> Vault {
+        // vault inside a fresh directory below the temporary directory
+        let dir = env::temp_dir().join(format!("rwm-{name}-{}", std::process::id()));
+        fs::remove_dir_all(&dir).unwrap_or(());
+        fs::create_dir_all(dir.join("backups")).unwrap();
+
+        Vault::new(&vault_entry_from_path(dir.join("vault.sqlite3")), None)
+    }
+
+    fn remaining(vault: &Vault) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(vault.get_backup_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn parses_backup_names() {
+        let backup = BackupFile::from_path(PathBuf::from("vault_20240110_120000_before-restore.sqlite3"), "vault").unwrap();
+        assert_eq!(backup.name(), "2024-01-10 12:00:00 UTC (before restore)");
+        assert_eq!(backup.label.as_deref(), Some("before-restore"));
+
+        assert!(BackupFile::from_path(PathBuf::from("vault_20240110_120000.sqlite3"), "vault").unwrap().label.is_none());
+        assert!(BackupFile::from_path(PathBuf::from("other_20240110_120000.sqlite3"), "vault").is_none());
+        assert!(BackupFile::from_path(PathBuf::from("vault_2024.sqlite3"), "vault").is_none());
+        assert!(BackupFile::from_path(PathBuf::from("vault_20240110_120000x.sqlite3"), "vault").is_none());
+    }
+
+    #[test]
+    fn parses_backup_names_with_milliseconds() {
+        let first = BackupFile::from_path(PathBuf::from("vault_20240110_120000_042.sqlite3"), "vault").unwrap();
+        let second = BackupFile::from_path(PathBuf::from("vault_20240110_120000_317_before-purge.sqlite3"), "vault").unwrap();
+        assert!(first.label.is_none());
+        assert_eq!(second.label.as_deref(), Some("before-purge"));
+        assert!(second.created > first.created);
+        assert_eq!(second.name(), "2024-01-10 12:00:00 UTC (before purge)");
+    }
+
+    #[test]
+    fn backups_taken_in_the_same_second_get_different_names() {
+        let vault = test_vault("names");
+        let first = vault.get_backup_path(None).unwrap();
+        thread::sleep(Duration::from_millis(2));
+        let second = vault.get_backup_path(None).unwrap();
+        assert_ne!(first, second);
+        assert!(BackupFile::from_path(second, "vault").is_some());
+        fs::remove_dir_all(vault.path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rotation_keeps_recent_daily_and_weekly_backups() {
+        let vault = test_vault("rotation");
+        let files = [
+            "vault_20240110_120000.sqlite3",
+            "vault_20240110_080000.sqlite3",
+            "vault_20240110_070000.sqlite3",
+            "vault_20240109_120000.sqlite3",
+            "vault_20240108_120000.sqlite3",
+            "vault_20240101_120000.sqlite3",
+            "vault_20231220_120000.sqlite3",
+            "vault_20231201_120000_before-restore.sqlite3",
+            "other_20231201_120000.sqlite3",
+        ];
+        for file in files {
+            File::create(vault.get_backup_dir().join(file)).unwrap();
+        }
+
+        let policy = BackupPolicy { keep_recent: 2, keep_daily: 2, keep_weekly: 2, interval: Duration::ZERO };
+        vault.rotate_backups(&policy).unwrap();
+
+        assert_eq!(
+            remaining(&vault),
+            [
+                "other_20231201_120000.sqlite3",
+                "vault_20231201_120000_before-restore.sqlite3",
+                "vault_20240101_120000.sqlite3",
+                "vault_20240109_120000.sqlite3",
+                "vault_20240110_080000.sqlite3",
+                "vault_20240110_120000.sqlite3",
+            ],
         );
+        fs::remove_dir_all(vault.path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rotation_keeps_one_backup_per_day_and_week() {
+        let vault = test_vault("retention");
+        // two backups a day from Monday 2024-01-01 to Sunday 2024-01-21
+        for day in 1..=21 {
+            for hour in ["080000_000", "200000_000"] {
+                File::create(vault.get_backup_dir().join(format!("vault_202401{day:02}_{hour}.sqlite3"))).unwrap();
+            }
+        }
+
+        let policy = BackupPolicy { keep_recent: 1, keep_daily: 3, keep_weekly: 3, interval: Duration::ZERO };
+        vault.rotate_backups(&policy).unwrap();
+
+        assert_eq!(
+            remaining(&vault),
+            [
+                // newest of the oldest week, then of the week before the current one
+                "vault_20240107_200000_000.sqlite3",
+                "vault_20240114_200000_000.sqlite3",
+                // newest of each of the last three days, the newest one also counts for the current week
+                "vault_20240119_200000_000.sqlite3",
+                "vault_20240120_200000_000.sqlite3",
+                "vault_20240121_200000_000.sqlite3",
+            ],
+        );
+
+        // without daily and weekly backups only the recent ones stay
+        let policy = BackupPolicy { keep_recent: 2, keep_daily: 0, keep_weekly: 0, interval: Duration::ZERO };
+        vault.rotate_backups(&policy).unwrap();
+        assert_eq!(remaining(&vault), ["vault_20240120_200000_000.sqlite3", "vault_20240121_200000_000.sqlite3"]);
+        fs::remove_dir_all(vault.path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn held_lock_is_only_released_by_its_owner() {
+        let mut vault = test_vault("lock");
+        let lock = vault.get_lock_path();
+
+        assert!(vault.acquire_lock().unwrap());
+        assert_eq!(fs::read_to_string(&lock).unwrap(), process::id().to_string());
+        release_held_lock();
+        assert!(!lock.exists());
+        vault.release_lock().unwrap();
+
+        // a lock written by another process stays
+        assert!(vault.acquire_lock().unwrap());
+        fs::write(&lock, "1").unwrap();
+        release_held_lock();
+        assert!(lock.exists());
 
-        logging_path
+        vault.release_lock().unwrap();
+        assert!(!lock.exists());
+        fs::remove_dir_all(vault.path.parent().unwrap()).unwrap();
     }
 }
@@ -0,0 +1,174 @@
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use super::{AppDirs, VaultEntry, REGISTRY_NAME};
+
+
+// folder in the home directory used before the XDG directories
+const LEGACY_DIR: &str = "RustwordManager";
+// name of the executable of old and current versions
+const PROCESS_NAME: &str = env!("CARGO_BIN_NAME");
+
+pub(super) fn migrate(dirs: &AppDirs) -> Result<bool, String> {
+    // moves vaults, backups, registry and logs of an old installation, returns weather anything was moved
+    // and why it wasn't if something is left behind; an interrupted migration continues on the next start
+    let Some(legacy) = dirs::home_dir().map(|home| home.join(LEGACY_DIR)) else {
+        return Ok(false);
+    };
+    if !legacy.is_dir() || legacy == dirs.data {
+        return Ok(false);
+    }
+
+    move_legacy(&legacy, dirs).map_err(|error| {
+        log::error!("Failed to migrate old application directory: {error}");
+        format!("Failed to move old vaults from {}: {error}", legacy.display())
+    })
+}
+
+fn move_legacy(legacy: &Path, dirs: &AppDirs) -> io::Result<bool> {
+    // old locks hold no process id, they are only in use while an old instance runs
+    let locks = find_locks(legacy)?;
+    if !locks.is_empty() {
+        if old_instance_running() {
+            return Err(io::Error::other("another instance is still running, close it and restart"));
+        }
+        for lock in locks.iter() {
+            fs::remove_file(lock)?;
+        }
+        log::info!("Removed {} stale lock(s) of the old application directory", locks.len());
+    }
+
+    // never overwrite anything created after the switch
+    let files = files_in(legacy)?
+        .into_iter()
+        .filter(|file| file.file_name().is_some_and(|name| name != REGISTRY_NAME))
+        .collect::<Vec<PathBuf>>();
+    if let Some(conflict) = files.iter().find(|file| target_in(&dirs.data, file).exists()) {
+        return Err(io::Error::other(format!(
+            "{} exists in {} too, move the files by hand",
+            conflict.file_name().unwrap_or_default().to_string_lossy(),
+            dirs.data.display(),
+        )));
+    }
+
+    log::info!("Migrating old application directory {}", legacy.display());
+    for file in files.iter() {
+        move_file(file, &target_in(&dirs.data, file))?;
+    }
+    move_dir_contents(&legacy.join("backups"), &dirs.data.join("backups"))?;
+    move_dir_contents(&legacy.join("logs"), &dirs.state.join("logs"))?;
+    migrate_registry(legacy, dirs)?;
+
+    // only empty directories get removed, unknown leftovers stay where they are
+    for dir in [legacy.join("backups"), legacy.join("logs"), legacy.to_path_buf()] {
+        if fs::remove_dir(&dir).is_err() && dir.exists() {
+            log::warn!("Left {} in place, it is not empty", dir.display());
+        }
+    }
+
+    log::info!("Migrated old application directory");
+    Ok(true)
+}
+
+fn find_locks(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    // old versions used a single lock file, later ones one lock per vault
+    Ok(files_in(dir)?
+        .into_iter()
+        .filter(|file| {
+            file.file_name().is_some_and(|name| name == "lock")
+                || file.extension().is_some_and(|extension| extension == "lock")
+        })
+        .collect())
+}
+
+fn old_instance_running() -> bool {
+    // looks for another process of this program, without /proc every lock counts as in use
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return true;
+    };
+    let own_pid = process::id().to_string();
+    // the kernel cuts process names after 15 bytes
+    let name = &PROCESS_NAME[..PROCESS_NAME.len().min(15)];
+
+    processes
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry.file_name().to_str().is_some_and(|pid| pid != own_pid && pid.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .any(|entry| fs::read_to_string(entry.path().join("comm")).is_ok_and(|comm| comm.trim_end() == name))
+}
+
+fn migrate_registry(legacy: &Path, dirs: &AppDirs) -> io::Result<()> {
+    // merges the known vaults into the config directory, vaults of the old directory moved with it
+    let old_registry = legacy.join(REGISTRY_NAME);
+    if !old_registry.exists() {
+        return Ok(());
+    }
+
+    let new_registry = dirs.config.join(REGISTRY_NAME);
+    let mut registry = read_registry(&new_registry)?;
+
+    for mut entry in read_registry(&old_registry)? {
+        if let Ok(relative) = entry.path.strip_prefix(legacy) {
+            entry.path = dirs.data.join(relative);
+        }
+        if !registry.iter().any(|v| v.path == entry.path) {
+            registry.push(entry);
+        }
+    }
+
+    fs::write(&new_registry, serde_json::to_vec_pretty(&registry)?)?;
+    fs::remove_file(&old_registry)
+}
+
+fn read_registry(path: &Path) -> io::Result<Vec<VaultEntry>> {
+    // a missing registry is empty
+    match fs::read(path) {
+        Ok(content) => Ok(serde_json::from_slice(&content)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+fn move_dir_contents(source: &Path, target: &Path) -> io::Result<()> {
+    // moves all files of a directory, files already present in the target are kept in the source
+    if !source.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(target)?;
+
+    for file in files_in(source)? {
+        let file_target = target_in(target, &file);
+        if !file_target.exists() {
+            move_file(&file, &file_target)?;
+        }
+    }
+    Ok(())
+}
+
+fn files_in(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    // regular files directly inside a directory
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect())
+}
+
+fn target_in(dir: &Path, file: &Path) -> PathBuf {
+    // same file name inside another directory
+    dir.join(file.file_name().unwrap_or_default())
+}
+
+fn move_file(source: &Path, target: &Path) -> io::Result<()> {
+    // renames the file, copies it if the directories are on different file systems
+    if fs::rename(source, target).is_err() {
+        fs::copy(source, target)?;
+        File::open(target)?.sync_all()?;
+        fs::remove_file(source)?;
+    }
+    Ok(())
+}
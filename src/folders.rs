@@ -0,0 +1,206 @@
+use std::collections::{BTreeMap, HashSet};
+
+
+// entry names are stored as their path, names only need to be unique inside their folder
+pub const SEPARATOR: char = '/';
+
+// number of entries in the recent section
+pub const RECENT_COUNT: usize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Section {
+    // parts of the entries list shown above each other while nothing is filtered
+    Favorites,
+    Recent,
+    All,
+}
+
+impl Section {
+    pub fn title(&self) -> &'static str {
+        // shown in the header row of the section
+        match self {
+            Section::Favorites => "★ Favorites",
+            Section::Recent => "Recent",
+            Section::All => "All entries",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum ListRow {
+    // a row of the entries list, folders can be collapsed and shortcuts show the whole path, like search results;
+    // groups head the entries of a template when the list is grouped
+    Header { section: Section, count: usize },
+    Group { template: String, count: usize },
+    Shortcut { name: String, section: Section },
+    Folder { path: String, depth: usize, count: usize, collapsed: bool },
+    Entry { name: String, depth: usize },
+}
+
+impl ListRow {
+    pub fn entry(&self) -> Option<&String> {
+        // name of the entry, None for folders
+        match self {
+            ListRow::Shortcut { name, .. } | ListRow::Entry { name, .. } => Some(name),
+            ListRow::Header { .. } | ListRow::Group { .. } | ListRow::Folder { .. } => None,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        // nesting level for the indentation
+        match self {
+            ListRow::Header { .. } | ListRow::Group { .. } | ListRow::Shortcut { .. } => 0,
+            ListRow::Folder { depth, .. } | ListRow::Entry { depth, .. } => *depth,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        // shown text without the parent folders
+        match self {
+            ListRow::Header { section, .. } => section.title(),
+            ListRow::Group { template, .. } => template,
+            ListRow::Shortcut { name, .. } => name,
+            ListRow::Folder { path, .. } => leaf(path),
+            ListRow::Entry { name, .. } => leaf(name),
+        }
+    }
+
+    pub fn section(&self) -> Section {
+        // part of the list the row belongs to
+        match self {
+            ListRow::Header { section, .. } | ListRow::Shortcut { section, .. } => *section,
+            ListRow::Group { .. } | ListRow::Folder { .. } | ListRow::Entry { .. } => Section::All,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Node {
+    // a folder with its subfolders by name and its entries in list order
+    folders: BTreeMap<String, Node>,
+    entries: Vec<String>,
+    count: usize,
+}
+
+pub fn folder(name: &str) -> &str {
+    // folder path of an entry, empty at the top level
+    name.rsplit_once(SEPARATOR).map_or("", |(folder, _)| folder)
+}
+
+pub fn leaf(name: &str) -> &str {
+    // name of an entry or folder without its parents
+    name.rsplit_once(SEPARATOR).map_or(name, |(_, leaf)| leaf)
+}
+
+pub fn join(folder: &str, leaf: &str) -> String {
+    // path of an entry inside a folder
+    if folder.is_empty() {
+        leaf.to_string()
+    } else {
+        format!("{folder}{SEPARATOR}{leaf}")
+    }
+}
+
+pub fn normalize_folder(input: &str) -> String {
+    // trims every level of a typed folder path and drops empty ones
+    input
+        .split(SEPARATOR)
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>()
+        .join(&SEPARATOR.to_string())
+}
+
+pub fn normalize_name(input: &str) -> Result<String, String> {
+    // a typed entry name with optional folders in front, the entry itself needs a name
+    let (folder, leaf) = input.rsplit_once(SEPARATOR).unwrap_or(("", input));
+    if leaf.trim().is_empty() {
+        return Err("Name missing!".to_string());
+    }
+    Ok(join(&normalize_folder(folder), leaf.trim()))
+}
+
+pub fn parse_tags(input: &str) -> Vec<String> {
+    // comma separated tags without surrounding spaces, empty and repeated ones are dropped
+    let mut tags: Vec<String> = vec![];
+    for tag in input.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+pub fn build_rows(names: &[String], collapsed: &HashSet<String>, expand_all: bool) -> Vec<ListRow> {
+    // folders come first on each level sorted by name, entries keep the order they are given in
+    let mut root = Node::default();
+    for name in names {
+        let mut node = &mut root;
+        let folder = folder(name);
+
+        if !folder.is_empty() {
+            for segment in folder.split(SEPARATOR) {
+                node = node.folders.entry(segment.to_string()).or_default();
+                node.count += 1;
+            }
+        }
+        node.entries.push(name.clone());
+    }
+
+    let mut rows = vec![];
+    flatten(&root, "", 0, collapsed, expand_all, &mut rows);
+    rows
+}
+
+pub fn build_groups(groups: &[(String, Vec<String>)], collapsed: &HashSet<String>, expand_all: bool) -> Vec<ListRow> {
+    // the tree of every template below a row with its name and number of entries
+    let mut rows = vec![];
+    for (template, names) in groups {
+        rows.push(ListRow::Group { template: template.clone(), count: names.len() });
+        rows.extend(build_rows(names, collapsed, expand_all));
+    }
+    rows
+}
+
+pub fn build_sections(favorites: &[String], recent: &[String], total: usize, tree: Vec<ListRow>) -> Vec<ListRow> {
+    // favorites and recent entries above the tree, sections without entries are left out
+    let mut rows = vec![];
+    for (section, names) in [(Section::Favorites, favorites), (Section::Recent, recent)] {
+        if names.is_empty() {
+            continue;
+        }
+        rows.push(ListRow::Header { section, count: names.len() });
+        rows.extend(names.iter().map(|name| ListRow::Shortcut { name: name.clone(), section }));
+    }
+
+    if !rows.is_empty() {
+        rows.push(ListRow::Header { section: Section::All, count: total });
+    }
+    rows.extend(tree);
+    rows
+}
+
+pub fn build_results(names: &[String]) -> Vec<ListRow> {
+    // search results in the given order with their whole path
+    names.iter().map(|name| ListRow::Shortcut { name: name.clone(), section: Section::All }).collect()
+}
+
+fn flatten(node: &Node, path: &str, depth: usize, collapsed: &HashSet<String>, expand_all: bool, rows: &mut Vec<ListRow>) {
+    // appends the rows of a folder and its open subfolders
+    for (segment, child) in node.folders.iter() {
+        let child_path = join(path, segment);
+        let is_collapsed = !expand_all && collapsed.contains(&child_path);
+
+        rows.push(ListRow::Folder {
+            path: child_path.clone(),
+            depth,
+            count: child.count,
+            collapsed: is_collapsed,
+        });
+        if !is_collapsed {
+            flatten(child, &child_path, depth + 1, collapsed, expand_all, rows);
+        }
+    }
+
+    rows.extend(node.entries.iter().map(|name| ListRow::Entry { name: name.clone(), depth }));
+}
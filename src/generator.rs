@@ -0,0 +1,410 @@
+use rand::{seq::SliceRandom, thread_rng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use zeroize::Zeroize;
+
+use crate::{app::stateful_list::StatefulList, secret::SecretString};
+
+
+pub const MAX_LENGTH: usize = 128;
+pub const DEFAULT_SYMBOLS: &str = "!#$%&*+-=?@^_~()[]{}<>.,:;/";
+// characters that are easily mistaken for each other when read or typed
+const AMBIGUOUS: &str = "Il1|O0o`'\"";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeneratorOptions {
+    // choices of the password generator, remembered per template field
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    pub exclude_ambiguous: bool,
+    pub symbol_set: String,
+    pub min_lowercase: usize,
+    pub min_uppercase: usize,
+    pub min_digits: usize,
+    pub min_symbols: usize,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> GeneratorOptions {
+        GeneratorOptions::new(24, true)
+    }
+}
+
+impl GeneratorOptions {
+    pub fn new(length: usize, symbols: bool) -> GeneratorOptions {
+        // every kind of characters at least once, like passwords generated before the options existed
+        GeneratorOptions {
+            length,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols,
+            exclude_ambiguous: false,
+            symbol_set: DEFAULT_SYMBOLS.to_string(),
+            min_lowercase: 1,
+            min_uppercase: 1,
+            min_digits: 1,
+            min_symbols: 1,
+        }
+    }
+
+    fn classes(&self) -> Vec<(Vec<char>, usize)> {
+        // characters and minimum count of each chosen kind, ambiguous ones left out if wanted
+        let mut symbols: Vec<char> = self.symbol_set.chars().filter(is_symbol).collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        [
+            (self.lowercase, ('a'..='z').collect(), self.min_lowercase),
+            (self.uppercase, ('A'..='Z').collect(), self.min_uppercase),
+            (self.digits, ('0'..='9').collect(), self.min_digits),
+            (self.symbols, symbols, self.min_symbols),
+        ]
+        .into_iter()
+        .filter(|(chosen, _, _)| *chosen)
+        .map(|(_, chars, min): (bool, Vec<char>, usize)| {
+            let chars = chars.into_iter().filter(|c| !(self.exclude_ambiguous && AMBIGUOUS.contains(*c))).collect();
+            (chars, min)
+        })
+        .collect()
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        // explains why no password can be generated with these options
+        let classes = self.classes();
+        let required: usize = classes.iter().map(|(_, min)| min).sum();
+
+        if classes.is_empty() {
+            Err("Choose at least one kind of characters".to_string())
+        } else if classes.iter().any(|(chars, _)| chars.is_empty()) {
+            Err("The symbol set has no usable characters".to_string())
+        } else if required > self.length {
+            Err(format!("Minimum counts need {required} characters, the length is {}", self.length))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn generate(&self) -> Result<SecretString, String> {
+        // uses thread_rng which is marked as cryptographically secure, see:
+        // https://rust-random.github.io/book/guide-rngs.html
+        self.check()?;
+        let classes = self.classes();
+        let all: Vec<char> = classes.iter().flat_map(|(chars, _)| chars.iter().copied()).collect();
+        let mut rng = thread_rng();
+
+        // the minimum counts first, the rest from all chosen characters, then mixed
+        let mut password: Vec<char> = Vec::with_capacity(self.length);
+        for (chars, min) in &classes {
+            password.extend((0..*min).filter_map(|_| chars.choose(&mut rng)));
+        }
+        while password.len() < self.length {
+            password.extend(all.choose(&mut rng));
+        }
+        password.shuffle(&mut rng);
+
+        let generated = SecretString::new(password.iter().collect());
+        password.zeroize();
+        Ok(generated)
+    }
+
+    pub fn entropy_bits(&self) -> f64 {
+        // lower bound: characters of the minimum counts only add the entropy of their own kind, the others
+        // that of all distinct chosen characters; the shuffle mixing them is not counted
+        let classes = self.classes();
+        let pool: HashSet<char> = classes.iter().flat_map(|(chars, _)| chars.iter().copied()).collect();
+        if pool.is_empty() {
+            return 0.0;
+        }
+
+        let required: usize = classes.iter().map(|(_, min)| min).sum();
+        let minimums: f64 = classes.iter().map(|(chars, min)| *min as f64 * (chars.len().max(1) as f64).log2()).sum();
+        minimums + self.length.saturating_sub(required) as f64 * (pool.len() as f64).log2()
+    }
+}
+
+fn is_symbol(c: &char) -> bool {
+    // characters allowed in the symbol set, letters and digits have their own kinds
+    !c.is_alphanumeric() && !c.is_whitespace() && !c.is_control()
+}
+
+pub fn strength(bits: f64) -> &'static str {
+    // rating of an entropy estimate shown next to the preview
+    match bits {
+        bits if bits < 50.0 => "weak",
+        bits if bits < 80.0 => "fair",
+        bits if bits < 120.0 => "strong",
+        _ => "very strong",
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GeneratorRow {
+    // options listed in the generator popup
+    Length,
+    Lowercase,
+    Uppercase,
+    Digits,
+    Symbols,
+    SymbolSet,
+    ExcludeAmbiguous,
+    MinLowercase,
+    MinUppercase,
+    MinDigits,
+    MinSymbols,
+}
+
+impl GeneratorRow {
+    pub fn all() -> Vec<GeneratorRow> {
+        // returns all rows in display order
+        vec![
+            GeneratorRow::Length,
+            GeneratorRow::Lowercase,
+            GeneratorRow::Uppercase,
+            GeneratorRow::Digits,
+            GeneratorRow::Symbols,
+            GeneratorRow::SymbolSet,
+            GeneratorRow::ExcludeAmbiguous,
+            GeneratorRow::MinLowercase,
+            GeneratorRow::MinUppercase,
+            GeneratorRow::MinDigits,
+            GeneratorRow::MinSymbols,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        // label shown in the popup
+        match self {
+            GeneratorRow::Length => "Length",
+            GeneratorRow::Lowercase => "Lowercase letters",
+            GeneratorRow::Uppercase => "Uppercase letters",
+            GeneratorRow::Digits => "Digits",
+            GeneratorRow::Symbols => "Symbols",
+            GeneratorRow::SymbolSet => "Symbol set",
+            GeneratorRow::ExcludeAmbiguous => "Exclude ambiguous",
+            GeneratorRow::MinLowercase => "At least lowercase",
+            GeneratorRow::MinUppercase => "At least uppercase",
+            GeneratorRow::MinDigits => "At least digits",
+            GeneratorRow::MinSymbols => "At least symbols",
+        }
+    }
+
+    pub fn value(&self, options: &GeneratorOptions) -> String {
+        // current choice of the row
+        let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+        match self {
+            GeneratorRow::Length => format!("{} characters", options.length),
+            GeneratorRow::Lowercase => yes_no(options.lowercase),
+            GeneratorRow::Uppercase => yes_no(options.uppercase),
+            GeneratorRow::Digits => yes_no(options.digits),
+            GeneratorRow::Symbols => yes_no(options.symbols),
+            GeneratorRow::SymbolSet => options.symbol_set.clone(),
+            GeneratorRow::ExcludeAmbiguous => format!("{} ({AMBIGUOUS})", yes_no(options.exclude_ambiguous)),
+            GeneratorRow::MinLowercase => options.min_lowercase.to_string(),
+            GeneratorRow::MinUppercase => options.min_uppercase.to_string(),
+            GeneratorRow::MinDigits => options.min_digits.to_string(),
+            GeneratorRow::MinSymbols => options.min_symbols.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GeneratorTarget {
+    // input the generated password gets written to
+    NewEntry(usize),
+    EntryEdit,
+}
+
+pub struct GeneratorPopup {
+    // open generator of a private field, element counts the fields of the template including the name
+    pub target: GeneratorTarget,
+    pub template: String,
+    pub element: usize,
+    pub field: String,
+    pub options: GeneratorOptions,
+    pub rows: StatefulList<GeneratorRow>,
+    pub preview: Result<SecretString, String>,
+    min_length: usize,
+}
+
+impl GeneratorPopup {
+    pub fn new(
+        target: GeneratorTarget,
+        (template, element, field): (String, usize, String),
+        mut options: GeneratorOptions,
+        min_length: usize,
+    ) -> GeneratorPopup {
+        // remembered options raised to the length policy of the vault, with a first preview
+        options.length = options.length.clamp(min_length, MAX_LENGTH);
+        let preview = options.generate();
+
+        GeneratorPopup {
+            target,
+            template,
+            element,
+            field,
+            options,
+            rows: StatefulList::with_items(GeneratorRow::all()),
+            preview,
+            min_length,
+        }
+    }
+
+    pub fn regenerate(&mut self) {
+        // new preview with the current options
+        self.preview = self.options.generate();
+    }
+
+    pub fn change(&mut self, increase: bool) {
+        // changes the selected option by one step or toggles it, the symbol set is typed instead
+        let Some(row) = self.rows.current_item().copied() else {
+            return;
+        };
+        let options = &mut self.options;
+        let step = |value: usize, max: usize| match increase {
+            true => (value + 1).min(max),
+            false => value.saturating_sub(1),
+        };
+
+        match row {
+            GeneratorRow::Length => options.length = step(options.length, MAX_LENGTH).max(self.min_length),
+            GeneratorRow::Lowercase => options.lowercase = !options.lowercase,
+            GeneratorRow::Uppercase => options.uppercase = !options.uppercase,
+            GeneratorRow::Digits => options.digits = !options.digits,
+            GeneratorRow::Symbols => options.symbols = !options.symbols,
+            GeneratorRow::ExcludeAmbiguous => options.exclude_ambiguous = !options.exclude_ambiguous,
+            GeneratorRow::MinLowercase => options.min_lowercase = step(options.min_lowercase, MAX_LENGTH),
+            GeneratorRow::MinUppercase => options.min_uppercase = step(options.min_uppercase, MAX_LENGTH),
+            GeneratorRow::MinDigits => options.min_digits = step(options.min_digits, MAX_LENGTH),
+            GeneratorRow::MinSymbols => options.min_symbols = step(options.min_symbols, MAX_LENGTH),
+            GeneratorRow::SymbolSet => return,
+        }
+        self.regenerate();
+    }
+
+    pub fn edit_symbols(&mut self, typed: Option<char>) {
+        // adds a typed character to the symbol set or removes the last one while the set is selected
+        if self.rows.current_item() != Some(&GeneratorRow::SymbolSet) {
+            return;
+        }
+
+        match typed {
+            Some(c) if is_symbol(&c) && !self.options.symbol_set.contains(c) => self.options.symbol_set.push(c),
+            Some(_) => return,
+            None => {
+                self.options.symbol_set.pop();
+            }
+        }
+        self.regenerate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(password: &SecretString, kind: fn(&char) -> bool) -> usize {
+        password.as_str().chars().filter(kind).count()
+    }
+
+    #[test]
+    fn generates_the_exact_length_with_every_kind() {
+        for length in [8, 24, MAX_LENGTH] {
+            let password = GeneratorOptions::new(length, true).generate().unwrap();
+            assert_eq!(password.as_str().chars().count(), length);
+            assert!(count(&password, char::is_ascii_lowercase) >= 1);
+            assert!(count(&password, char::is_ascii_uppercase) >= 1);
+            assert!(count(&password, char::is_ascii_digit) >= 1);
+            assert!(count(&password, is_symbol) >= 1);
+        }
+    }
+
+    #[test]
+    fn meets_the_minimum_counts() {
+        let options = GeneratorOptions {
+            min_lowercase: 0,
+            min_uppercase: 3,
+            min_digits: 5,
+            min_symbols: 4,
+            ..GeneratorOptions::new(12, true)
+        };
+        for _ in 0..50 {
+            let password = options.generate().unwrap();
+            assert!(count(&password, char::is_ascii_uppercase) >= 3);
+            assert!(count(&password, char::is_ascii_digit) >= 5);
+            assert!(count(&password, is_symbol) >= 4);
+        }
+    }
+
+    #[test]
+    fn respects_exclusions() {
+        let options = GeneratorOptions {
+            exclude_ambiguous: true,
+            symbol_set: "#!|".to_string(),
+            ..GeneratorOptions::new(MAX_LENGTH, true)
+        };
+        for _ in 0..20 {
+            let password = options.generate().unwrap();
+            assert!(!password.as_str().chars().any(|c| AMBIGUOUS.contains(c)));
+            assert!(password.as_str().chars().filter(is_symbol).all(|c| "#!".contains(c)));
+        }
+
+        let password = GeneratorOptions::new(MAX_LENGTH, false).generate().unwrap();
+        assert_eq!(count(&password, is_symbol), 0);
+    }
+
+    #[test]
+    fn check_explains_impossible_options() {
+        let none = GeneratorOptions { lowercase: false, uppercase: false, digits: false, ..GeneratorOptions::new(16, false) };
+        assert_eq!(none.check().unwrap_err(), "Choose at least one kind of characters");
+
+        let letters_only = GeneratorOptions { symbol_set: "ab c".to_string(), ..GeneratorOptions::new(16, true) };
+        assert_eq!(letters_only.check().unwrap_err(), "The symbol set has no usable characters");
+
+        let too_short = GeneratorOptions { min_digits: 6, ..GeneratorOptions::new(8, true) };
+        assert_eq!(too_short.check().unwrap_err(), "Minimum counts need 9 characters, the length is 8");
+        assert!(too_short.generate().is_err());
+
+        assert!(GeneratorOptions::new(8, true).check().is_ok());
+    }
+
+    #[test]
+    fn entropy_ignores_repeated_symbols_and_counts_minimums_by_kind() {
+        let digits_only = GeneratorOptions {
+            lowercase: false,
+            uppercase: false,
+            symbols: false,
+            min_digits: 0,
+            ..GeneratorOptions::new(10, false)
+        };
+        assert!((digits_only.entropy_bits() - 10.0 * 10f64.log2()).abs() < 1e-9);
+
+        let repeated = GeneratorOptions { symbol_set: "!!!##".to_string(), ..GeneratorOptions::new(20, true) };
+        let distinct = GeneratorOptions { symbol_set: "!#".to_string(), ..GeneratorOptions::new(20, true) };
+        assert_eq!(repeated.entropy_bits(), distinct.entropy_bits());
+
+        // a symbol forced into the password adds less than a character picked from all kinds
+        let forced = GeneratorOptions { min_symbols: 5, ..distinct.clone() };
+        assert!(forced.entropy_bits() < distinct.entropy_bits());
+
+        let none = GeneratorOptions { lowercase: false, uppercase: false, digits: false, ..GeneratorOptions::new(16, false) };
+        assert_eq!(none.entropy_bits(), 0.0);
+    }
+
+    #[test]
+    fn popup_keeps_the_length_policy() {
+        let target = (String::from("tp_web_credential"), 2, String::from("Password"));
+        let mut popup = GeneratorPopup::new(GeneratorTarget::EntryEdit, target, GeneratorOptions::new(8, true), 16);
+        assert_eq!(popup.options.length, 16);
+
+        popup.change(false);
+        assert_eq!(popup.options.length, 16);
+        popup.change(true);
+        assert_eq!(popup.options.length, 17);
+        assert_eq!(popup.preview.as_ref().unwrap().as_str().chars().count(), 17);
+    }
+}
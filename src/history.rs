@@ -0,0 +1,41 @@
+use chrono::{DateTime, Local, Utc};
+
+use crate::{app::stateful_list::StatefulList, secret::SecretString};
+
+
+pub struct HistoryValue {
+    // a previous value of a private field and when it got replaced
+    pub id: i64,
+    pub replaced: Option<DateTime<Utc>>,
+    pub value: SecretString,
+}
+
+impl HistoryValue {
+    pub fn replaced_local(&self) -> String {
+        // time of the change for display in the local timezone
+        match self.replaced {
+            Some(time) => time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+            None => "unknown".to_string(),
+        }
+    }
+}
+
+pub struct FieldHistory {
+    // opened history of a field of the displayed entry with (value, hidden) newest first
+    pub index: usize,
+    pub field: String,
+    pub values: StatefulList<(HistoryValue, bool)>,
+    pub status: Option<Result<String, String>>,
+}
+
+impl FieldHistory {
+    pub fn new(index: usize, field: String, values: Vec<HistoryValue>) -> FieldHistory {
+        // all values start hidden
+        FieldHistory {
+            index,
+            field,
+            values: StatefulList::with_items(values.into_iter().map(|value| (value, true)).collect()),
+            status: None,
+        }
+    }
+}
@@ -2,9 +2,18 @@
 use shielded::Shielded;
 use std::num::NonZeroUsize;
 use std::thread::available_parallelism;
+use zeroize::Zeroize;
 
+use crate::{
+    memory::{lock_region, unlock_region},
+    secret::SecretBytes,
+};
 
-pub fn derive_key(password: String, salt: &[u8; 16]) -> Vec<u8> {
+// ChaCha20-Poly1305 tag appended by shielded when encrypting in place
+const SHIELD_TAG_LEN: usize = 16;
+
+
+pub fn derive_key(password: &str, salt: &[u8; 16]) -> SecretBytes {
     // derives a strong 256-bit key from a password with argon2
     log::info!("Deriving a key from password");
     let mut key = [0u8; 32];
@@ -17,10 +26,10 @@
             1024 * 256,
             8,
             // ToDo: Not portable with other core count!
-            available_parallelism()
+            (available_parallelism()
                  .unwrap_or(NonZeroUsize::new(1).unwrap())
                  .get() as u32
-                 / 2.max(1),
+                 / 2).max(1),
             Some(key.len()),
         ).unwrap(),
     );
@@ -29,34 +38,68 @@
         .hash_password_into(password.as_bytes(), salt, &mut key)
         .unwrap();
 
-    // return key as a vector
-    key.to_vec()
+    // return key as a vector and wipe the stack copy
+    let secret = SecretBytes::from(key.as_slice());
+    key.zeroize();
+
+    secret
+}
+
+pub fn keys_match(first: &[u8], second: &[u8]) -> bool {
+    // compares two keys in constant time to not leak timing information
+    if first.len() != second.len() {
+        return false;
+    }
+
+    first
+        .iter()
+        .zip(second)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
 }
 
 pub struct SecureStorage {
-    // wrapper for shielded memory
+    // wrapper for shielded memory and the locked region it lives in
     memory: Shielded,
+    region: (usize, usize),
 }
 
 impl SecureStorage {
     //  key gets deleted after being stored
-    pub fn new(key_vector: Vec<u8>) -> SecureStorage {
-        SecureStorage { memory: Shielded::new(key_vector), }
+    pub fn new(key: &[u8]) -> SecureStorage {
+        let buffer = SecureStorage::shield_buffer(key);
+        let region = (buffer.as_ptr() as usize, buffer.capacity());
+
+        SecureStorage { memory: Shielded::new(buffer), region }
     }
 
-    pub fn from_string(input: String) -> SecureStorage {
+    pub fn from_string(input: &str) -> SecureStorage {
         // create new shielded memory from a string
-        let buffer = input.as_bytes().to_vec();
-        SecureStorage {
-            memory: Shielded::new(buffer),
-        }
+        SecureStorage::new(input.as_bytes())
     }
 
-    pub fn get_contents(&mut self) -> Vec<u8> {
+    pub fn get_contents(&mut self) -> SecretBytes {
         // shielded re-encrypts key after being unshielded
-        log::info!("Read contents from secure Storage");
+        log::debug!("Read contents from secure Storage");
 
         let unshielded = self.memory.unshield();
-        unshielded.as_ref().to_vec()
+        SecretBytes::from(unshielded.as_ref())
+    }
+
+    fn shield_buffer(content: &[u8]) -> Vec<u8> {
+        // reserves space for the tag so encrypting in place never leaves
+        // a reallocated plaintext copy behind
+        let mut buffer = Vec::with_capacity(content.len() + SHIELD_TAG_LEN);
+        lock_region(buffer.as_ptr(), buffer.capacity());
+
+        buffer.extend_from_slice(content);
+        buffer
+    }
+}
+
+impl Drop for SecureStorage {
+    fn drop(&mut self) {
+        // shielded overwrites its memory on its own afterwards
+        unlock_region(self.region.0 as *const u8, self.region.1);
     }
 }
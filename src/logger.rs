@@ -1,24 +1,120 @@
-use std::path::PathBuf;
+use std::path::Path;
 // logger.rs
-use log::LevelFilter;
-use log4rs::append::file::FileAppender;
+use log::{Log, Metadata, Record};
+use log4rs::append::rolling_file::policy::compound::{
+    roll::fixed_window::FixedWindowRoller, trigger::size::SizeTrigger, CompoundPolicy,
+};
+use log4rs::append::rolling_file::RollingFileAppender;
 use log4rs::config::{Appender, Config, Root};
-use log4rs::encode::pattern::PatternEncoder;
+use log4rs::encode::{json::JsonEncoder, pattern::PatternEncoder, Encode};
 
-pub fn init_logger(log_file_path: PathBuf) {
-    // Set up the file appender for log4rs
-    let file_appender = FileAppender::builder()
-        .encoder(Box::new(PatternEncoder::new("{d(%Y-%m-%d %H:%M:%S%.3f)} [{l}] {m}\n")))
-        .build(log_file_path.as_path())
+use crate::{
+    config::{LogFormat, LogSettings},
+    redact,
+};
+
+
+const LOG_NAME: &str = "rwmanager.log";
+// rotated files get numbered, 0 is the most recent one
+const ROTATED_PATTERN: &str = "rwmanager.{}.log";
+
+// every message passes the redaction before any appender sees it
+struct RedactingLogger<L: Log>(L);
+
+impl<L: Log> Log for RedactingLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let message = redact::redact(&record.args().to_string());
+        self.0.log(
+            &Record::builder()
+                .metadata(record.metadata().clone())
+                .args(format_args!("{message}"))
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+        );
+    }
+
+    fn flush(&self) {
+        self.0.flush();
+    }
+}
+
+pub fn init_logger(log_dir: &Path, settings: &LogSettings) {
+    // Set up the encoder in the configured format
+    let encoder: Box<dyn Encode> = match settings.format {
+        LogFormat::Text => Box::new(PatternEncoder::new("{d(%Y-%m-%d %H:%M:%S%.3f)} [{l}] {m}\n")),
+        LogFormat::Json => Box::new(JsonEncoder::new()),
+    };
+
+    // Rotate the file once it gets too big and keep a limited number of old ones
+    let roller = FixedWindowRoller::builder()
+        .build(&log_dir.join(ROTATED_PATTERN).to_string_lossy(), settings.keep_files)
+        .expect("Failed to create log roller");
+    let policy = CompoundPolicy::new(Box::new(SizeTrigger::new(settings.max_size)), Box::new(roller));
+
+    let file_appender = RollingFileAppender::builder()
+        .encoder(encoder)
+        .build(log_dir.join(LOG_NAME), Box::new(policy))
         .expect("Failed to create file appender");
 
     // Set up the log4rs configuration
     let config = Config::builder()
         .appender(Appender::builder().build("file", Box::new(file_appender)))
-        .build(Root::builder().appender("file").build(LevelFilter::Info)).expect("");
+        .build(Root::builder().appender("file").build(settings.level)).expect("");
 
-    // Try to set the log4rs configuration
-    if let Err(err) = log4rs::init_config(config) {
+    // Try to set the redacting logger globally
+    log::set_max_level(settings.level);
+    if let Err(err) = log::set_boxed_logger(Box::new(RedactingLogger(log4rs::Logger::new(config)))) {
         eprintln!("Failed to initialize log4rs: {}", err);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Capture(Mutex<Vec<String>>);
+
+    impl Log for Capture {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn log_through(logger: &RedactingLogger<Capture>, message: &str) {
+        logger.log(&Record::builder().level(Level::Info).args(format_args!("{message}")).build());
+    }
+
+    #[test]
+    fn redacts_before_the_inner_logger() {
+        let logger = RedactingLogger(Capture::default());
+        redact::register("s3cr3t-logger-value");
+        redact::register("7q");
+
+        log_through(&logger, "Copied s3cr3t-logger-value to clipboard");
+        log_through(&logger, "Field value 7q, not 7qx");
+
+        assert_eq!(
+            *logger.0.0.lock().unwrap(),
+            ["Copied [redacted] to clipboard", "Field value [redacted], not 7qx"],
+        );
+    }
+}
@@ -1,51 +1,115 @@
-use std::error::Error;
+use std::{error::Error, process::ExitCode};
 
-use crate::{app::App, terminal::*, types::*};
-use crate::file_manager::FileManager;
+use crate::{app::App, cli::Command, terminal::*, types::*};
+use crate::{
+    config::{Config, CONFIG_NAME},
+    file_manager::{vault_entry_from_path, FileManager},
+    signals::SignalHandler,
+};
 
 mod aes_impl;
 mod app;
 mod app_db_conn;
+mod audit;
 mod base64_enc_dec;
+mod cli;
+mod config;
 mod db_interface;
+mod doctor;
+mod entry_index;
 mod event;
 mod file_manager;
+mod folders;
+mod generator;
+mod history;
 mod key_processor;
 mod logger;
+mod memory;
+mod metadata;
 mod password;
+mod redact;
+mod search;
+mod secret;
+mod signals;
 mod terminal;
+mod trash;
 mod types;
 mod ui;
 
 
-fn main() -> std::result::Result<(), Box<dyn Error>> {
+fn main() -> std::result::Result<ExitCode, Box<dyn Error>> {
     // main: programm start
+    let arguments = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(arguments) => arguments,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            return Ok(ExitCode::from(2));
+        }
+    };
+    if let Command::Help = arguments.command {
+        println!("{}", cli::USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+
     let mut file_manager = FileManager::new();
     file_manager.create_path().unwrap();
+    let mut exit_code = ExitCode::SUCCESS;
 
-    // check if instance is already running
-    if !file_manager.check_lock_set()? {
-        // setup global logger
-        logger::init_logger(file_manager.get_logger_path());
-        log::info!("Setup global logger");
+    let config = match Config::load(&file_manager.dirs.config.join(CONFIG_NAME)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            return Ok(ExitCode::from(2));
+        }
+    };
 
-        let mut terminal = setup_terminal()?;
+    // setup global logger
+    logger::init_logger(&file_manager.get_log_dir(), &config.logging);
+    log::info!("Setup global logger");
 
-        let app = App::new(&mut file_manager);
-        let result = app.run(&mut terminal);
+    file_manager.migrate_legacy();
+    if let Err(err) = file_manager.remove_old_logs(config.logging.keep_files as usize) {
+        log::error!("Failed to remove old log files: {err}");
+    }
 
-        restore_terminal(terminal)?;
+    // keep secrets out of core dumps before any of them exist
+    memory::harden_process();
 
-        if let Err(err) = result {
-            eprintln!("{err:?}");
+    // a vault given on the command line replaces the default one
+    let cli_vault = arguments.vault.map(vault_entry_from_path);
+    if let Some(entry) = cli_vault.as_ref() {
+        file_manager.vault = file_manager.vault_from(entry);
+    }
+
+    match arguments.command {
+        Command::Doctor { fix } => {
+            match cli::run_doctor(&mut file_manager, fix) {
+                Ok(true) => println!("\nVault is healthy."),
+                Ok(false) => {
+                    println!("\nVault has problems.");
+                    exit_code = ExitCode::FAILURE;
+                }
+                Err(err) => {
+                    eprintln!("{err}");
+                    exit_code = ExitCode::FAILURE;
+                }
+            }
         }
+        _ => {
+            let signals = SignalHandler::new()?;
+            let mut terminal = setup_terminal()?;
+
+            let app = App::new(&mut file_manager, config, cli_vault);
+            let result = app.run(&mut terminal, &signals);
+
+            restore_terminal(terminal)?;
 
-        file_manager.release_file_lock()?;
-    } else {
-        eprintln!(
-            "\nAn instance is already running\n\
-            File 'home/RustwordManager/lock' is present\n"
-        );
+            if let Err(err) = result {
+                eprintln!("{err:?}");
+            }
+        }
     }
-    Ok(())
+
+    file_manager.vault.release_lock()?;
+    Ok(exit_code)
 }
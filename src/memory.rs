@@ -0,0 +1,121 @@
+use std::{
+    collections::HashMap,
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, OnceLock,
+    },
+};
+
+
+// set as soon as any protection could not be applied, shown in the ui
+static PROTECTION_DEGRADED: AtomicBool = AtomicBool::new(false);
+
+// pages can hold several secrets, so they are only unlocked after the last one is gone
+static LOCKED_PAGES: OnceLock<Mutex<HashMap<usize, usize>>> = OnceLock::new();
+
+pub fn harden_process() {
+    // prevents core dumps and debugger attachment of other users
+    #[cfg(target_os = "linux")]
+    if unsafe { libc::prctl(libc::PR_SET_DUMPABLE, 0, 0, 0, 0) } != 0 {
+        mark_degraded("Failed to mark process as non-dumpable", io::Error::last_os_error());
+    }
+
+    #[cfg(unix)]
+    {
+        let limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+        if unsafe { libc::setrlimit(libc::RLIMIT_CORE, &limit) } != 0 {
+            mark_degraded("Failed to disable core dumps", io::Error::last_os_error());
+        }
+    }
+
+    #[cfg(not(unix))]
+    mark_degraded("Failed to harden process", io::ErrorKind::Unsupported.into());
+
+    if !is_degraded() {
+        log::info!("Hardened process against core dumps");
+    }
+}
+
+pub fn is_degraded() -> bool {
+    // returns weather the application runs with reduced memory protection
+    PROTECTION_DEGRADED.load(Ordering::Relaxed)
+}
+
+fn mark_degraded(reason: &str, error: io::Error) {
+    // only warns once to keep the logs readable
+    if PROTECTION_DEGRADED.swap(true, Ordering::Relaxed) {
+        log::debug!("{reason}: {error}");
+    } else {
+        log::warn!("{reason}: {error}. Running with reduced memory protection");
+    }
+}
+
+pub fn lock_region(ptr: *const u8, len: usize) {
+    // prevents the pages of a buffer from being swapped to disk
+    #[cfg(unix)]
+    for_each_page(ptr, len, |page, size| {
+        let mut pages = locked_pages();
+        let count = pages.entry(page).or_insert(0);
+
+        if *count == 0 && unsafe { libc::mlock(page as *const libc::c_void, size) } != 0 {
+            let error = io::Error::last_os_error();
+            pages.remove(&page);
+            drop(pages);
+
+            mark_degraded("Failed to lock memory", error);
+            return;
+        }
+        *count += 1;
+    });
+
+    #[cfg(not(unix))]
+    let _ = (ptr, len);
+}
+
+pub fn unlock_region(ptr: *const u8, len: usize) {
+    // releases pages previously locked with lock_region
+    #[cfg(unix)]
+    for_each_page(ptr, len, |page, size| {
+        let mut pages = locked_pages();
+
+        if let Some(count) = pages.get_mut(&page) {
+            *count -= 1;
+
+            if *count == 0 {
+                pages.remove(&page);
+                unsafe { libc::munlock(page as *const libc::c_void, size) };
+            }
+        }
+    });
+
+    #[cfg(not(unix))]
+    let _ = (ptr, len);
+}
+
+fn locked_pages() -> MutexGuard<'static, HashMap<usize, usize>> {
+    // secrets get dropped while unwinding, so a poisoned lock must not panic again
+    LOCKED_PAGES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(unix)]
+fn for_each_page<F>(ptr: *const u8, len: usize, mut action: F)
+where
+    F: FnMut(usize, usize),
+{
+    // calls action with the start address and size of every page of a buffer
+    if len == 0 {
+        return;
+    }
+
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let first = ptr as usize / page_size * page_size;
+    let last = (ptr as usize + len - 1) / page_size * page_size;
+
+    for page in (first..=last).step_by(page_size) {
+        action(page, page_size);
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Timestamp {
+    // timestamps of an entry that get updated after its creation
+    Modified,
+    Revealed,
+    Copied,
+}
+
+impl Timestamp {
+    pub fn column(&self) -> &'static str {
+        // column of the timestamp in the vault
+        match self {
+            Timestamp::Modified => "modified",
+            Timestamp::Revealed => "revealed",
+            Timestamp::Copied => "copied",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct EntryMetadata {
+    // when an entry was created, changed and last used
+    pub created: DateTime<Utc>,
+    pub modified: DateTime<Utc>,
+    pub revealed: Option<DateTime<Utc>>,
+    pub copied: Option<DateTime<Utc>>,
+}
+
+impl EntryMetadata {
+    pub fn lines(&self) -> [String; 2] {
+        // creation and change, then usage for display below the entry title
+        [
+            format!("Created {}  Modified {}", local(Some(self.created)), local(Some(self.modified))),
+            format!("Revealed {}  Copied {}", local(self.revealed), local(self.copied)),
+        ]
+    }
+
+    pub fn last_used(&self) -> Option<DateTime<Utc>> {
+        // latest reveal or copy, None if the entry was never used
+        self.revealed.max(self.copied)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum EntrySort {
+    // orders of the entries list, all but the name and template show the most recent or most used first
+    Name,
+    Template,
+    Created,
+    Modified,
+    Revealed,
+    Copied,
+    MostUsed,
+}
+
+impl EntrySort {
+    pub fn next(&self) -> EntrySort {
+        // the following order, starts over after the last one
+        match self {
+            EntrySort::Name => EntrySort::Template,
+            EntrySort::Template => EntrySort::Created,
+            EntrySort::Created => EntrySort::Modified,
+            EntrySort::Modified => EntrySort::Revealed,
+            EntrySort::Revealed => EntrySort::Copied,
+            EntrySort::Copied => EntrySort::MostUsed,
+            EntrySort::MostUsed => EntrySort::Name,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        // shown in the title of the entries list
+        match self {
+            EntrySort::Name => "name",
+            EntrySort::Template => "template",
+            EntrySort::Created => "created",
+            EntrySort::Modified => "last modified",
+            EntrySort::Revealed => "last revealed",
+            EntrySort::Copied => "last copied",
+            EntrySort::MostUsed => "most used",
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        // stored in the vault settings to be remembered
+        match self {
+            EntrySort::Name => "name",
+            EntrySort::Template => "template",
+            EntrySort::Created => "created",
+            EntrySort::Modified => "modified",
+            EntrySort::Revealed => "revealed",
+            EntrySort::Copied => "copied",
+            EntrySort::MostUsed => "most_used",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<EntrySort> {
+        // reads a stored order, None if it is unknown
+        [
+            EntrySort::Name,
+            EntrySort::Template,
+            EntrySort::Created,
+            EntrySort::Modified,
+            EntrySort::Revealed,
+            EntrySort::Copied,
+            EntrySort::MostUsed,
+        ]
+        .into_iter()
+        .find(|sort| sort.key() == key)
+    }
+
+    pub fn time(&self, metadata: &EntryMetadata) -> Option<DateTime<Utc>> {
+        // timestamp an entry is sorted by, None for the orders without one
+        match self {
+            EntrySort::Created => Some(metadata.created),
+            EntrySort::Modified => Some(metadata.modified),
+            EntrySort::Revealed => metadata.revealed,
+            EntrySort::Copied => metadata.copied,
+            EntrySort::Name | EntrySort::Template | EntrySort::MostUsed => None,
+        }
+    }
+}
+
+pub fn recent(names: &[String], metadata: impl Fn(&str) -> Option<EntryMetadata>, limit: usize) -> Vec<String> {
+    // the most recently used entries of the given ones, newest first
+    let mut used: Vec<(DateTime<Utc>, &String)> = names
+        .iter()
+        .filter_map(|name| Some((metadata(name)?.last_used()?, name)))
+        .collect();
+
+    used.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+    used.into_iter().take(limit).map(|(_, name)| name.clone()).collect()
+}
+
+pub fn now() -> String {
+    // current time in the format stored in the vault
+    format(Utc::now())
+}
+
+pub fn format(time: DateTime<Utc>) -> String {
+    // time in the format stored in the vault
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub fn parse(time: &str) -> Option<DateTime<Utc>> {
+    // reads a stored time, None if it is damaged
+    DateTime::parse_from_rfc3339(time).ok().map(|time| time.with_timezone(&Utc))
+}
+
+fn local(time: Option<DateTime<Utc>>) -> String {
+    // time for display in the local timezone
+    match time {
+        Some(time) => time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+        None => "never".to_string(),
+    }
+}
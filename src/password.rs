@@ -1,22 +1,6 @@
 use passwords::{analyzer, scorer, PasswordGenerator};
 use tui_textarea::TextArea;
 
-pub fn generate_strong_password(length: usize) -> String {
-    // uses thread_rng which is marked as cryptographically secure, see:
-    // https://rust-random.github.io/book/guide-rngs.html
-    let generator = PasswordGenerator::new()
-        .length(length)
-        .numbers(true)
-        .lowercase_letters(true)
-        .uppercase_letters(true)
-        .symbols(true)
-        .strict(true);
-
-    generator
-        .generate_one()
-        .expect("Failed to generate Password")
-}
-
 pub fn generate_char_only_password(length: usize) -> String {
     let generator = PasswordGenerator::new()
         .length(length)
@@ -29,16 +13,16 @@ pub fn generate_char_only_password(length: usize) -> String {
         .expect("Failed to generate Password")
 }
 
-pub fn validate_password_strength(textarea: &mut TextArea) -> (Option<String>, u32) {
+pub fn validate_password_strength(textarea: &TextArea) -> (Option<String>, u32) {
     // Returns an error if password not strong enough otherwise nothing
     // Returns an integer with an external password score
-    let input = textarea.lines()[0].clone();
-    let score = scorer::score(&analyzer::analyze(&input)).floor() as u32;
+    let input = textarea.lines()[0].as_str();
+    let score = scorer::score(&analyzer::analyze(input)).floor() as u32;
 
-    (password_strength(&input), score)
+    (password_strength(input), score)
 }
 
-fn password_strength(password: &String) -> Option<String> {
+fn password_strength(password: &str) -> Option<String> {
     // checks password if requirements are fulfilled
     if process_letters(password, is_numeric) {
         Some(String::from("Password needs one numerical character"))
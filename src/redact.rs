@@ -0,0 +1,174 @@
+use std::{
+    collections::{hash_map::RandomState, BTreeSet, HashSet},
+    hash::BuildHasher,
+    sync::Mutex,
+};
+
+
+// shorter texts are too common inside other words, they are only redacted where they stand alone
+const MIN_LENGTH: usize = 3;
+pub const REDACTED: &str = "[redacted]";
+
+// only keyed hashes are kept, so the registry itself never holds a readable copy
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+struct Registry {
+    // hashes of all known names and values, grouped by their length in bytes;
+    // short ones are kept apart as they only count as whole tokens
+    hasher: RandomState,
+    lengths: BTreeSet<usize>,
+    fingerprints: HashSet<(usize, u64)>,
+    short_fingerprints: HashSet<(usize, u64)>,
+}
+
+impl Registry {
+    fn new() -> Registry {
+        // the hash key is random for every run
+        Registry {
+            hasher: RandomState::new(),
+            lengths: BTreeSet::new(),
+            fingerprints: HashSet::new(),
+            short_fingerprints: HashSet::new(),
+        }
+    }
+
+    fn insert(&mut self, text: &str) {
+        // remembers the fingerprint of a text, empty ones match nothing
+        if text.is_empty() {
+            return;
+        }
+
+        let fingerprint = (text.len(), self.hasher.hash_one(text));
+        self.lengths.insert(text.len());
+        match text.chars().count() < MIN_LENGTH {
+            true => self.short_fingerprints.insert(fingerprint),
+            false => self.fingerprints.insert(fingerprint),
+        };
+    }
+
+    fn matches(&self, message: &str, start: usize, end: usize) -> bool {
+        // checks a piece of a message against the known fingerprints, short texts only if they stand alone
+        let text = &message[start..end];
+        let fingerprint = (text.len(), self.hasher.hash_one(text));
+
+        self.fingerprints.contains(&fingerprint)
+            || (self.short_fingerprints.contains(&fingerprint) && is_whole_token(message, start, end))
+    }
+
+    fn redact(&self, message: &str) -> String {
+        // replaces every occurrence of a registered text in a message
+        let mut matches: Vec<(usize, usize)> = vec![];
+        for &length in self.lengths.iter() {
+            for (start, _) in message.char_indices() {
+                let end = start + length;
+                if end > message.len() {
+                    break;
+                }
+                if message.is_char_boundary(end) && self.matches(message, start, end) {
+                    matches.push((start, end));
+                }
+            }
+        }
+        if matches.is_empty() {
+            return message.to_string();
+        }
+
+        merge_matches(message, matches)
+    }
+}
+
+fn is_whole_token(message: &str, start: usize, end: usize) -> bool {
+    // the text is not part of a longer word or number, e.g. a value of a field like "pin: 42"
+    let before = message[..start].chars().next_back();
+    let after = message[end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+pub fn register(text: &str) {
+    // remembers an entry name or field value so it never shows up in a log
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    registry.get_or_insert_with(Registry::new).insert(text);
+}
+
+pub fn clear() {
+    // forgets everything registered, used once the vault is locked
+    *REGISTRY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub fn redact(message: &str) -> String {
+    // replaces every occurrence of a registered text in a log message
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    match registry.as_ref() {
+        Some(registry) => registry.redact(message),
+        None => message.to_string(),
+    }
+}
+
+fn merge_matches(message: &str, mut matches: Vec<(usize, usize)>) -> String {
+
+    // overlapping matches are merged into a single marker
+    matches.sort_unstable();
+    let mut redacted = String::with_capacity(message.len());
+    let mut position = 0;
+    for (start, end) in matches {
+        if end <= position {
+            continue;
+        }
+        if start >= position {
+            redacted.push_str(&message[position..start]);
+            redacted.push_str(REDACTED);
+        }
+        position = end;
+    }
+    redacted.push_str(&message[position..]);
+
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(texts: &[&str]) -> Registry {
+        let mut registry = Registry::new();
+        texts.iter().for_each(|text| registry.insert(text));
+        registry
+    }
+
+    #[test]
+    fn redacts_registered_texts_anywhere() {
+        let registry = registry(&["hunter2", "GitHub"]);
+        assert_eq!(
+            registry.redact("Copied hunter2 of MyGitHub account"),
+            "Copied [redacted] of My[redacted] account",
+        );
+        assert_eq!(registry.redact("Nothing to hide"), "Nothing to hide");
+    }
+
+    #[test]
+    fn redacts_short_texts_only_as_whole_tokens() {
+        let registry = registry(&["42", "x"]);
+        assert_eq!(registry.redact("pin: 42, id 4242"), "pin: [redacted], id 4242");
+        assert_eq!(registry.redact("x marks the box"), "[redacted] marks the box");
+        assert_eq!(registry.redact("\"x\""), "\"[redacted]\"");
+    }
+
+    #[test]
+    fn merges_overlapping_matches() {
+        let registry = registry(&["abcd", "cdef"]);
+        assert_eq!(registry.redact("xabcdefx"), "x[redacted]x");
+    }
+
+    #[test]
+    fn handles_multi_byte_characters() {
+        let registry = registry(&["é", "Grüße"]);
+        assert_eq!(registry.redact("é, Grüßen"), "[redacted], [redacted]n");
+        assert_eq!(registry.redact("café"), "café");
+    }
+
+    #[test]
+    fn ignores_empty_texts() {
+        let registry = registry(&[""]);
+        assert_eq!(registry.redact("anything"), "anything");
+    }
+}
@@ -0,0 +1,208 @@
+use crate::secret::SecretString;
+
+
+// keys of filters that don't refer to a field of the entry
+const TEMPLATE_KEY: &str = "template";
+const TAG_KEY: &str = "tag";
+
+pub struct Query {
+    // typed search split into words matched against the name and key:value filters
+    pub words: Vec<String>,
+    pub filters: Vec<(String, String)>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Query {
+        // a word like "user:alice" becomes a filter, everything else is matched against the name
+        let mut query = Query { words: vec![], filters: vec![] };
+
+        for word in input.split_whitespace() {
+            match word.split_once(':') {
+                Some((key, value)) if !key.is_empty() && !value.is_empty() && key.chars().all(char::is_alphanumeric) => {
+                    query.filters.push((key.to_lowercase(), value.to_string()));
+                }
+                _ => query.words.push(word.to_string()),
+            }
+        }
+        query
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // nothing typed except spaces
+        self.words.is_empty() && self.filters.is_empty()
+    }
+}
+
+pub struct Candidate<'a> {
+    // what of an entry can be searched, fields are the non-private ones by their name
+    pub name: &'a str,
+    pub template: &'a str,
+    pub tags: &'a [String],
+    pub fields: &'a [(String, SecretString)],
+}
+
+pub struct Match {
+    // how well an entry fits the query and the characters of its name that matched
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+pub fn match_entry(query: &Query, candidate: &Candidate) -> Option<Match> {
+    // every word has to match the name and every filter its target, scores add up
+    let mut score = 0;
+    let mut positions = vec![];
+
+    for word in query.words.iter() {
+        let (word_score, word_positions) = fuzzy_match(word, candidate.name)?;
+        score += word_score;
+        positions.extend(word_positions);
+    }
+
+    for (key, value) in query.filters.iter() {
+        let best = match key.as_str() {
+            TEMPLATE_KEY => fuzzy_match(value, candidate.template).map(|(s, _)| s),
+            TAG_KEY => candidate.tags.iter().filter_map(|tag| fuzzy_match(value, tag)).map(|(s, _)| s).max(),
+            _ => candidate
+                .fields
+                .iter()
+                .filter(|(field, _)| field.to_lowercase().starts_with(key.as_str()))
+                .filter_map(|(_, content)| fuzzy_match(value, content.as_str()))
+                .map(|(s, _)| s)
+                .max(),
+        };
+        score += best?;
+    }
+
+    positions.sort_unstable();
+    positions.dedup();
+    Some(Match { score, positions })
+}
+
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    // finds the characters of the pattern in order, ignoring case; positions count characters, not bytes
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    let text: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = text.iter().map(|c| fold(*c)).collect();
+    let first = *pattern.first()?;
+
+    // every occurrence of the first character is tried as start, the best result wins
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..folded.len()).filter(|i| folded[*i] == first) {
+        let mut positions = vec![start];
+        for c in pattern[1..].iter() {
+            match (positions[positions.len() - 1] + 1..folded.len()).find(|i| folded[*i] == *c) {
+                Some(found) => positions.push(found),
+                None => break,
+            }
+        }
+
+        // later starts leave even fewer characters to match
+        if positions.len() < pattern.len() {
+            break;
+        }
+
+        let score = score_positions(&text, &positions);
+        if best.as_ref().is_none_or(|(s, _)| score > *s) {
+            best = Some((score, positions));
+        }
+    }
+    best
+}
+
+fn score_positions(text: &[char], positions: &[usize]) -> i64 {
+    // consecutive characters and starts of words count most, gaps and a late start cost points
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+
+    for position in positions.iter().copied() {
+        score += 16;
+        if position == 0 || text.get(position - 1).is_some_and(|c| is_separator(*c)) {
+            score += 10;
+        }
+        match previous {
+            Some(p) if p + 1 == position => score += 12,
+            Some(p) => score -= (position - p - 1).min(8) as i64,
+            None => score -= position.min(10) as i64,
+        }
+        previous = Some(position);
+    }
+
+    // shorter names fit better if everything else is equal
+    score - (text.len() as i64 / 8)
+}
+
+fn is_separator(c: char) -> bool {
+    // characters after which a new word begins
+    c.is_whitespace() || matches!(c, '/' | '-' | '_' | '.' | '@' | ':')
+}
+
+fn fold(c: char) -> char {
+    // lowercase of a single character, characters becoming several keep their first one
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate<'a>(name: &'a str, tags: &'a [String], fields: &'a [(String, SecretString)]) -> Candidate<'a> {
+        Candidate { name, template: "tp_web_credential", tags, fields }
+    }
+
+    #[test]
+    fn parses_words_and_filters() {
+        let query = Query::parse("  git User:alice tag: a:b:c ");
+        assert_eq!(query.words, vec!["git", "tag:"]);
+        assert_eq!(query.filters, vec![("user".to_string(), "alice".to_string()), ("a".to_string(), "b:c".to_string())]);
+        assert!(Query::parse(" \t ").is_empty());
+    }
+
+    #[test]
+    fn matches_in_order_ignoring_case() {
+        assert_eq!(fuzzy_match("GH", "github").unwrap().1, vec![0, 3]);
+        assert!(fuzzy_match("hg", "github").is_none());
+        assert!(fuzzy_match("", "github").is_none());
+
+        // consecutive characters at the start of a word beat scattered ones
+        let (close, _) = fuzzy_match("mail", "my mail").unwrap();
+        let (scattered, _) = fuzzy_match("mail", "my aim list").unwrap();
+        assert!(close > scattered);
+    }
+
+    #[test]
+    fn positions_count_characters_of_multi_byte_names() {
+        assert_eq!(fuzzy_match("ÄRZT", "Dr. Ärztehaus").unwrap().1, vec![4, 5, 6, 7]);
+        assert_eq!(fuzzy_match("本", "日本").unwrap().1, vec![1]);
+    }
+
+    #[test]
+    fn case_folding_that_changes_length_keeps_positions() {
+        // 'İ' lowercases to two characters, positions still point at the characters of the name
+        assert_eq!(fuzzy_match("ist", "İstanbul").unwrap().1, vec![0, 1, 2]);
+        assert_eq!(fuzzy_match("bul", "İstanbul").unwrap().1, vec![5, 6, 7]);
+        assert_eq!(fuzzy_match("straße", "STRAẞE").unwrap().1, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn combining_marks_are_characters_of_their_own() {
+        // a decomposed "é" is matched by "e", the mark itself only by a mark
+        assert_eq!(fuzzy_match("cafe", "Cafe\u{301}").unwrap().1, vec![0, 1, 2, 3]);
+        assert_eq!(fuzzy_match("fs", "Cafe\u{301}s").unwrap().1, vec![2, 5]);
+    }
+
+    #[test]
+    fn filters_match_template_tags_and_fields() {
+        let tags = vec!["work".to_string(), "2fa".to_string()];
+        let fields = vec![
+            ("Username".to_string(), SecretString::new("alice@example.com".to_string())),
+            ("Url".to_string(), SecretString::new("https://git.example.com".to_string())),
+        ];
+        let entry = candidate("GitHub", &tags, &fields);
+
+        let found = match_entry(&Query::parse("hub user:alice tag:2fa template:web"), &entry).unwrap();
+        assert_eq!(found.positions, vec![3, 4, 5]);
+        assert!(match_entry(&Query::parse("user:bob"), &entry).is_none());
+        assert!(match_entry(&Query::parse("tag:home"), &entry).is_none());
+        assert!(match_entry(&Query::parse("phone:1"), &entry).is_none());
+    }
+}
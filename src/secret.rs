@@ -0,0 +1,121 @@
+use std::{fmt, ops::Deref};
+
+use zeroize::Zeroize;
+
+use crate::memory::{lock_region, unlock_region};
+
+
+#[derive(Default, PartialEq)]
+pub struct SecretString {
+    // plaintext string that gets overwritten when dropped
+    inner: String,
+}
+
+impl SecretString {
+    pub fn new(inner: String) -> SecretString {
+        // takes ownership of a string without copying it and keeps it out of swap
+        lock_region(inner.as_ptr(), inner.capacity());
+        SecretString { inner }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // borrows the plaintext
+        self.inner.as_str()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(inner: String) -> SecretString {
+        SecretString::new(inner)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(inner: &str) -> SecretString {
+        SecretString::new(inner.to_string())
+    }
+}
+
+impl Deref for SecretString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Clone for SecretString {
+    fn clone(&self) -> SecretString {
+        SecretString::new(self.inner.clone())
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        let (ptr, capacity) = (self.inner.as_ptr(), self.inner.capacity());
+        self.inner.zeroize();
+        unlock_region(ptr, capacity);
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // never print the plaintext
+        f.write_str("SecretString([redacted])")
+    }
+}
+
+
+#[derive(Default, PartialEq)]
+pub struct SecretBytes {
+    // plaintext bytes that get overwritten when dropped
+    inner: Vec<u8>,
+}
+
+impl SecretBytes {
+    pub fn new(inner: Vec<u8>) -> SecretBytes {
+        // takes ownership of a buffer without copying it and keeps it out of swap
+        lock_region(inner.as_ptr(), inner.capacity());
+        SecretBytes { inner }
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        // borrows the plaintext
+        self.inner.as_slice()
+    }
+}
+
+impl From<&[u8]> for SecretBytes {
+    fn from(inner: &[u8]) -> SecretBytes {
+        SecretBytes::new(inner.to_vec())
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl Clone for SecretBytes {
+    fn clone(&self) -> SecretBytes {
+        SecretBytes::new(self.inner.clone())
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        let (ptr, capacity) = (self.inner.as_ptr(), self.inner.capacity());
+        self.inner.zeroize();
+        unlock_region(ptr, capacity);
+    }
+}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // never print the plaintext
+        f.write_str("SecretBytes([redacted])")
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use signal_hook::{consts::signal::*, flag, low_level};
+
+
+pub enum SignalEvent {
+    // signals the application has to react to
+    Terminate,
+    Suspend,
+}
+
+pub struct SignalHandler {
+    // flags set asynchronously by the registered signal handlers
+    terminate: Arc<AtomicBool>,
+    suspend: Arc<AtomicBool>,
+}
+
+impl SignalHandler {
+    pub fn new() -> io::Result<SignalHandler> {
+        // registers handlers for termination and suspend signals
+        let terminate = Arc::new(AtomicBool::new(false));
+        let suspend = Arc::new(AtomicBool::new(false));
+
+        flag::register(SIGINT, Arc::clone(&terminate))?;
+        flag::register(SIGTERM, Arc::clone(&terminate))?;
+
+        // closed ssh sessions and ctrl+z only exist on unix systems
+        #[cfg(unix)]
+        {
+            flag::register(SIGHUP, Arc::clone(&terminate))?;
+            flag::register(SIGTSTP, Arc::clone(&suspend))?;
+        }
+
+        log::info!("Registered signal handlers");
+        Ok(SignalHandler { terminate, suspend })
+    }
+
+    pub fn poll(&self) -> Option<SignalEvent> {
+        // returns the most important pending signal and resets its flag
+        if self.terminate.swap(false, Ordering::Relaxed) {
+            Some(SignalEvent::Terminate)
+        } else if self.suspend.swap(false, Ordering::Relaxed) {
+            Some(SignalEvent::Suspend)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn request_termination() {
+    // raw mode swallows ctrl+c, so it gets forwarded as a signal
+    if low_level::raise(SIGINT).is_err() {
+        log::warn!("Failed to raise termination signal");
+    }
+}
+
+pub fn request_suspend() {
+    // raw mode swallows ctrl+z, so it gets forwarded as a signal
+    #[cfg(unix)]
+    if low_level::raise(SIGTSTP).is_err() {
+        log::warn!("Failed to raise suspend signal");
+    }
+}
+
+pub fn suspend_process() {
+    // stops the process like the default handler would and returns after being continued
+    #[cfg(unix)]
+    if low_level::emulate_default_handler(SIGTSTP).is_err() {
+        log::warn!("Failed to suspend process");
+    }
+}
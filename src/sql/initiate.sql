@@ -25,11 +25,13 @@ VALUES ('Web Credential', CAST('{
     },
     {
       "name": "Username",
-      "private": false
+      "private": false,
+      "role": "username"
     },
     {
       "name": "Password",
-      "private": true
+      "private": true,
+      "role": "password"
     }
   ]
 }' AS BLOB));
@@ -62,7 +64,8 @@ VALUES ('SSH-Keypair', CAST('{
     },
     {
       "name": "SSH-Private",
-      "private": true
+      "private": true,
+      "role": "password"
     }
   ]
 }' AS BLOB));
@@ -80,6 +83,7 @@ VALUES ('Banking', CAST('{
   "deletable": false,
   "name": "Banking",
   "db_name": "tp_banking",
+  "reauthenticate": true,
   "elements": [
     {
       "name": "Name",
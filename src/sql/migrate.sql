@@ -0,0 +1,130 @@
+-- idempotent upgrades applied to new and existing vaults on every connect
+
+-- entries that require the master password before revealing or copying
+CREATE TABLE IF NOT EXISTS sensitive_entries
+(
+    description TEXT UNIQUE
+);
+
+-- settings that belong to the vault like the auto-lock timeout and the password policy
+CREATE TABLE IF NOT EXISTS settings
+(
+    name  TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- append-only trail of what happened to the entries, rows can't be changed or removed
+CREATE TABLE IF NOT EXISTS audit_log
+(
+    id     INTEGER PRIMARY KEY AUTOINCREMENT,
+    time   TEXT NOT NULL,
+    action TEXT NOT NULL,
+    entry  TEXT,
+    detail TEXT
+);
+
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit log is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit log is append-only');
+END;
+
+-- when entries were created, changed and last used, times in RFC 3339 and UTC
+CREATE TABLE IF NOT EXISTS entry_metadata
+(
+    description TEXT PRIMARY KEY,
+    created     TEXT NOT NULL,
+    modified    TEXT NOT NULL,
+    revealed    TEXT,
+    copied      TEXT
+);
+
+-- entries of older versions get the time of this migration
+INSERT OR IGNORE INTO entry_metadata (description, created, modified)
+SELECT description, strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+FROM descriptions;
+
+-- previous values of private fields, kept encrypted together with the nonce they were written with
+CREATE TABLE IF NOT EXISTS field_history
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    orig_table TEXT NOT NULL,
+    orig_desc  TEXT NOT NULL,
+    orig_entry TEXT NOT NULL,
+    value      TEXT NOT NULL,
+    nonce      TEXT NOT NULL UNIQUE,
+    replaced   TEXT NOT NULL
+);
+
+-- deleted entries until they get restored or purged, the previous values of their fields are
+-- kept in field_history under 'trash:<id>' instead of their name
+CREATE TABLE IF NOT EXISTS trash
+(
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    description TEXT    NOT NULL,
+    template    TEXT    NOT NULL,
+    sensitive   INTEGER NOT NULL,
+    created     TEXT,
+    modified    TEXT,
+    revealed    TEXT,
+    copied      TEXT,
+    deleted     TEXT    NOT NULL
+);
+
+-- encrypted values of trashed entries together with the nonce they were written with
+CREATE TABLE IF NOT EXISTS trash_values
+(
+    trash_id   INTEGER NOT NULL,
+    orig_entry TEXT    NOT NULL,
+    value      TEXT    NOT NULL,
+    nonce      TEXT    NOT NULL UNIQUE
+);
+
+-- free-form tags of entries, trashed entries keep theirs under 'trash:<id>'
+CREATE TABLE IF NOT EXISTS entry_tags
+(
+    description TEXT NOT NULL,
+    tag         TEXT NOT NULL,
+    UNIQUE (description, tag)
+);
+
+-- entries pinned to the top of the list, trashed ones are kept under 'trash:<id>' like their tags
+CREATE TABLE IF NOT EXISTS favorite_entries
+(
+    description TEXT UNIQUE
+);
+
+-- how often entries were revealed or copied, trashed ones are kept under 'trash:<id>' like their tags
+CREATE TABLE IF NOT EXISTS entry_usage
+(
+    description TEXT PRIMARY KEY,
+    uses        INTEGER NOT NULL
+);
+
+-- fields copied from the entries list as username or password, built-in templates of older vaults get them here;
+-- damaged templates are left alone for the health check
+UPDATE templates
+SET structure = CAST(json_set(CAST(structure AS TEXT), '$.elements[1].role', 'username', '$.elements[2].role', 'password') AS BLOB)
+WHERE CASE
+          WHEN json_valid(CAST(structure AS TEXT)) THEN json_extract(CAST(structure AS TEXT), '$.db_name') = 'tp_web_credential'
+              AND json_extract(CAST(structure AS TEXT), '$.elements[1].role') IS NULL
+          ELSE 0 END;
+
+UPDATE templates
+SET structure = CAST(json_set(CAST(structure AS TEXT), '$.elements[3].role', 'password') AS BLOB)
+WHERE CASE
+          WHEN json_valid(CAST(structure AS TEXT)) THEN json_extract(CAST(structure AS TEXT), '$.db_name') = 'tp_ssh_keypair'
+              AND json_extract(CAST(structure AS TEXT), '$.elements[3].role') IS NULL
+          ELSE 0 END;
+
+-- banking details of older vaults require the master password like in new ones, unless it was turned off since
+UPDATE templates
+SET structure = CAST(json_set(CAST(structure AS TEXT), '$.reauthenticate', json('true')) AS BLOB)
+WHERE CASE
+          WHEN json_valid(CAST(structure AS TEXT)) THEN json_extract(CAST(structure AS TEXT), '$.db_name') = 'tp_banking'
+              AND json_extract(CAST(structure AS TEXT), '$.reauthenticate') IS NULL
+          ELSE 0 END;
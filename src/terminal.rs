@@ -3,9 +3,9 @@
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::backend::CrosstermBackend;
-use std::{error::Error, io::stdout};
+use std::{error::Error, io::stdout, thread};
 
-use crate::types::Terminal;
+use crate::{file_manager, types::Terminal};
 
 pub fn setup_terminal() -> Result<Terminal, Box<dyn Error>> {
     // helper method to setup terminal. See Ratatui Manuals
@@ -30,13 +30,42 @@ pub fn restore_terminal(mut terminal: Terminal) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+pub fn suspend_terminal(terminal: &mut Terminal) -> Result<(), Box<dyn Error>> {
+    // hands the terminal back to the shell before the process gets stopped
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    log::info!("Suspended Terminal");
+    Ok(())
+}
+
+pub fn resume_terminal(terminal: &mut Terminal) -> Result<(), Box<dyn Error>> {
+    // takes over the terminal again and forces a full redraw
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    log::info!("Resumed Terminal");
+    Ok(())
+}
+
 pub fn initialize_panic_handler() {
+    // the clipboard, key material and database connection get cleaned up by their destructors while
+    // unwinding, everything else is done here; the vault lock is released here as well as destructors
+    // don't run on a panic during unwinding or an abort. Panics of other threads don't end the program,
+    // so they keep the lock
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
         log::error!("Programm has panic-ed! Exiting...");
 
-        execute!(std::io::stderr(), LeaveAlternateScreen).unwrap();
-        disable_raw_mode().unwrap();
+        execute!(std::io::stderr(), LeaveAlternateScreen).unwrap_or(());
+        disable_raw_mode().unwrap_or(());
+
+        if thread::current().name() == Some("main") {
+            file_manager::release_held_lock();
+        }
+
         original_hook(panic_info);
     }));
 }
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Local, Utc};
+
+
+pub struct TrashItem {
+    // an entry moved to the trash, template is the name of its table
+    pub id: i64,
+    pub name: String,
+    pub template: String,
+    pub deleted: Option<DateTime<Utc>>,
+}
+
+impl TrashItem {
+    pub fn deleted_local(&self) -> String {
+        // time of deletion for display in the local timezone
+        match self.deleted {
+            Some(time) => time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string(),
+            None => "unknown".to_string(),
+        }
+    }
+}
+
+pub fn trash_key(id: i64) -> String {
+    // stands in for the name of a trashed entry in other tables, never valid base64
+    format!("trash:{id}")
+}
+
+pub fn available_name(name: &str, available: impl Fn(&str) -> bool) -> String {
+    // the original name if it is free, otherwise the first free "name (restored n)"
+    if available(name) {
+        return name.to_string();
+    }
+
+    let mut candidate = format!("{name} (restored)");
+    let mut number = 2;
+    while !available(&candidate) {
+        candidate = format!("{name} (restored {number})");
+        number += 1;
+    }
+    candidate
+}
@@ -1,4 +1,4 @@
-use crate::app::extras::SingleValue;
+use crate::{app::extras::SingleValue, secret::SecretString};
 use ratatui::backend::CrosstermBackend;
 use std::sync::{Arc, Mutex};
 use std::{error::Error, io::Stdout};
@@ -8,3 +8,6 @@
 pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
 pub type ClState = Arc<Mutex<SingleValue<Option<usize>>>>;
+
+// entry name and (field index, value) of some fields of an entry
+pub type FieldValues = (String, Vec<(usize, SecretString)>);
@@ -1,5 +1,13 @@
-use crate::app::{states::LoginState, App};
-use ratatui::Frame;
+use crate::{
+    app::{states::LoginState, App},
+    memory,
+};
+use ratatui::{
+    layout::Alignment,
+    style::Stylize,
+    widgets::block::{Position, Title},
+    Frame,
+};
 
 pub(crate) mod fields;
 mod login;
@@ -13,3 +21,16 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
         _ => login::draw_ui(frame, app),
     }
 }
+
+pub fn protection_warning<'a>() -> Option<Title<'a>> {
+    // visible hint when secrets could not be locked in memory
+    if memory::is_degraded() {
+        Some(
+            Title::from(" \u{26A0} Reduced memory protection ".light_red().bold())
+                .alignment(Alignment::Right)
+                .position(Position::Top),
+        )
+    } else {
+        None
+    }
+}
@@ -1,10 +1,13 @@
 use ratatui::prelude::Style;
 use tui_textarea::TextArea;
+use zeroize::Zeroize;
 
 pub fn input_field<'a>() -> TextArea<'a> {
     // creates a new input field
+    // undo history is disabled so no copies of typed secrets are kept
     let mut text_input = TextArea::default();
     text_input.set_cursor_line_style(Style::default());
+    text_input.set_max_histories(0);
 
     text_input
 }
@@ -15,6 +18,32 @@ pub fn password_field<'a>() -> TextArea<'a> {
 
     password_input.set_cursor_line_style(Style::default());
     password_input.set_mask_char('\u{2022}'); //U+2022 BULLET (•)
+    password_input.set_max_histories(0);
 
     password_input
 }
+
+pub fn wipe_field<'a>(field: &mut TextArea<'a>, replacement: TextArea<'a>) {
+    // replaces a field and overwrites the contents of the old one
+    field.set_yank_text(String::new());
+    let old = std::mem::replace(field, replacement);
+
+    for mut line in old.into_lines() {
+        line.zeroize();
+    }
+}
+
+pub fn refill_field(field: &mut TextArea, text: &str) {
+    // replaces the contents of a field but keeps its look, the old contents get overwritten
+    let mut replacement = input_field();
+    if let Some(mask) = field.mask_char() {
+        replacement.set_mask_char(mask);
+    }
+    if let Some(block) = field.block() {
+        replacement.set_block(block.clone());
+    }
+    replacement.set_placeholder_text(field.placeholder_text().to_string());
+    replacement.insert_str(text);
+
+    wipe_field(field, replacement);
+}
@@ -1,15 +1,16 @@
 use ratatui::widgets::block::{Position, Title};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    prelude::{Color, Style},
+    prelude::{Line, Span, Style},
     style::Stylize,
-    widgets::{Block, BorderType, Borders, Padding, Paragraph},
+    widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
     Frame,
 };
 
 use crate::{
     app::{states::LoginState, App},
     password::validate_password_strength,
+    ui::protection_warning,
 };
 
 const TITLE: [&str; 5] = [
@@ -41,6 +42,22 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             Constraint::Percentage(10),
         ],
     ).split(main_layout[2]);
+    let info_layout = Layout::new(
+        Direction::Horizontal,
+        [
+            Constraint::Percentage(10),
+            Constraint::Percentage(80),
+            Constraint::Percentage(10),
+        ],
+    ).split(main_layout[1]);
+    let hint_layout = Layout::new(
+        Direction::Horizontal,
+        [
+            Constraint::Percentage(10),
+            Constraint::Percentage(80),
+            Constraint::Percentage(10),
+        ],
+    ).split(main_layout[3]);
 
     // create title
     let title = TITLE.join("\n");
@@ -57,51 +74,70 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
             register_password(frame, app, center_layout[1]);
             " ❱ Create new Vault ❰ "
         }
+        LoginState::VaultUnavailable => {
+            vault_unavailable(frame, app, center_layout[1]);
+            " ❱ Vault Login ❰ "
+        }
+        LoginState::ChooseVaultPath => {
+            choose_vault_path(frame, app, center_layout[1]);
+            " ❱ Open or create Vault ❰ "
+        }
         _ => unreachable!(),
     };
 
+    // show the selected vault above and the picker keys below the input
+    if app.vault_state.state != LoginState::ChooseVaultPath {
+        selected_vault(frame, app, info_layout[1]);
+    }
+    render_hints(frame, app, hint_layout[1]);
+
     // display title
+    let mut title_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(
+            Title::from(action)
+                .alignment(Alignment::Center)
+                .position(Position::Bottom),
+        );
+
+    if let Some(warning) = protection_warning() {
+        title_block = title_block.title(warning);
+    }
+
     frame.render_widget(
         Paragraph::new(title)
             .alignment(Alignment::Center)
             .style(Style::new().bold())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .title(
-                        Title::from(action)
-                            .alignment(Alignment::Center)
-                            .position(Position::Bottom),
-                    ),
-            ),
+            .block(title_block),
         main_layout[0],
     );
 }
 
 fn login_with_password(frame: &mut Frame, app: &mut App, area: Rect) {
     // page for logging in
+    let theme = app.config.theme;
     let password_field = &mut app.text_fields.password_input;
     password_field.set_placeholder_text("Please enter your password");
 
     // style color according to last sent input
     if app.vault_state.state == LoginState::Login {
-        password_field.set_style(Style::default().fg(Color::LightYellow));
+        password_field.set_style(Style::default().fg(theme.selected));
         password_field.set_block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .fg(Color::LightYellow)
+                .fg(theme.selected)
                 .padding(Padding::horizontal(1))
                 .title("Enter password"),
         );
     } else {
-        password_field.set_style(Style::default().fg(Color::LightRed));
+        password_field.set_style(Style::default().fg(theme.danger));
         password_field.set_block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .fg(Color::LightRed)
+                .fg(theme.danger)
                 .padding(Padding::horizontal(1))
                 .title("Invalid Password! Try again!"),
         );
@@ -110,6 +146,96 @@ fn login_with_password(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(password_field.widget(), area);
 }
 
+fn selected_vault(frame: &mut Frame, app: &mut App, area: Rect) {
+    // name and location of the vault the password is entered for
+    let theme = app.config.theme;
+    let Some(vault) = app.vault_list.current_item() else {
+        return;
+    };
+    let position = format!(
+        " ({}/{})",
+        app.vault_list.current_index().unwrap_or(0) + 1,
+        app.vault_list.len(),
+    );
+
+    let lines = vec![
+        Line::from(vec![
+            Span::from("Vault: "),
+            Span::from(vault.name.clone()).bold(),
+            Span::from(position).fg(theme.disabled),
+        ]),
+        Line::from(Span::from(vault.path.display().to_string()).fg(theme.disabled)),
+    ];
+
+    let height = area.height.min(lines.len() as u16);
+    let area = Rect { y: area.bottom() - height, height, ..area };
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), area);
+}
+
+fn render_hints(frame: &mut Frame, app: &mut App, area: Rect) {
+    // keys available on the login screen
+    let theme = app.config.theme;
+    let hints = match app.vault_state.state {
+        LoginState::ChooseVaultPath => "Enter: open or create vault · Esc: back".to_string(),
+        _ => format!(
+            "↑/↓: switch vault · Ctrl+{}: open or create vault · Esc: quit",
+            app.config.keys.new_vault.to_ascii_uppercase(),
+        ),
+    };
+
+    let mut lines = vec![Line::from(Span::from(hints).fg(theme.disabled))];
+    if let Some(warning) = &app.file_manager.legacy_warning {
+        lines.insert(0, Line::from(Span::from(warning.clone()).fg(theme.danger)));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+fn vault_unavailable(frame: &mut Frame, app: &mut App, area: Rect) {
+    // the selected vault can't be opened by this instance
+    let theme = app.config.theme;
+    let error = app.vault_error.clone().unwrap_or_default();
+
+    frame.render_widget(
+        Paragraph::new(error).fg(theme.danger).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick)
+                .fg(theme.danger)
+                .padding(Padding::horizontal(1))
+                .title("Vault unavailable"),
+        ),
+        area,
+    );
+}
+
+fn choose_vault_path(frame: &mut Frame, app: &mut App, area: Rect) {
+    // input for the file of another vault
+    let theme = app.config.theme;
+    let path_field = &mut app.text_fields.vault_path_input;
+    path_field.set_placeholder_text("~/Vaults/work.sqlite3");
+
+    // style color according to the last error
+    let (color, title) = match &app.vault_error {
+        Some(error) => (theme.danger, error.clone()),
+        None => (theme.selected, "Path of an existing or new vault file".to_string()),
+    };
+    path_field.set_style(Style::default().fg(color));
+    path_field.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .fg(color)
+            .padding(Padding::horizontal(1))
+            .title(title),
+    );
+
+    frame.render_widget(path_field.widget(), area);
+}
+
 fn register_password(frame: &mut Frame, app: &mut App, area: Rect) {
     // page for registering
     match app.vault_state.state {
@@ -125,27 +251,28 @@ fn register_password(frame: &mut Frame, app: &mut App, area: Rect) {
 
 fn first_password(frame: &mut Frame, app: &mut App, area: Rect) {
     // handle first registration password and check needed criteria
+    let theme = app.config.theme;
     let pw_field = &mut app.text_fields.password_input;
     pw_field.set_placeholder_text("Please enter a strong password");
 
     // set design depending on validation of password strength
     let result = validate_password_strength(pw_field);
     if let Some(error) = result.0 {
-        pw_field.set_style(Style::default().fg(Color::LightRed));
+        pw_field.set_style(Style::default().fg(theme.danger));
         pw_field.set_block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .fg(Color::LightRed)
+                .fg(theme.danger)
                 .padding(Padding::horizontal(1))
                 .title(format!("{} ({}%)", error, result.1)),
         );
     } else {
-        pw_field.set_style(Style::default().fg(Color::LightGreen));
+        pw_field.set_style(Style::default().fg(theme.success));
         pw_field.set_block(
             Block::default()
                 .borders(Borders::ALL)
-                .fg(Color::LightGreen)
+                .fg(theme.success)
                 .padding(Padding::horizontal(1))
                 .title(format!("Strong Password ({}%)", result.1)),
         );
@@ -156,27 +283,28 @@ fn first_password(frame: &mut Frame, app: &mut App, area: Rect) {
 
 fn confirm_password(frame: &mut Frame, app: &mut App, area: Rect) {
     // check for matching second password
+    let theme = app.config.theme;
     let pw_field = &mut app.text_fields.password_input;
     pw_field.set_placeholder_text("Please confirm your password");
 
     // render ui depending if entries match or not
     match app.vault_state.state {
         LoginState::NewVaultConfirmMatch => {
-            pw_field.set_style(Style::default().fg(Color::LightGreen));
+            pw_field.set_style(Style::default().fg(theme.success));
             pw_field.set_block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .fg(Color::LightGreen)
+                    .fg(theme.success)
                     .padding(Padding::horizontal(1))
                     .title("Press Enter to confirm"),
             );
         }
         LoginState::NewVaultConfirmNoMatch => {
-            pw_field.set_style(Style::default().fg(Color::LightRed));
+            pw_field.set_style(Style::default().fg(theme.danger));
             pw_field.set_block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .fg(Color::LightRed)
+                    .fg(theme.danger)
                     .padding(Padding::horizontal(1))
                     .title("Password do not match!"),
             )
@@ -1,4 +1,10 @@
-use crate::app::App;
+use crate::{
+    app::{extras::VaultAction, states::{EntryEdit, EntryView, ReauthAction}, App},
+    config::Theme,
+    folders::{self, ListRow},
+    generator::{strength, GeneratorRow},
+    ui::protection_warning,
+};
 use ratatui::widgets::Wrap;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -6,38 +12,45 @@
     style::Stylize,
     widgets::{
         block::{Position, Title},
-        Block, BorderType, Borders, List, ListItem, Padding, Paragraph, Tabs,
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding, Paragraph, Tabs,
     },
     Frame,
 };
-use ratatui::text::Line;
+use ratatui::text::{Line, Span};
+use std::borrow::Cow;
 use tui_textarea::TextArea;
+use unicode_width::UnicodeWidthChar;
 
 pub fn draw_ui(frame: &mut Frame, app: &mut App) {
     // main view
+    let theme = app.config.theme;
     let main_layout = Layout::new(
         Direction::Vertical,
-        [Constraint::Length(3), Constraint::Min(3)],
+        [Constraint::Length(3), Constraint::Min(3), Constraint::Length(1)],
     )
         .split(frame.size());
 
     // create and name tabs
     let color = if app.page_selected {
-        Color::DarkGray
+        theme.disabled
     } else {
-        Color::White
+        theme.text
     };
-    let tab_titles = vec!["Credentials", "New Entry", "Templates"];
+    let tab_titles = vec!["Credentials", "New Entry", "Templates", "Vault", "Audit", "Trash"];
+    let mut tabs_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .fg(color)
+        .title("Pages");
+
+    if let Some(warning) = protection_warning() {
+        tabs_block = tabs_block.title(warning);
+    }
+
     let tabs = Tabs::new(tab_titles)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .fg(color)
-                .title("Pages"),
-        )
+        .block(tabs_block)
         .select(app.page_index.index)
-        .highlight_style(Style::default().bold().yellow());
+        .highlight_style(Style::default().bold().fg(theme.item));
 
     frame.render_widget(tabs, main_layout[0]);
 
@@ -45,13 +58,39 @@ pub fn draw_ui(frame: &mut Frame, app: &mut App) {
     match app.page_index.index {
         0 => page_credentials(frame, app, main_layout[1]),
         1 => page_new_entry(frame, app, main_layout[1]),
-        2 => page_template_creator(frame, app, main_layout[1]),
+        2 => page_templates(frame, app, main_layout[1]),
+        3 => page_vault(frame, app, main_layout[1]),
+        4 => page_audit(frame, app, main_layout[1]),
+        5 => page_trash(frame, app, main_layout[1]),
         _ => unreachable!(),
     }
+
+    // status bar counting down until the clipboard gets cleared, a running sequence waits for its key
+    // as pasting can't be noticed
+    let status = match (seconds_left(app), app.clipboard.next_in_sequence()) {
+        (Some(secs), Some(next)) => format!(
+            " Clipboard gets cleared in {secs}s. Pasting isn't detected, press Ctrl+{} after pasting to copy {next}",
+            app.config.keys.copy_sequence.to_ascii_uppercase(),
+        )
+        .fg(theme.highlight),
+        (Some(secs), None) => format!(
+            " Clipboard gets cleared in {secs}s, press Ctrl+{} to clear it now",
+            app.config.keys.clear_clipboard.to_ascii_uppercase(),
+        )
+        .fg(theme.highlight),
+        (None, _) => " Nothing copied".fg(theme.disabled),
+    };
+    frame.render_widget(Paragraph::new(Line::from(status)), main_layout[2]);
+}
+
+fn seconds_left(app: &App) -> Option<u64> {
+    // whole seconds until the copied value gets cleared, rounded up
+    app.clipboard.remaining().map(|left| left.as_secs_f64().ceil() as u64)
 }
 
 fn page_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
     // split view of credentials
+    let theme = app.config.theme;
     let lists_layout = Layout::new(
         Direction::Horizontal,
         [Constraint::Percentage(50), Constraint::Percentage(50)],
@@ -67,65 +106,117 @@ fn page_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // create items to be displayed
     let entry_color = if app.page_selected {
-        Color::DarkGray
+        theme.disabled
     } else {
-        Color::Yellow
+        theme.item
     };
 
-    let search = app.text_fields.search_bar.lines()[0].to_lowercase();
-    let items: Vec<ListItem> = app
+    // only the rows that fit are built, the offset follows the selection
+    let height = password_list[0].height.saturating_sub(2) as usize;
+    let selected = app.entries_list.state.selected();
+    let offset = visible_offset(selected, app.entries_list.state.offset(), height, app.entries_list.len());
+    *app.entries_list.state.offset_mut() = offset;
+
+    let lines: Vec<(Line, Option<&str>)> = app
         .entries_list
         .items
         .iter()
-        .map(|i| {
-            let name = i.clone();
+        .skip(offset)
+        .take(height)
+        .map(|row| {
+            let indent = "  ".repeat(row.depth());
 
-            if app.page_selected || search.is_empty() {
-                ListItem::new(name)
-                    .style(Style::default().fg(entry_color))
-            } else {
-                let lower = name.to_lowercase();
-
-                let line = if let Some(index) = lower.find(&search) {
-                    let first = &name[..index];
-                    let middle = &name[index..index + search.len()];
-                    let last = &name[index + search.len()..];
-
-                    Line::from(vec![
-                        first.to_string().yellow(),
-                        middle.to_string().light_yellow().bold().on_dark_gray(),
-                        last.to_string().yellow(),
-                    ])
-                } else {
-                    Line::from("An Error occurred")
-                };
+            let (name, count) = match row {
+                ListRow::Header { count, .. } | ListRow::Group { count, .. } => {
+                    let line = Line::from(vec![
+                        row.label().to_string().fg(theme.unfocused).bold(),
+                        format!(" ({count})").fg(theme.unfocused),
+                    ]);
+                    return (line, None);
+                }
+                ListRow::Folder { count, collapsed, .. } => {
+                    let arrow = if *collapsed { "▸" } else { "▾" };
+                    (format!("{indent}{arrow} {}", row.label()), Some(count))
+                }
+                ListRow::Shortcut { .. } | ListRow::Entry { .. } => (format!("{indent}{}", row.label()), None),
+            };
+
+            // search results show their whole name, matched characters are highlighted
+            let positions = match row {
+                ListRow::Shortcut { name, .. } if !app.page_selected => app.search_matches.get(name),
+                _ => None,
+            };
+            let mut line = match positions {
+                Some(positions) if !positions.is_empty() => highlight_matches(&name, positions, theme),
+                _ => Line::from(name.fg(entry_color)),
+            };
+            if let Some(count) = count {
+                line.spans.push(format!(" ({count})").fg(theme.unfocused));
+            }
+            if matches!(row, ListRow::Entry { name, .. } if app.index.is_favorite(name)) {
+                line.spans.push(" ★".fg(theme.unfocused));
+            }
+
+            (line, row.entry().and_then(|name| app.index.summary(name)))
+        })
+        .collect();
 
-                ListItem::new(line)
+    // summaries like the username form a second column, names keep at least half of the width
+    let width = password_list[0].width.saturating_sub(5) as usize;
+    let column = lines
+        .iter()
+        .filter(|(_, summary)| summary.is_some())
+        .map(|(line, _)| line.width() + 2)
+        .max()
+        .unwrap_or_default()
+        .min(width / 2);
+
+    let items: Vec<ListItem> = lines
+        .into_iter()
+        .map(|(mut line, summary)| {
+            let space = width.saturating_sub(column);
+            if let Some(summary) = summary.filter(|_| line.width() < column && space > 0) {
+                line.spans.push(" ".repeat(column - line.width()).into());
+                line.spans.push(summary.chars().take(space).collect::<String>().fg(theme.unfocused));
             }
+            ListItem::new(line)
         })
         .collect();
 
     // create a list from all list items and highlight the currently selected one
     let border_color = if app.page_selected {
-        Color::DarkGray
+        theme.disabled
     } else {
-        Color::White
+        theme.text
     };
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .fg(border_color)
+        .title(match &app.tag_filter {
+            Some(tag) => format!("List by {} tagged {tag}", app.vault_settings.entry_sort.name()),
+            None => format!("List by {}", app.vault_settings.entry_sort.name()),
+        });
+
+    // confirms what was copied from the list
+    if let Some(status) = &app.list_status {
+        let line = match status {
+            Ok(message) => message.clone().fg(theme.success),
+            Err(message) => message.clone().fg(theme.danger),
+        };
+        block = block.title(Title::from(line).position(Position::Bottom));
+    }
+
     let items = List::new(items)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .fg(border_color)
-                .title("List"),
-        )
+        .block(block)
         .highlight_style(
             Style::default()
-                .fg(Color::LightYellow)
+                .fg(theme.selected)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol(" ❱ ");
 
-    frame.render_stateful_widget(items, password_list[0], &mut app.entries_list.state);
+    let mut state = ListState::default().with_selected(selected.map(|index| index - offset));
+    frame.render_stateful_widget(items, password_list[0], &mut state);
 
     // search field
     let search_bar = &mut app.text_fields.search_bar;
@@ -141,7 +232,7 @@ fn page_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // set bar color to blue if search active
     if !search_bar.is_empty() {
-        let block = set_border_color(search_bar, Color::LightBlue);
+        let block = set_border_color(search_bar, theme.highlight);
         search_bar.set_block(block);
     }
 
@@ -160,19 +251,341 @@ fn page_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
             lists_layout[1],
         );
     }
+
+    // previous values and the input for a new value of the focused field, folder or tags
+    if app.field_history.is_some() {
+        render_field_history(frame, app, lists_layout[1]);
+    }
+    if app.entry_edit.is_some() {
+        render_entry_input(frame, app, lists_layout[1]);
+    }
+    if app.generator.is_some() {
+        render_generator(frame, app, lists_layout[1]);
+    }
+
+    // password prompt for sensitive entries on top
+    if app.reauth_prompt.is_some() {
+        render_reauth_prompt(frame, app, lists_layout[1]);
+    }
+}
+
+fn render_reauth_prompt(frame: &mut Frame, app: &mut App, area: Rect) {
+    // popup asking for the master password again
+    let theme = app.config.theme;
+    let failed = app.reauth_prompt.as_ref().is_some_and(|p| p.failed);
+    let (color, title) = if failed {
+        (theme.danger, "Invalid Password! Try again!")
+    } else {
+        (theme.selected, "Re-enter master password")
+    };
+
+    let protected = match app.reauth_prompt.as_ref().map(|p| p.action) {
+        Some(ReauthAction::ToggleTemplate(_)) => "Template",
+        _ => "Entry",
+    };
+
+    let popup = centered_rect(area, 80, 3);
+    let field = &mut app.text_fields.reauth_input;
+    field.set_placeholder_text(format!("{protected} is protected, press Esc to cancel"));
+    field.set_style(Style::default().fg(color));
+    field.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .fg(color)
+            .padding(Padding::horizontal(1))
+            .title(title),
+    );
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(field.widget(), popup);
+}
+
+fn render_entry_input(frame: &mut Frame, app: &mut App, area: Rect) {
+    // popup taking the new value of a field, the folder or the tags of the entry
+    let theme = app.config.theme;
+    let (target, placeholder) = match app.entry_edit {
+        Some(EntryEdit::Field(index)) => {
+            let Some((field, private)) = app.current_entry.as_ref().and_then(|entry| {
+                let (name, _, _) = entry.fields.items.get(index)?;
+                Some((name.clone(), app.current_entry_template()?.elements.get(index + 1)?.private))
+            }) else {
+                return;
+            };
+            let placeholder = match private {
+                true => format!(
+                    "Press Enter to generate a password, Ctrl+{} for options, Esc to cancel",
+                    app.config.keys.generator.to_ascii_uppercase(),
+                ),
+                false => "Press Enter to save, Esc to cancel".to_string(),
+            };
+            (format!("New value for {field}"), placeholder)
+        }
+        Some(EntryEdit::Folder) => (
+            "Folder, subfolders separated by /".to_string(),
+            "Leave empty for the top level, Esc to cancel".to_string(),
+        ),
+        Some(EntryEdit::Tags) => (
+            "Tags, separated by commas".to_string(),
+            "Leave empty to remove all tags, Esc to cancel".to_string(),
+        ),
+        None => return,
+    };
+
+    let (color, title) = match &app.entry_edit_error {
+        Some(error) => (theme.danger, format!("Not saved: {error}")),
+        None => (theme.selected, target),
+    };
+
+    let popup = centered_rect(area, 80, 3);
+    let input = &mut app.text_fields.field_input;
+
+    input.set_placeholder_text(placeholder);
+    input.set_style(Style::default().fg(color));
+    input.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .fg(color)
+            .padding(Padding::horizontal(1))
+            .title(title),
+    );
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(input.widget(), popup);
+}
+
+fn render_generator(frame: &mut Frame, app: &mut App, area: Rect) {
+    // popup with the options of the password generator and a preview with its entropy below
+    let theme = app.config.theme;
+    let Some(generator) = app.generator.as_mut() else {
+        return;
+    };
+
+    let items: Vec<ListItem> = generator
+        .rows
+        .items
+        .iter()
+        .map(|row| {
+            ListItem::new(Line::from(vec![
+                format!("{:<20}", row.name()).fg(theme.unfocused),
+                row.value(&generator.options).fg(theme.text),
+            ]))
+        })
+        .collect();
+
+    let mut preview = vec![Line::default()];
+    match &generator.preview {
+        Ok(password) => {
+            let bits = generator.options.entropy_bits();
+            preview.push(Line::from(password.as_str().fg(theme.highlight)));
+            preview.push(Line::from(
+                format!("About {bits:.0} bits of entropy, {}", strength(bits)).fg(theme.unfocused),
+            ));
+        }
+        Err(error) => preview.push(Line::from(error.as_str().fg(theme.danger))),
+    }
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .fg(theme.selected)
+        .title(format!("Generate password for {}", generator.field))
+        .title(
+            Title::from("←→ change  Tab new preview  Enter use  Esc cancel")
+                .position(Position::Bottom)
+                .alignment(Alignment::Center),
+        );
+    if generator.rows.current_item() == Some(&GeneratorRow::SymbolSet) {
+        block = block.title(Title::from("Type to add symbols, Backspace removes").alignment(Alignment::Right));
+    }
+
+    let height = (generator.rows.len() as u16 + 6).min(area.height);
+    let popup = centered_rect(area, 90, height);
+    let inner = block.inner(popup);
+    let layout = Layout::new(Direction::Vertical, [Constraint::Min(1), Constraint::Length(4)]).split(inner);
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+    frame.render_stateful_widget(
+        List::new(items)
+            .highlight_style(Style::default().fg(theme.selected).add_modifier(Modifier::BOLD))
+            .highlight_symbol(" ❱ "),
+        layout[0],
+        &mut generator.rows.state,
+    );
+    frame.render_widget(Paragraph::new(preview).wrap(Wrap { trim: false }), layout[1]);
+}
+
+fn render_field_history(frame: &mut Frame, app: &mut App, area: Rect) {
+    // popup listing the previous values of a field, newest first
+    let theme = app.config.theme;
+    let copy_key = app.config.keys.copy.to_ascii_uppercase();
+    let Some(history) = app.field_history.as_mut() else {
+        return;
+    };
+
+    let items: Vec<ListItem> = history
+        .values
+        .items
+        .iter()
+        .map(|(value, hidden)| {
+            // revealed values are borrowed, a plain copy would stay in memory
+            let text: Cow<str> = if *hidden {
+                Cow::Owned("\u{2022}".repeat(value.value.chars().count()))
+            } else {
+                Cow::Borrowed(value.value.as_str())
+            };
+            ListItem::new(Line::from(vec![
+                format!("{}  ", value.replaced_local()).fg(theme.unfocused),
+                Span::styled(text, Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .fg(theme.selected)
+        .title(format!("Previous values of {}", history.field))
+        .title(
+            Title::from(format!("Tab reveal  {copy_key} copy  Enter restore  Esc close"))
+                .position(Position::Bottom)
+                .alignment(Alignment::Center),
+        );
+    if let Some(status) = &history.status {
+        let (message, color) = match status {
+            Ok(message) => (message.as_str(), theme.success),
+            Err(message) => (message.as_str(), theme.danger),
+        };
+        block = block.title(Title::from(message.fg(color)).alignment(Alignment::Right));
+    }
+
+    let height = (history.values.len() as u16).clamp(1, area.height.saturating_sub(2).max(1)) + 2;
+    let popup = centered_rect(area, 90, height);
+    frame.render_widget(Clear, popup);
+
+    if history.values.items.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No previous values yet".fg(theme.disabled)).block(block),
+            popup,
+        );
+    } else {
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol(" ❱ ");
+        frame.render_stateful_widget(list, popup, &mut history.values.state);
+    }
+}
+
+fn highlight_matches<'a>(text: &str, positions: &[usize], theme: Theme) -> Line<'a> {
+    // splits a text into runs of matched and unmatched characters, positions count characters
+    let mut spans = vec![];
+    let mut run = String::new();
+    let mut matched = false;
+
+    for (index, c) in text.chars().enumerate() {
+        // combining marks stay with the character before them, alone in a span they would not be drawn
+        let is_match = match c.width() {
+            Some(0) if !run.is_empty() => matched,
+            _ => positions.binary_search(&index).is_ok(),
+        };
+        if is_match != matched && !run.is_empty() {
+            spans.push(styled_run(std::mem::take(&mut run), matched, theme));
+        }
+        matched = is_match;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(styled_run(run, matched, theme));
+    }
+    Line::from(spans)
+}
+
+fn styled_run<'a>(run: String, matched: bool, theme: Theme) -> ratatui::text::Span<'a> {
+    // a matched run stands out from the rest of the name
+    if matched {
+        run.fg(theme.selected).bold().bg(theme.disabled)
+    } else {
+        run.fg(theme.item)
+    }
+}
+
+fn visible_offset(selected: Option<usize>, offset: usize, height: usize, len: usize) -> usize {
+    // first visible row of a list, moved only as far as needed to keep the selection in view
+    let mut offset = offset.min(len.saturating_sub(height));
+    if let Some(selected) = selected {
+        if selected < offset {
+            offset = selected;
+        } else if height > 0 && selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+    }
+    offset
+}
+
+fn centered_rect(area: Rect, percent_x: u16, height: u16) -> Rect {
+    // returns a rect with a fixed height centered inside an area
+    let vertical = Layout::new(
+        Direction::Vertical,
+        [
+            Constraint::Min(0),
+            Constraint::Length(height),
+            Constraint::Min(0),
+        ],
+    )
+        .split(area);
+
+    Layout::new(
+        Direction::Horizontal,
+        [
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ],
+    )
+        .split(vertical[1])[1]
 }
 
 fn render_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
     // function for rendering selected credentials
-    if let Some((temp_name, entries)) = &app.current_entry {
+    let theme = app.config.theme;
+    let protected = app.is_entry_protected();
+
+    if let Some(EntryView { name, template: temp_name, fields: entries }) = &app.current_entry {
         let title_content = Layout::new(Direction::Vertical, vec![
-            Constraint::Length(2),
+            Constraint::Length(5),
             Constraint::Min(0),
         ]).split(area);
 
-        // show template name
+        // show template name and if the master password is needed to reveal
+        let mut title = vec![temp_name.as_str().bold()];
+        if protected {
+            title.push(" (protected)".fg(theme.disabled));
+        }
+        if app.index.is_favorite(name) {
+            title.push(" ★".fg(theme.item));
+        }
+
+        // timestamps, folder and tags of the entry below
+        let mut lines = vec![Line::from(title)];
+        if let Some(metadata) = app.index.metadata(name) {
+            lines.extend(metadata.lines().map(|line| Line::from(line.fg(theme.unfocused))));
+        }
+
+        let folder = match folders::folder(name) {
+            "" => "none",
+            folder => folder,
+        };
+        let tags = match app.index.tags(name) {
+            [] => "none".to_string(),
+            tags => tags.join(", "),
+        };
+        lines.push(Line::from(format!("Folder {folder}  Tags {tags}").fg(theme.unfocused)));
+
         frame.render_widget(
-            Paragraph::new(temp_name.as_str().bold())
+            Paragraph::new(lines)
                 .alignment(Alignment::Center),
             title_content[0],
         );
@@ -196,21 +609,21 @@ fn render_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
             }
 
             // set color and style with responsive copy
-            let mut color = Color::DarkGray;
+            let mut color = theme.disabled;
             let mut border_type = BorderType::Rounded;
 
             if app.page_selected {
                 if index == entries.current_index().unwrap() {
-                    color = Color::White;
+                    color = theme.text;
                     border_type = BorderType::Thick;
 
                     if let Some(copied) = app.get_copied_state() {
                         if copied == index {
-                            color = Color::LightBlue;
+                            color = theme.highlight;
                         }
                     }
                 } else {
-                    color = Color::Gray;
+                    color = theme.unfocused;
                 }
             }
 
@@ -223,8 +636,12 @@ fn render_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
             // apply theme if copied field
             if let Some(copied) = app.get_copied_state() {
                 if copied == index {
+                    let title = match seconds_left(app) {
+                        Some(secs) => format!("Copied to clipboard! {secs}s"),
+                        None => "Copied to clipboard!".to_string(),
+                    };
                     p_block = p_block.title(
-                        Title::from("Copied to clipboard!")
+                        Title::from(title)
                             .position(Position::Top)
                             .alignment(Alignment::Center),
                     );
@@ -232,10 +649,10 @@ fn render_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
             }
 
             // display private credentials hidden until switched
-            let private_text: String = if entry.2 {
-                "\u{2022}".repeat(entry.1.len())
+            let private_text: Cow<str> = if entry.2 {
+                Cow::Owned("\u{2022}".repeat(entry.1.chars().count()))
             } else {
-                entry.1.to_string()
+                Cow::Borrowed(entry.1.as_str())
             };
 
             frame.render_widget(
@@ -247,21 +664,21 @@ fn render_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
         }
 
         // create a delete button and theme it
-        let mut color = Color::DarkGray;
+        let mut color = theme.disabled;
         let mut border_type = BorderType::Rounded;
 
         if app.page_selected {
             if entries.items.len() - 1 == entries.current_index().unwrap() {
-                color = Color::LightRed;
+                color = theme.danger;
                 border_type = BorderType::Thick;
             } else {
-                color = Color::Red;
+                color = theme.danger_dim;
             }
         }
         let text = if app.delete_confirm {
-            "Confirm Delete".bold()
+            "Confirm Move to Trash".bold()
         } else {
-            "Delete Entry".not_bold()
+            "Move to Trash".not_bold()
         };
 
         frame.render_widget(
@@ -278,6 +695,7 @@ fn render_credentials(frame: &mut Frame, app: &mut App, area: Rect) {
 
 fn page_new_entry(frame: &mut Frame, app: &mut App, area: Rect) {
     // split view of templates
+    let theme = app.config.theme;
     let lists_layout = Layout::new(
         Direction::Horizontal,
         [Constraint::Percentage(50), Constraint::Percentage(50)],
@@ -286,9 +704,9 @@ fn page_new_entry(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // create items to be displayed
     let color = if app.page_selected {
-        Color::DarkGray
+        theme.disabled
     } else {
-        Color::Yellow
+        theme.item
     };
 
     let items: Vec<ListItem> = app
@@ -300,9 +718,9 @@ fn page_new_entry(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // create a list from all list items and highlight the currently selected one
     let color_border = if app.page_selected {
-        Color::DarkGray
+        theme.disabled
     } else {
-        Color::White
+        theme.text
     };
     let items = List::new(items)
         .block(
@@ -313,7 +731,7 @@ fn page_new_entry(frame: &mut Frame, app: &mut App, area: Rect) {
         )
         .highlight_style(
             Style::default()
-                .fg(Color::LightYellow)
+                .fg(theme.selected)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol(" ❱ ");
@@ -333,10 +751,16 @@ fn page_new_entry(frame: &mut Frame, app: &mut App, area: Rect) {
             lists_layout[1],
         );
     }
+
+    // password generator of a private field on top
+    if app.generator.is_some() {
+        render_generator(frame, app, lists_layout[1]);
+    }
 }
 
 fn display_template(frame: &mut Frame, app: &mut App, area: Rect) {
     // function for rendering input fields of selected template
+    let theme = app.config.theme;
     if let Some(template) = app.templates.items.get(app.current_template.unwrap_or(0)) {
         let mut fields = vec![Constraint::Length(4); template.elements.len()];
         fields.push(Constraint::Min(0));
@@ -358,14 +782,14 @@ fn display_template(frame: &mut Frame, app: &mut App, area: Rect) {
 
             // apply theme
             if i == highlight_index && app.page_selected {
-                field_active(current);
+                field_active(current, &theme);
             } else {
-                field_inactive(current, app.page_selected);
+                field_inactive(current, app.page_selected, &theme);
             }
 
             // exception for name field
             if i == 0 && !app.insert_success.unwrap_or(true) {
-                let block = set_border_color(current, Color::LightYellow);
+                let block = set_border_color(current, theme.selected);
                 current.set_block(block);
             }
 
@@ -377,7 +801,7 @@ fn display_template(frame: &mut Frame, app: &mut App, area: Rect) {
         let last_index = items.len() - 1;
         let confirm_button = items.last_mut().unwrap();
 
-        let mut color = Color::DarkGray;
+        let mut color = theme.disabled;
         let mut border_type = BorderType::Rounded;
 
         if app.page_selected {
@@ -385,15 +809,15 @@ fn display_template(frame: &mut Frame, app: &mut App, area: Rect) {
             if all_filled && app.insert_success.unwrap_or(all_filled) {
                 if highlight_index == last_index {
                     border_type = BorderType::Thick;
-                    color = Color::LightGreen;
+                    color = theme.success;
                 } else {
-                    color = Color::Green;
+                    color = theme.success_dim;
                 }
             } else if highlight_index == last_index {
                 border_type = BorderType::Thick;
-                color = Color::LightRed;
+                color = theme.danger;
             } else {
-                color = Color::Red;
+                color = theme.danger_dim;
             }
         }
 
@@ -405,9 +829,304 @@ fn display_template(frame: &mut Frame, app: &mut App, area: Rect) {
     }
 }
 
-fn page_template_creator(frame: &mut Frame, _app: &mut App, area: Rect) {
-    // placeholder for last page
-    frame.render_widget(Paragraph::new("In progress..."), area);
+fn page_templates(frame: &mut Frame, app: &mut App, area: Rect) {
+    // templates on the left, fields and protection of the highlighted one on the right
+    let theme = app.config.theme;
+    let lists_layout = Layout::new(
+        Direction::Horizontal,
+        [Constraint::Percentage(50), Constraint::Percentage(50)],
+    )
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .templates
+        .items
+        .iter()
+        .map(|t| ListItem::new(t.name.clone()).style(Style::default().fg(theme.item)))
+        .collect();
+
+    let items = List::new(items)
+        .block(Block::new().borders(Borders::ALL).fg(theme.text).title("Templates"))
+        .highlight_style(
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(" ❱ ");
+    frame.render_stateful_widget(items, lists_layout[0], &mut app.templates.state);
+
+    let Some(template) = app.templates.current_item() else {
+        frame.render_widget(
+            Paragraph::new("Select a template to display").block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .padding(Padding::uniform(1)),
+            ),
+            lists_layout[1],
+        );
+        return;
+    };
+
+    // fields without the name, marked private and by the role they are copied from the list as
+    let mut lines: Vec<Line> = template.elements[1..]
+        .iter()
+        .map(|element| {
+            let mut line = vec![format!("{}: ", element.name).fg(theme.item)];
+            line.push(if element.private { "private" } else { "public" }.fg(theme.text));
+            if let Some(role) = element.role {
+                line.push(format!(", copied as {}", role.name()).fg(theme.unfocused));
+            }
+            Line::from(line)
+        })
+        .collect();
+
+    let (protection, color) = match template.reauthenticate {
+        true => ("required", theme.success),
+        false => ("not required", theme.disabled),
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        "Master password before revealing or copying: ".fg(theme.item),
+        protection.fg(color).bold(),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(
+        "Press Enter to change it, turning it off asks for the master password.".fg(theme.unfocused),
+    ));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .padding(Padding::uniform(1)),
+            ),
+        lists_layout[1],
+    );
+
+    if app.reauth_prompt.is_some() {
+        render_reauth_prompt(frame, app, lists_layout[1]);
+    }
+}
+
+fn page_vault(frame: &mut Frame, app: &mut App, area: Rect) {
+    // maintenance actions on the left, details and outcome on the right
+    let theme = app.config.theme;
+    let lists_layout = Layout::new(
+        Direction::Horizontal,
+        [Constraint::Percentage(50), Constraint::Percentage(50)],
+    )
+        .split(area);
+
+    let (entry_color, border_color) = if app.page_selected {
+        (theme.disabled, theme.disabled)
+    } else {
+        (theme.item, theme.text)
+    };
+
+    let items: Vec<ListItem> = app
+        .vault_actions
+        .items
+        .iter()
+        .map(|a| ListItem::new(a.name()).style(Style::default().fg(entry_color)))
+        .collect();
+
+    let items = List::new(items)
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .fg(border_color)
+                .title("Actions"),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(" ❱ ");
+
+    frame.render_stateful_widget(items, lists_layout[0], &mut app.vault_actions.state);
+
+    // show backups while restoring or the settings while changing them,
+    // else description and result of the last run
+    if app.page_selected {
+        match app.vault_actions.current_item() {
+            Some(VaultAction::Settings) => render_settings(frame, app, lists_layout[1]),
+            _ => render_backups(frame, app, lists_layout[1]),
+        }
+        return;
+    }
+
+    let lines: Vec<Line> = app
+        .vault_actions
+        .current_item()
+        .map(|a| a.description(&app.config.keys))
+        .unwrap_or_default()
+        .lines()
+        .map(|line| Line::from(line.to_string()))
+        .chain(status_lines(app))
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .padding(Padding::uniform(1)),
+            ),
+        lists_layout[1],
+    );
+}
+
+fn page_audit(frame: &mut Frame, app: &mut App, area: Rect) {
+    // records of the audit trail with search, filter help and export status below
+    let theme = app.config.theme;
+    let audit_layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Min(3), Constraint::Length(3), Constraint::Length(3)],
+    )
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .audit_list
+        .items
+        .iter()
+        .map(|r| {
+            let mut line = vec![
+                format!("{}  ", r.local_time()).fg(theme.unfocused),
+                format!("{:<16}", r.action).fg(theme.item),
+            ];
+            if let Some(entry) = &r.entry {
+                line.push(entry.clone().fg(theme.text).bold());
+            }
+            if let Some(detail) = &r.detail {
+                line.push(format!("  {detail}").fg(theme.disabled));
+            }
+            ListItem::new(Line::from(line))
+        })
+        .collect();
+
+    let title = match app.audit_filter {
+        Some(action) => format!("Audit Trail: {}", action.name()),
+        None => "Audit Trail: all actions".to_string(),
+    };
+    let list = List::new(items)
+        .block(Block::new().borders(Borders::ALL).fg(theme.text).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(" ❱ ");
+    frame.render_stateful_widget(list, audit_layout[0], &mut app.audit_list.state);
+
+    // search field
+    let search_bar = &mut app.text_fields.audit_search;
+    search_bar.set_placeholder_text("Type to search entries and details");
+    search_bar.set_block(
+        Block::new()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .fg(theme.text)
+            .title("Search"),
+    );
+
+    if !search_bar.is_empty() {
+        let block = set_border_color(search_bar, theme.highlight);
+        search_bar.set_block(block);
+    }
+
+    frame.render_widget(search_bar.widget(), audit_layout[1]);
+
+    let help = format!(
+        "Press Left or Right to show a single action and CTRL+{} to export the shown records.",
+        app.config.keys.export.to_ascii_uppercase(),
+    );
+    let mut lines = vec![Line::from(help)];
+    if let Some(status) = &app.audit_status {
+        lines.push(match status {
+            Ok(message) => message.clone().fg(theme.success).into(),
+            Err(message) => message.clone().fg(theme.danger).into(),
+        });
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .padding(Padding::horizontal(1)),
+            ),
+        audit_layout[2],
+    );
+}
+
+fn page_trash(frame: &mut Frame, app: &mut App, area: Rect) {
+    // trashed entries with help and the outcome of the last action below
+    let theme = app.config.theme;
+    let trash_layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Min(3), Constraint::Length(4)],
+    )
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .trash_list
+        .items
+        .iter()
+        .map(|item| {
+            ListItem::new(Line::from(vec![
+                format!("{}  ", item.deleted_local()).fg(theme.unfocused),
+                item.name.clone().fg(theme.text).bold(),
+                format!("  {}", app.trash_template_name(item)).fg(theme.disabled),
+            ]))
+        })
+        .collect();
+
+    let title = match app.vault_settings.trash_days {
+        0 => "Trash".to_string(),
+        days => format!("Trash: purged after {days} days"),
+    };
+    let block = Block::new().borders(Borders::ALL).fg(theme.text).title(title);
+
+    if items.is_empty() {
+        frame.render_widget(
+            Paragraph::new("The trash is empty".fg(theme.disabled)).block(block.padding(Padding::horizontal(1))),
+            trash_layout[0],
+        );
+    } else {
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol(" ❱ ");
+        frame.render_stateful_widget(list, trash_layout[0], &mut app.trash_list.state);
+    }
+
+    let purge_key = app.config.keys.purge.to_ascii_uppercase();
+    let help = format!(
+        "Press Enter to restore the selected entry, it gets renamed if its name is taken. \
+        Press {purge_key} twice to delete it permanently."
+    );
+    let mut lines = vec![Line::from(help)];
+    if app.purge_confirm {
+        lines.push(format!("Press {purge_key} again to purge the entry").fg(theme.danger).into());
+    } else if let Some(status) = &app.trash_status {
+        lines.push(match status {
+            Ok(message) => message.clone().fg(theme.success).into(),
+            Err(message) => message.clone().fg(theme.danger).into(),
+        });
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .padding(Padding::horizontal(1)),
+            ),
+        trash_layout[1],
+    );
 }
 
 fn set_border_color<'a>(text_field: &TextArea<'a>, color: Color) -> Block<'a> {
@@ -419,14 +1138,14 @@ fn set_border_color<'a>(text_field: &TextArea<'a>, color: Color) -> Block<'a> {
         .style(Style::default().fg(color))
 }
 
-fn field_active(text_field: &mut TextArea<'_>) {
+fn field_active(text_field: &mut TextArea<'_>, theme: &Theme) {
     // sets theme to active
     text_field.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
 
     // set color to red if empty
-    let mut color = Color::White;
+    let mut color = theme.text;
     if text_field.is_empty() {
-        color = Color::LightRed;
+        color = theme.danger;
     }
 
     let block = set_border_color(text_field, color).border_type(BorderType::Thick);
@@ -434,20 +1153,230 @@ fn field_active(text_field: &mut TextArea<'_>) {
     text_field.set_block(block);
 }
 
-fn field_inactive(text_field: &mut TextArea<'_>, can_select: bool) {
+fn field_inactive(text_field: &mut TextArea<'_>, can_select: bool, theme: &Theme) {
     // modifies block to look inactive
     text_field.set_cursor_line_style(Style::default());
     text_field.set_cursor_style(Style::default());
 
-    let mut color = Color::DarkGray;
+    let mut color = theme.disabled;
     if can_select {
         if text_field.is_empty() {
-            color = Color::Red;
+            color = theme.danger_dim;
         } else {
-            color = Color::Gray;
+            color = theme.unfocused;
         }
     }
 
     let block = set_border_color(text_field, color).border_type(BorderType::Rounded);
     text_field.set_block(block);
 }
+
+fn render_backups(frame: &mut Frame, app: &mut App, area: Rect) {
+    // list of backups or the entries of an opened one with help and status below
+    let theme = app.config.theme;
+    let backup_layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Min(3), Constraint::Length(8)],
+    )
+        .split(area);
+
+    let highlight = Style::default()
+        .fg(theme.selected)
+        .add_modifier(Modifier::BOLD);
+
+    let help = if let Some(entries) = app.backup_entries.as_mut() {
+        let title = format!("Entries of {}", app.backups.current_item().map(|b| b.name()).unwrap_or_default());
+        let items: Vec<ListItem> = entries
+            .items
+            .iter()
+            .map(|e| ListItem::new(e.as_str()).style(Style::default().fg(theme.item)))
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::new().borders(Borders::ALL).fg(theme.text).title(title))
+            .highlight_style(highlight)
+            .highlight_symbol(" ❱ ");
+        frame.render_stateful_widget(list, backup_layout[0], &mut entries.state);
+
+        let restore_key = app.config.keys.restore_vault.to_ascii_uppercase();
+        if app.restore_confirm {
+            format!("Press {restore_key} again to replace the whole vault with this backup. The current state is backed up first.")
+        } else {
+            format!("Press Enter to restore the selected entry, {restore_key} to restore the whole vault and Esc to go back.")
+        }
+    } else {
+        let items: Vec<ListItem> = app
+            .backups
+            .items
+            .iter()
+            .map(|b| ListItem::new(b.name()).style(Style::default().fg(theme.item)))
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::new().borders(Borders::ALL).fg(theme.text).title("Backups"))
+            .highlight_style(highlight)
+            .highlight_symbol(" ❱ ");
+        frame.render_stateful_widget(list, backup_layout[0], &mut app.backups.state);
+
+        "Press Enter to open the selected backup read-only and Esc to go back.".to_string()
+    };
+
+    let lines: Vec<Line> = std::iter::once(Line::from(help))
+        .chain(status_lines(app))
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .padding(Padding::horizontal(1)),
+            ),
+        backup_layout[1],
+    );
+}
+
+fn render_settings(frame: &mut Frame, app: &mut App, area: Rect) {
+    // settings stored in the vault with help and status below
+    let theme = app.config.theme;
+    let settings_layout = Layout::new(
+        Direction::Vertical,
+        [Constraint::Min(3), Constraint::Length(6)],
+    )
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .settings_list
+        .items
+        .iter()
+        .map(|s| {
+            ListItem::new(Line::from(vec![
+                format!("{}: ", s.name()).fg(theme.item),
+                s.value(&app.vault_settings).fg(theme.text).bold(),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::new().borders(Borders::ALL).fg(theme.text).title("Vault Settings"))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(" ❱ ");
+    frame.render_stateful_widget(list, settings_layout[0], &mut app.settings_list.state);
+
+    let help = "Press Left or Right to change the selected setting and Esc to go back. \
+        Changes are saved in the vault right away.";
+    let lines: Vec<Line> = std::iter::once(Line::from(help))
+        .chain(status_lines(app))
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::NONE)
+                    .padding(Padding::horizontal(1)),
+            ),
+        settings_layout[1],
+    );
+}
+
+fn status_lines(app: &App) -> Vec<Line<'static>> {
+    // result of the last vault action, colored by its outcome
+    let theme = app.config.theme;
+    let Some(status) = &app.vault_status else {
+        return vec![];
+    };
+
+    let (message, color) = match status {
+        Ok(message) => (message, theme.success),
+        Err(message) => (message, theme.danger),
+    };
+
+    std::iter::once(Line::from(""))
+        .chain(message.lines().map(|l| l.to_string().fg(color).into()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn runs(text: &str, positions: &[usize]) -> Vec<(String, bool)> {
+        // the text of each span and whether it is drawn as a match
+        let theme = Config::default().theme;
+        highlight_matches(text, positions, theme)
+            .spans
+            .into_iter()
+            .map(|span| (span.content.to_string(), span.style.fg == Some(theme.selected)))
+            .collect()
+    }
+
+    #[test]
+    fn highlights_runs_of_characters() {
+        assert_eq!(
+            runs("github", &[0, 1, 4]),
+            vec![("gi".to_string(), true), ("th".to_string(), false), ("u".to_string(), true), ("b".to_string(), false)]
+        );
+        assert_eq!(runs("mail", &[]), vec![("mail".to_string(), false)]);
+    }
+
+    #[test]
+    fn highlights_multi_byte_names_by_character() {
+        assert_eq!(
+            runs("Bäckerei 日本", &[1, 9]),
+            vec![
+                ("B".to_string(), false),
+                ("ä".to_string(), true),
+                ("ckerei ".to_string(), false),
+                ("日".to_string(), true),
+                ("本".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_combining_marks_with_their_character() {
+        // "Café" with a decomposed é, the accent follows the e whether it matched or not
+        assert_eq!(
+            runs("Cafe\u{301}s", &[3]),
+            vec![("Caf".to_string(), false), ("e\u{301}".to_string(), true), ("s".to_string(), false)]
+        );
+        assert_eq!(
+            runs("Cafe\u{301}s", &[4, 5]),
+            vec![("Cafe\u{301}".to_string(), false), ("s".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn offset_of_an_empty_list_is_zero() {
+        assert_eq!(visible_offset(None, 0, 10, 0), 0);
+        assert_eq!(visible_offset(None, 7, 10, 0), 0);
+        assert_eq!(visible_offset(Some(0), 3, 0, 0), 0);
+    }
+
+    #[test]
+    fn offset_follows_the_selection_only_as_far_as_needed() {
+        // inside the window nothing moves
+        assert_eq!(visible_offset(Some(12), 10, 5, 30), 10);
+        // above the window it becomes the first row
+        assert_eq!(visible_offset(Some(3), 10, 5, 30), 3);
+        // below the window it becomes the last row
+        assert_eq!(visible_offset(Some(20), 10, 5, 30), 16);
+        assert_eq!(visible_offset(Some(29), 0, 5, 30), 25);
+    }
+
+    #[test]
+    fn offset_adapts_to_shrinking_height_and_list() {
+        // a smaller window still shows the selection at its bottom
+        assert_eq!(visible_offset(Some(14), 10, 5, 30), 10);
+        assert_eq!(visible_offset(Some(14), 10, 2, 30), 13);
+        // a list shorter than the old offset scrolls back so no rows stay empty
+        assert_eq!(visible_offset(None, 20, 5, 8), 3);
+        assert_eq!(visible_offset(Some(7), 20, 5, 8), 3);
+        // a window without rows keeps a usable offset
+        assert_eq!(visible_offset(Some(4), 2, 0, 8), 2);
+    }
+}